@@ -0,0 +1,382 @@
+use anyhow::{Context, Result, bail};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use super::limits::{
+    CpuKnob, DEFAULT_CPU_PERIOD_USEC, LimitValue, MemoryKnob, format_cpu_max, parse_memory_value,
+    parse_pids_max, read_counter, read_cpu_max, read_limit,
+};
+use super::read_controller_list;
+use crate::audit::{AuditEntry, AuditLog};
+
+/// Key of the list of controllers to enable for children
+pub const SUBTREE_CONTROL: &str = "cgroup.subtree_control";
+
+/// Control files a spec may set
+pub const SPEC_FILES: &[&str] = &[
+    "memory.max",
+    "memory.high",
+    "memory.low",
+    "memory.min",
+    "memory.swap.max",
+    "cpu.max",
+    "cpu.weight",
+    "cpu.max.burst",
+    "pids.max",
+];
+
+/// The kernel keeps memory limits in whole pages
+const PAGE_SIZE: u64 = 4096;
+
+/// Desired settings for the cgroups matching one glob pattern
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpecEntry {
+    /// Glob relative to the cgroup root
+    pub pattern: String,
+    /// Control file and value as written by the user (`2G`, `1.5`, `max`)
+    pub settings: Vec<(String, String)>,
+    /// Controllers that must be enabled in `cgroup.subtree_control`
+    pub controllers: Vec<String>,
+}
+
+/// A limits profile read from a spec file
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Spec {
+    pub entries: Vec<SpecEntry>,
+}
+
+impl Spec {
+    /// Load a TOML spec, or a YAML one if the file ends in `.yaml`/`.yml`
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("failed to read spec {}", path.display()))?;
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("yaml" | "yml") => Self::parse_yaml(&content),
+            _ => Self::parse_toml(&content),
+        }
+        .with_context(|| format!("invalid spec {}", path.display()))
+    }
+
+    /// Parse a spec of the form
+    ///
+    /// ```toml
+    /// ["batch.slice/*.scope"]
+    /// "memory.max" = "2G"
+    /// "cpu.max" = "1.5"
+    /// "cgroup.subtree_control" = ["cpu", "memory"]
+    /// ```
+    pub fn parse_toml(content: &str) -> Result<Self> {
+        Self::from_table(toml::from_str(content)?)
+    }
+
+    /// Parse the YAML form of the same spec
+    ///
+    /// ```yaml
+    /// batch.slice/*.scope:
+    ///   memory.max: 2G
+    ///   cpu.max: 1.5
+    ///   cgroup.subtree_control: [cpu, memory]
+    /// ```
+    pub fn parse_yaml(content: &str) -> Result<Self> {
+        // Read into the same table as TOML; mapping order is kept
+        Self::from_table(serde_yaml::from_str(content)?)
+    }
+
+    fn from_table(table: toml::Table) -> Result<Self> {
+        let mut entries = Vec::new();
+
+        for (pattern, settings) in table {
+            let toml::Value::Table(settings) = settings else {
+                bail!("[{:?}] must be a table of control files", pattern);
+            };
+            glob::Pattern::new(pattern.trim_matches('/'))
+                .with_context(|| format!("invalid cgroup pattern {:?}", pattern))?;
+
+            let mut entry = SpecEntry {
+                pattern: pattern.trim_matches('/').to_string(),
+                settings: Vec::new(),
+                controllers: Vec::new(),
+            };
+            for (file, value) in settings {
+                match (file.as_str(), value) {
+                    (SUBTREE_CONTROL, toml::Value::Array(controllers)) => {
+                        for controller in controllers {
+                            let toml::Value::String(name) = controller else {
+                                bail!("{}: {} takes controller names", pattern, SUBTREE_CONTROL);
+                            };
+                            entry.controllers.push(name);
+                        }
+                    }
+                    (file, _) if !SPEC_FILES.contains(&file) => bail!(
+                        "{}: unsupported file {:?} (expected one of {} or {})",
+                        pattern,
+                        file,
+                        SPEC_FILES.join(", "),
+                        SUBTREE_CONTROL
+                    ),
+                    (file, toml::Value::String(value)) => {
+                        entry.settings.push((file.to_string(), value))
+                    }
+                    (file, toml::Value::Integer(value)) => {
+                        entry.settings.push((file.to_string(), value.to_string()))
+                    }
+                    // Unquoted `cpu.max: 1.5` in YAML
+                    (file, toml::Value::Float(value)) => {
+                        entry.settings.push((file.to_string(), value.to_string()))
+                    }
+                    (file, value) => bail!(
+                        "{}: {} must be a string or a number, not {}",
+                        pattern,
+                        file,
+                        value.type_str()
+                    ),
+                }
+            }
+            entries.push(entry);
+        }
+
+        Ok(Self { entries })
+    }
+}
+
+/// One write needed to bring a cgroup in line with the spec
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    pub cgroup: PathBuf,
+    pub file: String,
+    /// Live content, `None` if the file is missing
+    pub current: Option<String>,
+    /// Exactly what gets written
+    pub value: String,
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "~ {} {}: {} -> {}",
+            self.cgroup.display(),
+            self.file,
+            self.current.as_deref().unwrap_or("(missing)"),
+            self.value
+        )
+    }
+}
+
+/// Differences between a spec and the live hierarchy
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Plan {
+    /// Parents come before their children, controller changes before limits
+    pub changes: Vec<Change>,
+    /// Settings that already match
+    pub unchanged: usize,
+    /// Patterns that matched no cgroup
+    pub unmatched: Vec<String>,
+}
+
+impl Plan {
+    pub fn has_drift(&self) -> bool {
+        !self.changes.is_empty()
+    }
+}
+
+/// Settings a single cgroup ends up with after merging every matching entry
+#[derive(Default)]
+struct Desired<'a> {
+    controllers: Vec<&'a str>,
+    settings: Vec<(&'a str, &'a str)>,
+}
+
+/// Compare `spec` with the cgroups below `root`
+pub fn plan(spec: &Spec, root: &Path) -> Result<Plan> {
+    // Ordered by path, so parents are handled before their children
+    let mut targets: BTreeMap<PathBuf, Desired> = BTreeMap::new();
+    let mut plan = Plan::default();
+
+    for entry in &spec.entries {
+        let cgroups = matching_cgroups(root, &entry.pattern)?;
+        if cgroups.is_empty() {
+            plan.unmatched.push(entry.pattern.clone());
+        }
+
+        for cgroup in cgroups {
+            let desired = targets.entry(cgroup).or_default();
+            for controller in &entry.controllers {
+                if !desired.controllers.contains(&controller.as_str()) {
+                    desired.controllers.push(controller);
+                }
+            }
+            // A later entry overrides an earlier one for the same file
+            for (file, input) in &entry.settings {
+                match desired
+                    .settings
+                    .iter_mut()
+                    .find(|(existing, _)| existing == file)
+                {
+                    Some(setting) => setting.1 = input,
+                    None => desired.settings.push((file, input)),
+                }
+            }
+        }
+    }
+
+    // Memory limits the plan sets, so a child's percentage refers to the
+    // parent's new limit rather than the one about to be replaced
+    let mut planned: HashMap<(PathBuf, &str), LimitValue> = HashMap::new();
+
+    for (cgroup, desired) in targets {
+        let enabled = read_controller_list(&cgroup, SUBTREE_CONTROL);
+        for controller in desired.controllers {
+            if enabled.iter().any(|name| name == controller) {
+                plan.unchanged += 1;
+            } else {
+                plan.changes.push(Change {
+                    cgroup: cgroup.clone(),
+                    file: SUBTREE_CONTROL.to_string(),
+                    current: Some(enabled.join(" ")),
+                    value: format!("+{}", controller),
+                });
+            }
+        }
+
+        for (file, input) in desired.settings {
+            let value = kernel_value(&cgroup, root, file, input, &planned)
+                .with_context(|| format!("{}: {} = {:?}", cgroup.display(), file, input))?;
+            if file.starts_with("memory.")
+                && let Some(limit) = LimitValue::from_file_content(&value)
+            {
+                planned.insert((cgroup.clone(), file), limit);
+            }
+            let current = read_current(&cgroup, file);
+
+            if current
+                .as_deref()
+                .is_some_and(|current| same_value(file, current, &value))
+            {
+                plan.unchanged += 1;
+            } else {
+                plan.changes.push(Change {
+                    cgroup: cgroup.clone(),
+                    file: file.to_string(),
+                    current,
+                    value,
+                });
+            }
+        }
+    }
+
+    Ok(plan)
+}
+
+/// Directories below `root` matching `pattern`
+fn matching_cgroups(root: &Path, pattern: &str) -> Result<Vec<PathBuf>> {
+    let root_pattern = glob::Pattern::escape(&root.to_string_lossy());
+    let full = if pattern.is_empty() {
+        root_pattern
+    } else {
+        format!("{}/{}", root_pattern, pattern)
+    };
+
+    let mut cgroups: Vec<PathBuf> = glob::glob(&full)
+        .with_context(|| format!("invalid cgroup pattern {:?}", pattern))?
+        .filter_map(|path| path.ok())
+        .filter(|path| path.is_dir())
+        .collect();
+    cgroups.sort();
+    Ok(cgroups)
+}
+
+/// Turn a spec value into the string the kernel expects in `file`
+fn kernel_value(
+    cgroup: &Path,
+    root: &Path,
+    file: &str,
+    input: &str,
+    planned: &HashMap<(PathBuf, &str), LimitValue>,
+) -> Result<String> {
+    if let Some(knob) = MemoryKnob::ALL.iter().find(|knob| knob.file_name() == file) {
+        let parent_limit = cgroup
+            .parent()
+            .and_then(|parent| planned_limit(parent, root, knob.limit_file(), planned));
+        return parse_memory_value(input, parent_limit).map(|value| value.to_string());
+    }
+    if let Some(knob) = CpuKnob::ALL.iter().find(|knob| knob.file_name() == file) {
+        let period = read_cpu_max(cgroup).map_or(DEFAULT_CPU_PERIOD_USEC, |(_, period)| period);
+        return knob.parse(input, period);
+    }
+    if file == "pids.max" {
+        return parse_pids_max(input).map(|value| value.to_string());
+    }
+    bail!("unsupported file {}", file)
+}
+
+/// Like `effective_limit`, but with the limits the plan sets taking the place
+/// of the live ones
+fn planned_limit(
+    cgroup: &Path,
+    root: &Path,
+    file: &str,
+    planned: &HashMap<(PathBuf, &str), LimitValue>,
+) -> Option<u64> {
+    cgroup
+        .ancestors()
+        .take_while(|ancestor| ancestor.starts_with(root))
+        .filter_map(|ancestor| {
+            planned
+                .get(&(ancestor.to_path_buf(), file))
+                .copied()
+                .or_else(|| read_limit(ancestor, file))?
+                .value()
+        })
+        .min()
+}
+
+/// Live value of `file` in the form `kernel_value` produces, `None` if the
+/// file is missing or unreadable
+fn read_current(cgroup: &Path, file: &str) -> Option<String> {
+    match file {
+        "cpu.max" => read_cpu_max(cgroup).map(|(quota, period)| format_cpu_max(quota, period)),
+        "cpu.weight" | "cpu.max.burst" => read_counter(cgroup, file).map(|value| value.to_string()),
+        _ => read_limit(cgroup, file).map(|value| value.to_string()),
+    }
+}
+
+/// Whether the live `current` content already satisfies `desired`
+fn same_value(file: &str, current: &str, desired: &str) -> bool {
+    if file.starts_with("memory.") {
+        // The kernel rounds memory limits down to whole pages
+        let pages = |content: &str| {
+            LimitValue::from_file_content(content).map(|value| value.value().map(|v| v / PAGE_SIZE))
+        };
+        return pages(current).is_some() && pages(current) == pages(desired);
+    }
+    current == desired
+}
+
+/// Write one change, recording it in `audit`
+pub fn apply_change(change: &Change, audit: &mut AuditLog) -> io::Result<()> {
+    let target = change.cgroup.join(&change.file);
+    let old = fs::read_to_string(&target).ok();
+    let result = fs::write(&target, &change.value);
+
+    audit.record(AuditEntry::new(
+        &change.cgroup,
+        &change.file,
+        old,
+        &change.value,
+        result.as_ref().map_err(ToString::to_string).copied(),
+    ));
+    match &result {
+        Ok(()) => log::info!("Wrote {:?} to {}", change.value, target.display()),
+        Err(e) => log::error!(
+            "Failed to write {:?} to {}: {}",
+            change.value,
+            target.display(),
+            e
+        ),
+    }
+    result
+}
@@ -0,0 +1,57 @@
+use anyhow::{Context, Result};
+use std::ffi::OsStr;
+use std::fs::OpenOptions;
+use std::io;
+use std::os::unix::process::CommandExt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Shell used when `$SHELL` is unset
+pub const DEFAULT_SHELL: &str = "/bin/sh";
+
+/// Resolve a `--cgroup` argument: absolute paths under `root` are kept, anything else is taken
+/// relative to `root`
+pub fn resolve_cgroup(root: &Path, cgroup: &Path) -> PathBuf {
+    if cgroup.starts_with(root) {
+        cgroup.to_path_buf()
+    } else {
+        root.join(cgroup.strip_prefix("/").unwrap_or(cgroup))
+    }
+}
+
+/// A command that joins `cgroup` between fork and exec, so it never runs anywhere else
+pub fn command_in_cgroup<P: AsRef<OsStr>, A: AsRef<OsStr>>(
+    cgroup: &Path,
+    program: P,
+    args: &[A],
+) -> Result<Command> {
+    let procs_path = cgroup.join("cgroup.procs");
+    // Opened up front: the child must not allocate between fork and exec. The descriptor is
+    // close-on-exec, so the program never sees it.
+    let procs = OpenOptions::new()
+        .write(true)
+        .open(&procs_path)
+        .with_context(|| format!("cannot open {}", procs_path.display()))?;
+
+    let mut command = Command::new(program);
+    command.args(args);
+    // SAFETY: the hook only issues a single write(2) on an already open descriptor, which is
+    // async-signal-safe
+    unsafe {
+        command.pre_exec(move || {
+            // Writing "0" moves the writing process itself
+            nix::unistd::write(&procs, b"0")
+                .map(|_| ())
+                .map_err(io::Error::from)
+        });
+    }
+    Ok(command)
+}
+
+/// The user's login shell, started inside `cgroup`
+pub fn shell_in_cgroup(cgroup: &Path) -> Result<Command> {
+    let shell = std::env::var_os("SHELL")
+        .filter(|shell| !shell.is_empty())
+        .unwrap_or_else(|| DEFAULT_SHELL.into());
+    command_in_cgroup::<_, &OsStr>(cgroup, shell, &[])
+}
@@ -0,0 +1,465 @@
+use anyhow::{Result, bail};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+/// Value of a `*.max`-style control file: a number or the literal `max`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LimitValue {
+    Value(u64),
+    Max,
+}
+
+impl LimitValue {
+    /// Parse the content of a control file
+    pub fn from_file_content(content: &str) -> Option<Self> {
+        match content.trim() {
+            "max" => Some(LimitValue::Max),
+            value => value.parse().ok().map(LimitValue::Value),
+        }
+    }
+
+    pub fn value(self) -> Option<u64> {
+        match self {
+            LimitValue::Value(value) => Some(value),
+            LimitValue::Max => None,
+        }
+    }
+}
+
+/// Formats the value the way the kernel expects it to be written
+impl fmt::Display for LimitValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LimitValue::Value(value) => write!(f, "{}", value),
+            LimitValue::Max => write!(f, "max"),
+        }
+    }
+}
+
+/// Memory control files that can be edited
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemoryKnob {
+    Max,
+    High,
+    Low,
+    Min,
+    SwapMax,
+}
+
+impl MemoryKnob {
+    pub const ALL: [MemoryKnob; 5] = [
+        MemoryKnob::Max,
+        MemoryKnob::High,
+        MemoryKnob::Low,
+        MemoryKnob::Min,
+        MemoryKnob::SwapMax,
+    ];
+
+    pub fn file_name(self) -> &'static str {
+        match self {
+            MemoryKnob::Max => "memory.max",
+            MemoryKnob::High => "memory.high",
+            MemoryKnob::Low => "memory.low",
+            MemoryKnob::Min => "memory.min",
+            MemoryKnob::SwapMax => "memory.swap.max",
+        }
+    }
+
+    /// File holding the ancestor limit this knob is checked against
+    pub fn limit_file(self) -> &'static str {
+        match self {
+            MemoryKnob::SwapMax => "memory.swap.max",
+            _ => "memory.max",
+        }
+    }
+
+    /// File with the usage this knob applies to
+    pub fn usage_file(self) -> &'static str {
+        match self {
+            MemoryKnob::SwapMax => "memory.swap.current",
+            _ => "memory.current",
+        }
+    }
+}
+
+/// Size suffixes and the power of two they multiply by, matched case-insensitively
+const SIZE_SUFFIXES: &[(&str, u32)] = &[
+    ("KIB", 10),
+    ("KB", 10),
+    ("K", 10),
+    ("MIB", 20),
+    ("MB", 20),
+    ("M", 20),
+    ("GIB", 30),
+    ("GB", 30),
+    ("G", 30),
+    ("TIB", 40),
+    ("TB", 40),
+    ("T", 40),
+    ("B", 0),
+];
+
+/// Parse a memory size typed by the user.
+///
+/// Accepts `max`, plain bytes, binary suffixes (`512K`, `1.5G`, `2GiB`) and
+/// percentages of `parent_limit` (`80%`).
+pub fn parse_memory_value(input: &str, parent_limit: Option<u64>) -> Result<LimitValue> {
+    let input = input.trim();
+    if input.is_empty() {
+        bail!("enter a size such as 512M, 2G, 80% or max");
+    }
+    if input.eq_ignore_ascii_case("max") {
+        return Ok(LimitValue::Max);
+    }
+
+    if let Some(percent) = input.strip_suffix('%') {
+        let (digits, scale) = parse_decimal(percent.trim())
+            .ok_or_else(|| anyhow::anyhow!("invalid percentage {:?}", input))?;
+        let hundred = 100 * 10u128.pow(scale);
+        if digits > hundred {
+            bail!("percentage must be between 0 and 100");
+        }
+        let Some(parent_limit) = parent_limit else {
+            bail!("parent has no limit, so a percentage has nothing to refer to");
+        };
+        // At most 100% of a u64, so this neither overflows nor truncates
+        return Ok(LimitValue::Value(
+            (u128::from(parent_limit) * digits / hundred) as u64,
+        ));
+    }
+
+    let upper = input.to_ascii_uppercase();
+    let (number, shift) = SIZE_SUFFIXES
+        .iter()
+        .find_map(|&(suffix, shift)| Some((upper.strip_suffix(suffix)?, shift)))
+        .unwrap_or((&upper, 0));
+    let (digits, scale) =
+        parse_decimal(number.trim()).ok_or_else(|| anyhow::anyhow!("invalid size {:?}", input))?;
+
+    digits
+        .checked_mul(1 << shift)
+        .map(|bytes| bytes / 10u128.pow(scale))
+        .and_then(|bytes| u64::try_from(bytes).ok())
+        .map(LimitValue::Value)
+        .ok_or_else(|| anyhow::anyhow!("size {:?} is too large", input))
+}
+
+/// Fractional digits accepted in sizes and percentages, which keeps the
+/// scaling well inside a u128
+const MAX_FRACTION_DIGITS: usize = 18;
+
+/// Split an unsigned decimal such as `1.5` into its digits and the number of
+/// fractional digits, `(15, 1)`, so sizes can be scaled without floats
+fn parse_decimal(text: &str) -> Option<(u128, u32)> {
+    let (whole, fraction) = text.split_once('.').unwrap_or((text, ""));
+    if whole.is_empty() && fraction.is_empty()
+        || fraction.len() > MAX_FRACTION_DIGITS
+        || !whole
+            .chars()
+            .chain(fraction.chars())
+            .all(|c| c.is_ascii_digit())
+    {
+        return None;
+    }
+    let digits = format!("{}{}", whole, fraction).parse().ok()?;
+    Some((digits, fraction.len() as u32))
+}
+
+/// Read a `max`-or-number control file
+pub fn read_limit(cgroup: &Path, file: &str) -> Option<LimitValue> {
+    let content = fs::read_to_string(cgroup.join(file)).ok()?;
+    LimitValue::from_file_content(&content)
+}
+
+/// Read a plain counter such as `memory.current`
+pub fn read_counter(cgroup: &Path, file: &str) -> Option<u64> {
+    fs::read_to_string(cgroup.join(file))
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+}
+
+/// The tightest `file` limit set on `cgroup` or any ancestor up to `root`
+pub fn effective_limit(cgroup: &Path, root: &Path, file: &str) -> Option<u64> {
+    cgroup
+        .ancestors()
+        .take_while(|ancestor| ancestor.starts_with(root))
+        .filter_map(|ancestor| read_limit(ancestor, file)?.value())
+        .min()
+}
+
+/// Check `value` against the limit inherited from the parent
+pub fn validate_against_parent(value: LimitValue, parent_limit: Option<u64>) -> Result<()> {
+    if let (LimitValue::Value(bytes), Some(parent_limit)) = (value, parent_limit)
+        && bytes > parent_limit
+    {
+        bail!(
+            "{} exceeds the parent's effective limit of {}",
+            bytes,
+            parent_limit
+        );
+    }
+    Ok(())
+}
+
+/// Default `cpu.max` period in microseconds
+pub const DEFAULT_CPU_PERIOD_USEC: u64 = 100_000;
+
+/// CPU control files that can be edited
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CpuKnob {
+    Max,
+    Weight,
+    MaxBurst,
+}
+
+impl CpuKnob {
+    pub const ALL: [CpuKnob; 3] = [CpuKnob::Max, CpuKnob::Weight, CpuKnob::MaxBurst];
+
+    pub fn file_name(self) -> &'static str {
+        match self {
+            CpuKnob::Max => "cpu.max",
+            CpuKnob::Weight => "cpu.weight",
+            CpuKnob::MaxBurst => "cpu.max.burst",
+        }
+    }
+
+    /// Turn user input into the string written to the file
+    pub fn parse(self, input: &str, current_period: u64) -> Result<String> {
+        match self {
+            CpuKnob::Max => {
+                let (quota, period) = parse_cpu_max(input, current_period)?;
+                Ok(format_cpu_max(quota, period))
+            }
+            CpuKnob::Weight => parse_cpu_weight(input).map(|weight| weight.to_string()),
+            CpuKnob::MaxBurst => parse_duration_usec(input).map(|usec| usec.to_string()),
+        }
+    }
+}
+
+/// Parse `cpu.max` content into quota (`None` for `max`) and period
+pub fn read_cpu_max(cgroup: &Path) -> Option<(Option<u64>, u64)> {
+    let content = fs::read_to_string(cgroup.join("cpu.max")).ok()?;
+    let mut parts = content.split_whitespace();
+    let quota = match parts.next()? {
+        "max" => None,
+        quota => Some(quota.parse().ok()?),
+    };
+    let period = parts
+        .next()
+        .and_then(|period| period.parse().ok())
+        .unwrap_or(DEFAULT_CPU_PERIOD_USEC);
+    Some((quota, period))
+}
+
+pub fn format_cpu_max(quota: Option<u64>, period: u64) -> String {
+    match quota {
+        Some(quota) => format!("{} {}", quota, period),
+        None => format!("max {}", period),
+    }
+}
+
+/// Parse a CPU quota typed by the user.
+///
+/// Accepts `max`, a number of cores (`1.5`, `2 cores`) applied to
+/// `current_period`, or an explicit `quota period` / `quota/period` pair.
+pub fn parse_cpu_max(input: &str, current_period: u64) -> Result<(Option<u64>, u64)> {
+    let input = input.trim().to_ascii_lowercase();
+    if input.is_empty() {
+        bail!("enter cores (1.5), quota/period (200000/100000) or max");
+    }
+
+    let parts: Vec<&str> = input
+        .split(|c: char| c == '/' || c.is_whitespace())
+        .filter(|part| !part.is_empty())
+        .collect();
+
+    let (quota, period) = match parts.as_slice() {
+        ["max"] => (None, current_period),
+        ["max", period] => (None, parse_usec_field(period, "period")?),
+        [cores] | [cores, "core" | "cores"] => {
+            let cores: f64 = cores
+                .parse()
+                .map_err(|_| anyhow::anyhow!("invalid CPU quota {:?}", input))?;
+            if !cores.is_finite() || cores <= 0.0 {
+                bail!("number of cores must be positive");
+            }
+            (
+                Some((cores * current_period as f64).round() as u64),
+                current_period,
+            )
+        }
+        [quota, period] => (
+            Some(parse_usec_field(quota, "quota")?),
+            parse_usec_field(period, "period")?,
+        ),
+        _ => bail!("invalid CPU quota {:?}", input),
+    };
+
+    // Limits enforced by the kernel for cpu.max
+    if !(1_000..=1_000_000).contains(&period) {
+        bail!("period must be between 1000 and 1000000 microseconds");
+    }
+    if let Some(quota) = quota
+        && quota < 1_000
+    {
+        bail!("quota must be at least 1000 microseconds");
+    }
+
+    Ok((quota, period))
+}
+
+fn parse_usec_field(value: &str, name: &str) -> Result<u64> {
+    value
+        .parse()
+        .map_err(|_| anyhow::anyhow!("invalid {} {:?}", name, value))
+}
+
+/// `cpu.weight` accepts 1 to 10000, 100 being the default share
+pub fn parse_cpu_weight(input: &str) -> Result<u64> {
+    let weight: u64 = input
+        .trim()
+        .parse()
+        .map_err(|_| anyhow::anyhow!("invalid weight {:?}", input.trim()))?;
+    if !(1..=10_000).contains(&weight) {
+        bail!("weight must be between 1 and 10000");
+    }
+    Ok(weight)
+}
+
+/// Parse a duration in microseconds; `us`, `ms` and `s` suffixes are accepted
+pub fn parse_duration_usec(input: &str) -> Result<u64> {
+    let input = input.trim().to_ascii_lowercase();
+    let (number, multiplier) = if let Some(number) = input.strip_suffix("us") {
+        (number, 1.0)
+    } else if let Some(number) = input.strip_suffix("ms") {
+        (number, 1_000.0)
+    } else if let Some(number) = input.strip_suffix('s') {
+        (number, 1_000_000.0)
+    } else {
+        (input.as_str(), 1.0)
+    };
+
+    let value: f64 = number
+        .trim()
+        .parse()
+        .map_err(|_| anyhow::anyhow!("invalid duration {:?}", input))?;
+    if !value.is_finite() || value < 0.0 {
+        bail!("duration must be a positive number");
+    }
+    Ok((value * multiplier).round() as u64)
+}
+
+/// Whether `controller` is listed in `cgroup.subtree_control` of `cgroup`
+pub fn controller_enabled(cgroup: &Path, controller: &str) -> bool {
+    fs::read_to_string(cgroup.join("cgroup.subtree_control"))
+        .map(|content| content.split_whitespace().any(|name| name == controller))
+        .unwrap_or(false)
+}
+
+/// A block device as listed in `/sys/block`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockDevice {
+    pub name: String,
+    /// `MAJ:MIN` as used by `io.max` and `io.weight`
+    pub dev: String,
+}
+
+/// Block devices under `sys_block` (normally `/sys/block`), sorted by name
+pub fn list_block_devices(sys_block: &Path) -> Vec<BlockDevice> {
+    let Ok(entries) = fs::read_dir(sys_block) else {
+        return Vec::new();
+    };
+
+    let mut devices: Vec<BlockDevice> = entries
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let dev = fs::read_to_string(entry.path().join("dev")).ok()?;
+            Some(BlockDevice {
+                name: entry.file_name().to_string_lossy().into_owned(),
+                dev: dev.trim().to_string(),
+            })
+        })
+        .collect();
+    devices.sort_by(|a, b| a.name.cmp(&b.name));
+    devices
+}
+
+/// The line for `dev` in a per-device file such as `io.max`, without the device prefix
+pub fn read_device_entry(cgroup: &Path, file: &str, dev: &str) -> Option<String> {
+    let content = fs::read_to_string(cgroup.join(file)).ok()?;
+    content.lines().find_map(|line| {
+        let (device, rest) = line.split_once(' ')?;
+        (device == dev).then(|| rest.trim().to_string())
+    })
+}
+
+/// Parse `pids.max` input: `max` or a positive number of tasks
+pub fn parse_pids_max(input: &str) -> Result<LimitValue> {
+    let input = input.trim();
+    if input.eq_ignore_ascii_case("max") {
+        return Ok(LimitValue::Max);
+    }
+    match input.parse::<u64>() {
+        Ok(0) => bail!("pids.max must be at least 1"),
+        Ok(pids) => Ok(LimitValue::Value(pids)),
+        Err(_) => bail!("enter a number of tasks or max"),
+    }
+}
+
+/// Keys accepted in an `io.max` line
+const IO_MAX_KEYS: [&str; 4] = ["rbps", "wbps", "riops", "wiops"];
+
+/// Build an `io.max` line for `dev` from input such as `wbps=10M riops=500`.
+///
+/// Bandwidth takes memory-style units, `max` lifts a limit; a lone `max`
+/// lifts all four.
+pub fn parse_io_max(input: &str, dev: &str) -> Result<String> {
+    let input = input.trim();
+    if input.is_empty() {
+        bail!("enter limits such as wbps=10M riops=500, or max");
+    }
+    if input.eq_ignore_ascii_case("max") {
+        let all_max: Vec<String> = IO_MAX_KEYS
+            .iter()
+            .map(|key| format!("{}=max", key))
+            .collect();
+        return Ok(format!("{} {}", dev, all_max.join(" ")));
+    }
+
+    let mut settings = Vec::new();
+    for setting in input.split_whitespace() {
+        let Some((key, value)) = setting.split_once('=') else {
+            bail!("expected key=value, got {:?}", setting);
+        };
+        let key = key.to_ascii_lowercase();
+        let value = match key.as_str() {
+            // io.max has no parent limit a percentage could refer to
+            "rbps" | "wbps" if value.trim_end().ends_with('%') => {
+                bail!("{} takes a rate such as 10M or max, not a percentage", key)
+            }
+            "rbps" | "wbps" => parse_memory_value(value, None)?.to_string(),
+            "riops" | "wiops" => parse_pids_max(value)
+                .map_err(|_| anyhow::anyhow!("{} takes a number or max", key))?
+                .to_string(),
+            _ => bail!(
+                "unknown io.max key {:?} (use rbps, wbps, riops, wiops)",
+                key
+            ),
+        };
+        settings.push(format!("{}={}", key, value));
+    }
+
+    Ok(format!("{} {}", dev, settings.join(" ")))
+}
+
+/// Build an `io.weight` line: `N` for `dev`, or `default N` for every device
+pub fn parse_io_weight(input: &str, dev: &str) -> Result<String> {
+    let input = input.trim();
+    match input.strip_prefix("default") {
+        Some(weight) => Ok(format!("default {}", parse_cpu_weight(weight)?)),
+        None => Ok(format!("{} {}", dev, parse_cpu_weight(input)?)),
+    }
+}
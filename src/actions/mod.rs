@@ -0,0 +1,662 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use nix::errno::Errno;
+use nix::sys::signal::{self, Signal};
+use nix::unistd::Pid;
+
+pub mod apply;
+pub mod exec;
+pub mod limits;
+
+/// What to do with processes still living in a cgroup that is about to be removed
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum MemberPolicy {
+    /// Leave populated cgroups alone and report them
+    #[default]
+    Refuse,
+    /// Move every member process into the given cgroup first
+    MigrateTo(PathBuf),
+    /// Kill the whole subtree through `cgroup.kill` first
+    Kill,
+}
+
+#[derive(Debug, Clone)]
+pub struct RemoveOptions {
+    pub member_policy: MemberPolicy,
+    /// How often `rmdir` is retried while the kernel reports EBUSY
+    pub busy_retries: u32,
+    pub retry_delay: Duration,
+    /// How long to wait for killed or migrated processes to leave
+    pub drain_timeout: Duration,
+}
+
+impl Default for RemoveOptions {
+    fn default() -> Self {
+        Self {
+            member_policy: MemberPolicy::Refuse,
+            busy_retries: 5,
+            retry_delay: Duration::from_millis(50),
+            drain_timeout: Duration::from_secs(2),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RemovalOutcome {
+    Removed {
+        attempts: u32,
+    },
+    /// `cgroup.events` still reports `populated 1`
+    Populated,
+    /// A child could not be removed, so this cgroup was left in place
+    Skipped,
+    Failed(String),
+}
+
+/// Result of removing a single cgroup
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CGroupRemoval {
+    pub path: PathBuf,
+    pub outcome: RemovalOutcome,
+}
+
+/// What happened to member processes before a tree was removed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Eviction {
+    /// `MemberPolicy::Kill`
+    Killed(Result<KillMethod, String>),
+    /// `MemberPolicy::MigrateTo`
+    Migrated(MoveReport),
+}
+
+/// Per-cgroup results of a removal, in the order they were attempted (leaves first)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RemovalReport {
+    pub entries: Vec<CGroupRemoval>,
+    /// Set unless the member policy was `Refuse`
+    pub eviction: Option<Eviction>,
+}
+
+impl RemovalReport {
+    pub fn is_success(&self) -> bool {
+        self.entries
+            .iter()
+            .all(|entry| matches!(entry.outcome, RemovalOutcome::Removed { .. }))
+    }
+
+    pub fn removed_count(&self) -> usize {
+        self.entries
+            .iter()
+            .filter(|entry| matches!(entry.outcome, RemovalOutcome::Removed { .. }))
+            .count()
+    }
+
+    /// Entries that were not removed and are not just waiting on a child
+    pub fn failures(&self) -> impl Iterator<Item = &CGroupRemoval> {
+        self.entries.iter().filter(|entry| {
+            !matches!(
+                entry.outcome,
+                RemovalOutcome::Removed { .. } | RemovalOutcome::Skipped
+            )
+        })
+    }
+
+    fn push(&mut self, path: &Path, outcome: RemovalOutcome) {
+        self.entries.push(CGroupRemoval {
+            path: path.to_path_buf(),
+            outcome,
+        });
+    }
+
+    fn outcome_of(&self, path: &Path) -> Option<&RemovalOutcome> {
+        self.entries
+            .iter()
+            .find(|entry| entry.path == path)
+            .map(|entry| &entry.outcome)
+    }
+}
+
+impl fmt::Display for RemovalReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "removed {}/{}", self.removed_count(), self.entries.len())?;
+
+        if let Some(failure) = self.failures().next() {
+            let reason = match &failure.outcome {
+                RemovalOutcome::Populated => "still has processes".to_string(),
+                RemovalOutcome::Failed(error) => error.clone(),
+                _ => unreachable!("failures() only yields populated or failed entries"),
+            };
+            write!(f, ", {}: {}", failure.path.display(), reason)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// What removing a cgroup tree would touch, gathered before asking for confirmation
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RemovalPlan {
+    pub target: PathBuf,
+    /// Every cgroup that would be removed, deepest first (the target comes last)
+    pub cgroups: Vec<PlannedCGroup>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlannedCGroup {
+    pub path: PathBuf,
+    /// PID and command of each member process
+    pub processes: Vec<(u32, String)>,
+}
+
+impl RemovalPlan {
+    /// Whether cgroups below the target would be removed as well
+    pub fn is_recursive(&self) -> bool {
+        self.cgroups.len() > 1
+    }
+
+    pub fn process_count(&self) -> usize {
+        self.cgroups
+            .iter()
+            .map(|cgroup| cgroup.processes.len())
+            .sum()
+    }
+}
+
+/// Inspect the subtree at `path` without changing anything
+pub fn plan_removal(path: &Path) -> io::Result<RemovalPlan> {
+    let cgroups = collect_subtree(path)?
+        .into_iter()
+        .map(|cgroup| PlannedCGroup {
+            processes: read_procs(&cgroup)
+                .into_iter()
+                .map(|pid| (pid, process_command(pid)))
+                .collect(),
+            path: cgroup,
+        })
+        .collect();
+
+    Ok(RemovalPlan {
+        target: path.to_path_buf(),
+        cgroups,
+    })
+}
+
+/// PIDs listed in `cgroup.procs`; an unreadable file counts as empty. The
+/// kernel lists processes outside cgtop's PID namespace as 0, which is left out
+/// since signalling or moving "PID 0" would act on cgtop itself.
+pub fn read_procs(cgroup: &Path) -> Vec<u32> {
+    fs::read_to_string(cgroup.join("cgroup.procs"))
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| line.trim().parse().ok())
+        .filter(|&pid| pid != 0)
+        .collect()
+}
+
+/// Short command name from `/proc/<pid>/comm`
+pub fn process_command(pid: u32) -> String {
+    fs::read_to_string(format!("/proc/{}/comm", pid))
+        .map(|comm| comm.trim().to_string())
+        .unwrap_or_else(|_| "?".to_string())
+}
+
+/// Remove the cgroup at `path` and every cgroup below it.
+///
+/// cgroupfs directories cannot be emptied like regular ones: their control
+/// files go away with the directory, so each cgroup is `rmdir`ed on its own,
+/// children before parents. A cgroup whose child stayed behind is skipped.
+pub fn remove_cgroup_tree(path: &Path, options: &RemoveOptions) -> RemovalReport {
+    let mut report = RemovalReport::default();
+
+    log::info!(
+        "Removing cgroup tree {} ({:?})",
+        path.display(),
+        options.member_policy
+    );
+
+    let cgroups = match collect_subtree(path) {
+        Ok(cgroups) => cgroups,
+        Err(e) => {
+            report.push(path, RemovalOutcome::Failed(e.to_string()));
+            return report;
+        }
+    };
+
+    report.eviction = evict_members(path, &cgroups, options);
+
+    for cgroup in &cgroups {
+        let child_left_behind = cgroups
+            .iter()
+            .filter(|other| other.parent() == Some(cgroup.as_path()))
+            .any(|child| {
+                !matches!(
+                    report.outcome_of(child),
+                    Some(RemovalOutcome::Removed { .. })
+                )
+            });
+
+        let outcome = if child_left_behind {
+            RemovalOutcome::Skipped
+        } else if is_populated(cgroup) {
+            RemovalOutcome::Populated
+        } else {
+            rmdir_with_retry(cgroup, options)
+        };
+
+        match &outcome {
+            RemovalOutcome::Removed { .. } => log::info!("Removed cgroup {}", cgroup.display()),
+            other => log::warn!("Did not remove cgroup {}: {:?}", cgroup.display(), other),
+        }
+        report.push(cgroup, outcome);
+    }
+
+    report
+}
+
+/// Every cgroup below and including `path`, deepest first
+pub(crate) fn collect_subtree(path: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut cgroups = Vec::new();
+    collect_subtree_into(path, &mut cgroups)?;
+    Ok(cgroups)
+}
+
+fn collect_subtree_into(path: &Path, cgroups: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(path)? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            collect_subtree_into(&entry.path(), cgroups)?;
+        }
+    }
+
+    cgroups.push(path.to_path_buf());
+    Ok(())
+}
+
+/// Whether `cgroup.events` reports live processes in the cgroup or below it
+pub fn is_populated(cgroup: &Path) -> bool {
+    read_events_flag(cgroup, "populated").unwrap_or(false)
+}
+
+/// Look up a `key 0|1` line in `cgroup.events`
+pub fn read_events_flag(cgroup: &Path, key: &str) -> Option<bool> {
+    let content = fs::read_to_string(cgroup.join("cgroup.events")).ok()?;
+    content.lines().find_map(|line| {
+        let (name, value) = line.split_once(' ')?;
+        (name == key).then(|| value.trim() == "1")
+    })
+}
+
+/// Apply the member policy to every cgroup of the tree at `root`
+fn evict_members(root: &Path, cgroups: &[PathBuf], options: &RemoveOptions) -> Option<Eviction> {
+    let eviction = match &options.member_policy {
+        MemberPolicy::Refuse => return None,
+        MemberPolicy::Kill => {
+            let result = kill_all(root).map_err(|e| e.to_string());
+            if let Err(e) = &result {
+                log::warn!("Failed to kill members of {}: {}", root.display(), e);
+            }
+            Eviction::Killed(result)
+        }
+        MemberPolicy::MigrateTo(target) => {
+            let pids: Vec<u32> = cgroups
+                .iter()
+                .flat_map(|cgroup| read_procs(cgroup))
+                .collect();
+            Eviction::Migrated(move_processes(&pids, target))
+        }
+    };
+
+    // Processes take a moment to exit or move; wait for the kernel to notice
+    let deadline = Instant::now() + options.drain_timeout;
+    while is_populated(root) && Instant::now() < deadline {
+        thread::sleep(Duration::from_millis(10));
+    }
+
+    Some(eviction)
+}
+
+/// How `kill_all` reached the processes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KillMethod {
+    /// One write to `cgroup.kill`, which also catches processes forked meanwhile
+    CgroupKill,
+    /// `cgroup.kill` is missing (before Linux 5.14), so each PID got SIGKILL
+    Signalled { processes: usize },
+}
+
+/// Passes over the subtree when signalling PIDs one by one, to catch forks
+const KILL_PASSES: usize = 3;
+
+/// SIGKILL every process in `cgroup` and below it.
+///
+/// Without `cgroup.kill`, PIDs that cannot be signalled are reported in the
+/// error after the remaining ones were killed.
+pub fn kill_all(cgroup: &Path) -> io::Result<KillMethod> {
+    let kill_file = cgroup.join("cgroup.kill");
+    if kill_file.exists() {
+        fs::write(kill_file, "1")?;
+        return Ok(KillMethod::CgroupKill);
+    }
+
+    // One failing PID must not stop the others from being killed, so errors
+    // are collected and reported once every pass is done
+    let mut signalled = 0;
+    let mut failures = BTreeMap::new();
+    for _ in 0..KILL_PASSES {
+        let pids: Vec<u32> = collect_subtree(cgroup)?
+            .iter()
+            .flat_map(|cgroup| read_procs(cgroup))
+            .filter(|pid| !failures.contains_key(pid))
+            .collect();
+        if pids.is_empty() {
+            break;
+        }
+
+        for pid in pids {
+            match process_id(pid).and_then(|pid| signal::kill(pid, Signal::SIGKILL)) {
+                Ok(()) => signalled += 1,
+                // Exited on its own in the meantime
+                Err(Errno::ESRCH) => {}
+                Err(errno) => {
+                    failures.insert(pid, errno);
+                }
+            }
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+
+    if !failures.is_empty() {
+        let pids: Vec<String> = failures
+            .iter()
+            .map(|(pid, errno)| format!("{} ({:?})", pid, errno))
+            .collect();
+        return Err(io::Error::other(format!(
+            "signalled {} process(es), could not kill {}",
+            signalled,
+            pids.join(", ")
+        )));
+    }
+
+    Ok(KillMethod::Signalled {
+        processes: signalled,
+    })
+}
+
+/// The `kill(2)` target for `pid`. 0 would signal cgtop's own process group
+/// and PIDs past `i32::MAX` wrap to negative group IDs, so both are refused.
+fn process_id(pid: u32) -> Result<Pid, Errno> {
+    match i32::try_from(pid) {
+        Ok(pid) if pid > 0 => Ok(Pid::from_raw(pid)),
+        _ => Err(Errno::EINVAL),
+    }
+}
+
+/// Whether the freezer reports `cgroup` as frozen
+pub fn is_frozen(cgroup: &Path) -> bool {
+    read_events_flag(cgroup, "frozen").unwrap_or(false)
+}
+
+/// Ask the kernel to freeze or thaw `cgroup` without waiting for it to happen;
+/// `is_frozen` tells when it did
+pub fn write_freeze(cgroup: &Path, frozen: bool) -> io::Result<()> {
+    fs::write(cgroup.join("cgroup.freeze"), if frozen { "1" } else { "0" })
+}
+
+fn rmdir_with_retry(cgroup: &Path, options: &RemoveOptions) -> RemovalOutcome {
+    let mut attempts = 0;
+
+    loop {
+        attempts += 1;
+        match fs::remove_dir(cgroup) {
+            Ok(()) => return RemovalOutcome::Removed { attempts },
+            // Recently emptied cgroups linger as "dying" for a short while
+            Err(e) if e.raw_os_error() == Some(Errno::EBUSY as i32) => {
+                if attempts > options.busy_retries {
+                    return RemovalOutcome::Failed(format!(
+                        "{} (still busy after {} attempts)",
+                        e, attempts
+                    ));
+                }
+                thread::sleep(options.retry_delay);
+            }
+            Err(e) => return RemovalOutcome::Failed(e.to_string()),
+        }
+    }
+}
+
+/// Prefixes of interface files; a child cgroup with such a name would clash with them
+const RESERVED_NAME_PREFIXES: &[&str] = &[
+    "cgroup.", "cpu.", "cpuset.", "io.", "memory.", "pids.", "hugetlb.", "rdma.", "misc.",
+];
+
+/// Check that `name` can be used for a new child cgroup
+pub fn validate_cgroup_name(name: &str) -> Result<(), String> {
+    if name.is_empty() {
+        return Err("name is empty".to_string());
+    }
+    if name == "." || name == ".." {
+        return Err(format!("{:?} is not a valid name", name));
+    }
+    if name.len() > 255 {
+        return Err("name is longer than 255 bytes".to_string());
+    }
+    if let Some(c) = name
+        .chars()
+        .find(|c| *c == '/' || c.is_control() || c.is_whitespace())
+    {
+        return Err(format!("name must not contain {:?}", c));
+    }
+    if let Some(prefix) = RESERVED_NAME_PREFIXES
+        .iter()
+        .find(|prefix| name.starts_with(*prefix))
+    {
+        return Err(format!(
+            "names starting with {:?} clash with interface files",
+            prefix
+        ));
+    }
+    Ok(())
+}
+
+/// Controllers listed in a whitespace-separated file such as `cgroup.controllers`
+pub fn read_controller_list(cgroup: &Path, file: &str) -> Vec<String> {
+    fs::read_to_string(cgroup.join(file))
+        .unwrap_or_default()
+        .split_whitespace()
+        .map(str::to_string)
+        .collect()
+}
+
+/// Outcome of writing one PID to the target's `cgroup.procs`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PidMove {
+    pub pid: u32,
+    pub result: Result<(), Errno>,
+}
+
+/// Per-PID results of a migration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MoveReport {
+    pub target: PathBuf,
+    pub moves: Vec<PidMove>,
+}
+
+impl MoveReport {
+    pub fn moved_count(&self) -> usize {
+        self.moves.iter().filter(|m| m.result.is_ok()).count()
+    }
+
+    pub fn failures(&self) -> impl Iterator<Item = &PidMove> {
+        self.moves.iter().filter(|m| m.result.is_err())
+    }
+}
+
+/// Move each of `pids` into `target` by writing it to `cgroup.procs`
+pub fn move_processes(pids: &[u32], target: &Path) -> MoveReport {
+    let procs = target.join("cgroup.procs");
+    let moves = pids
+        .iter()
+        .map(|&pid| {
+            // The kernel takes exactly one PID per write, and "0" means the
+            // writing process, which would move cgtop itself
+            let result = if pid == 0 {
+                Err(Errno::EINVAL)
+            } else {
+                fs::write(&procs, pid.to_string()).map_err(|e| {
+                    e.raw_os_error()
+                        .map_or(Errno::UnknownErrno, Errno::from_raw)
+                })
+            };
+            if let Err(errno) = result {
+                log::warn!(
+                    "Failed to move PID {} to {}: {}",
+                    pid,
+                    target.display(),
+                    errno
+                );
+            }
+            PidMove { pid, result }
+        })
+        .collect();
+
+    MoveReport {
+        target: target.to_path_buf(),
+        moves,
+    }
+}
+
+/// What a failed write to `cgroup.procs` usually means
+pub fn explain_move_error(errno: Errno) -> &'static str {
+    match errno {
+        Errno::EINVAL => "target is a threaded cgroup or cannot take processes",
+        Errno::EACCES | Errno::EPERM => {
+            "permission denied: under delegation both cgroups need a writable common ancestor"
+        }
+        Errno::EBUSY => "target enables controllers for children (no internal processes)",
+        Errno::ESRCH => "process has exited",
+        Errno::ENOENT => "target cgroup no longer exists",
+        _ => "write to cgroup.procs failed",
+    }
+}
+
+/// Signals offered in the process list's signal menu
+pub const SIGNAL_MENU: &[Signal] = &[
+    Signal::SIGTERM,
+    Signal::SIGKILL,
+    Signal::SIGSTOP,
+    Signal::SIGCONT,
+    Signal::SIGHUP,
+    Signal::SIGUSR1,
+    Signal::SIGUSR2,
+];
+
+/// Parse a signal given as a number (`9`) or a name with or without prefix (`TERM`, `sigusr1`)
+pub fn parse_signal(input: &str) -> Result<Signal, String> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Err("enter a signal name or number".to_string());
+    }
+
+    if let Ok(number) = input.parse::<i32>() {
+        return Signal::try_from(number).map_err(|_| format!("unknown signal number {}", number));
+    }
+
+    let upper = input.to_ascii_uppercase();
+    let name = if upper.starts_with("SIG") {
+        upper
+    } else {
+        format!("SIG{}", upper)
+    };
+    name.parse::<Signal>()
+        .map_err(|_| format!("unknown signal {:?}", input))
+}
+
+/// Whether `/proc/<pid>/stat` content belongs to a kernel thread
+pub fn is_kernel_thread_stat(stat: &str) -> bool {
+    /// PF_KTHREAD from include/linux/sched.h
+    const PF_KTHREAD: u64 = 0x0020_0000;
+
+    // The command may contain spaces and parentheses, so split after the last ')'
+    let Some((_, rest)) = stat.rsplit_once(')') else {
+        return false;
+    };
+    // state ppid pgrp session tty_nr tpgid flags ...
+    rest.split_whitespace()
+        .nth(6)
+        .and_then(|flags| flags.parse::<u64>().ok())
+        .is_some_and(|flags| flags & PF_KTHREAD != 0)
+}
+
+pub fn is_kernel_thread(pid: u32) -> bool {
+    fs::read_to_string(format!("/proc/{}/stat", pid)).is_ok_and(|stat| is_kernel_thread_stat(&stat))
+}
+
+/// Why signalling `pid` deserves a second confirmation
+pub fn signal_warning(pid: u32) -> Option<&'static str> {
+    if pid == 1 {
+        Some("init: the system goes down with it")
+    } else if is_kernel_thread(pid) {
+        Some("kernel thread")
+    } else {
+        None
+    }
+}
+
+/// Outcome of delivering a signal to one process
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PidSignal {
+    pub pid: u32,
+    pub result: Result<(), Errno>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SignalReport {
+    pub signal: Signal,
+    pub deliveries: Vec<PidSignal>,
+}
+
+impl SignalReport {
+    pub fn delivered_count(&self) -> usize {
+        self.deliveries.iter().filter(|d| d.result.is_ok()).count()
+    }
+
+    pub fn failures(&self) -> impl Iterator<Item = &PidSignal> {
+        self.deliveries.iter().filter(|d| d.result.is_err())
+    }
+}
+
+/// Send `sig` to each of `pids`
+pub fn send_signal(pids: &[u32], sig: Signal) -> SignalReport {
+    let deliveries = pids
+        .iter()
+        .map(|&pid| {
+            let result = process_id(pid).and_then(|target| signal::kill(target, sig));
+            match result {
+                Ok(()) => log::info!("Sent {} to PID {}", sig, pid),
+                Err(errno) => log::warn!("Failed to send {} to PID {}: {}", sig, pid, errno),
+            }
+            PidSignal { pid, result }
+        })
+        .collect();
+
+    SignalReport {
+        signal: sig,
+        deliveries,
+    }
+}
+
+/// What a failed `kill(2)` usually means
+pub fn explain_signal_error(errno: Errno) -> &'static str {
+    match errno {
+        Errno::ESRCH => "process has exited",
+        Errno::EPERM => "permission denied: the process belongs to another user",
+        Errno::EINVAL => "invalid signal",
+        _ => "kill failed",
+    }
+}
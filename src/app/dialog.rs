@@ -0,0 +1,730 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::path::{Path, PathBuf};
+
+use crate::actions::limits::{
+    BlockDevice, CpuKnob, LimitValue, MemoryKnob, effective_limit, read_counter, read_cpu_max,
+    read_device_entry, read_limit,
+};
+use crate::actions::{
+    MemberPolicy, MoveReport, RemovalPlan, SIGNAL_MENU, SignalReport, parse_signal,
+    read_controller_list,
+};
+use crate::canvas::format_bytes;
+use crate::widgets::CGroupTreeState;
+use nix::sys::signal::Signal;
+
+/// Modal dialog drawn over the main view; it receives every key while open
+#[derive(Debug, Clone)]
+pub enum Dialog {
+    ConfirmDelete(ConfirmDelete),
+    ConfirmKill(ConfirmKill),
+    ConfirmFreeze(ConfirmFreeze),
+    EditLimits(LimitEditor),
+    NewChild(NewChild),
+    SubtreeControl(SubtreeControlEditor),
+    MoveProcesses(MoveChooser),
+    MoveResult(MoveReport),
+    SignalMenu(SignalMenu),
+    ConfirmSignal(ConfirmSignal),
+    SignalResult(SignalReport),
+    History(HistoryView),
+}
+
+/// What a key press did to the open dialog
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DialogOutcome {
+    /// Still open
+    Pending,
+    Cancelled,
+    Confirmed,
+}
+
+impl Dialog {
+    pub fn handle_key(&mut self, key: KeyEvent) -> DialogOutcome {
+        let is_ctrl_c =
+            key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL);
+        if key.code == KeyCode::Esc || is_ctrl_c {
+            return DialogOutcome::Cancelled;
+        }
+
+        match self {
+            Dialog::ConfirmDelete(confirm) => confirm.handle_key(key),
+            Dialog::ConfirmKill(_) | Dialog::ConfirmFreeze(_) => match key.code {
+                KeyCode::Enter | KeyCode::Char('y') | KeyCode::Char('Y') => {
+                    DialogOutcome::Confirmed
+                }
+                KeyCode::Char('n') | KeyCode::Char('N') => DialogOutcome::Cancelled,
+                _ => DialogOutcome::Pending,
+            },
+            Dialog::EditLimits(editor) => editor.handle_key(key),
+            Dialog::NewChild(new_child) => match key.code {
+                KeyCode::Enter => DialogOutcome::Confirmed,
+                KeyCode::Backspace => {
+                    new_child.input.pop();
+                    DialogOutcome::Pending
+                }
+                KeyCode::Char(c) => {
+                    new_child.input.push(c);
+                    DialogOutcome::Pending
+                }
+                _ => DialogOutcome::Pending,
+            },
+            Dialog::SubtreeControl(editor) => editor.handle_key(key),
+            Dialog::MoveProcesses(chooser) => chooser.handle_key(key),
+            // Read-only: any key dismisses it
+            Dialog::MoveResult(_) | Dialog::SignalResult(_) => DialogOutcome::Cancelled,
+            Dialog::SignalMenu(menu) => menu.handle_key(key),
+            Dialog::History(view) => view.handle_key(key),
+            Dialog::ConfirmSignal(_) => match key.code {
+                KeyCode::Enter | KeyCode::Char('y') | KeyCode::Char('Y') => {
+                    DialogOutcome::Confirmed
+                }
+                KeyCode::Char('n') | KeyCode::Char('N') => DialogOutcome::Cancelled,
+                _ => DialogOutcome::Pending,
+            },
+        }
+    }
+}
+
+/// What a delete does with processes still living in the trees
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MemberAction {
+    /// Leave populated cgroups in place
+    #[default]
+    Leave,
+    /// Move the processes into the parent of each tree first
+    MoveToParent,
+    /// Kill the processes first
+    Kill,
+}
+
+impl MemberAction {
+    /// The choice after this one, cycled with Tab
+    pub fn next(self) -> Self {
+        match self {
+            MemberAction::Leave => MemberAction::MoveToParent,
+            MemberAction::MoveToParent => MemberAction::Kill,
+            MemberAction::Kill => MemberAction::Leave,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            MemberAction::Leave => "leave them (cgroups with members stay in place)",
+            MemberAction::MoveToParent => "move them to the parent cgroup",
+            MemberAction::Kill => "kill them",
+        }
+    }
+
+    /// Removal policy for the tree at `target`
+    pub fn policy_for(self, target: &Path) -> MemberPolicy {
+        match (self, target.parent()) {
+            (MemberAction::MoveToParent, Some(parent)) => {
+                MemberPolicy::MigrateTo(parent.to_path_buf())
+            }
+            (MemberAction::Kill, _) => MemberPolicy::Kill,
+            _ => MemberPolicy::Refuse,
+        }
+    }
+}
+
+/// Confirmation for removing one or more cgroup trees
+#[derive(Debug, Clone)]
+pub struct ConfirmDelete {
+    pub title: String,
+    /// One plan per tree, in the order they are removed
+    pub plans: Vec<RemovalPlan>,
+    /// Text typed so far when a typed confirmation is required
+    pub input: String,
+    /// What happens to member processes, only offered when there are some
+    pub members: MemberAction,
+}
+
+impl ConfirmDelete {
+    pub fn new(title: String, plans: Vec<RemovalPlan>) -> Self {
+        Self {
+            title,
+            plans,
+            input: String::new(),
+            members: MemberAction::default(),
+        }
+    }
+
+    /// Processes living in the trees when the dialog was opened
+    pub fn process_count(&self) -> usize {
+        self.plans.iter().map(RemovalPlan::process_count).sum()
+    }
+
+    /// Text that has to be typed before a recursive removal goes ahead: the
+    /// cgroup name for a single tree, the number of trees for a batch
+    pub fn required_input(&self) -> Option<String> {
+        if !self.plans.iter().any(RemovalPlan::is_recursive) {
+            return None;
+        }
+
+        match self.plans.as_slice() {
+            [plan] => Some(
+                plan.target
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| plan.target.display().to_string()),
+            ),
+            plans => Some(plans.len().to_string()),
+        }
+    }
+
+    /// Number of cgroups removed across all plans
+    pub fn cgroup_count(&self) -> usize {
+        self.plans.iter().map(|plan| plan.cgroups.len()).sum()
+    }
+
+    pub fn can_confirm(&self) -> bool {
+        self.required_input()
+            .is_none_or(|required| self.input == required)
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) -> DialogOutcome {
+        if key.code == KeyCode::Tab && self.process_count() > 0 {
+            self.members = self.members.next();
+            return DialogOutcome::Pending;
+        }
+        if self.required_input().is_none() {
+            return match key.code {
+                KeyCode::Enter | KeyCode::Char('y') | KeyCode::Char('Y') => {
+                    DialogOutcome::Confirmed
+                }
+                KeyCode::Char('n') | KeyCode::Char('N') => DialogOutcome::Cancelled,
+                _ => DialogOutcome::Pending,
+            };
+        }
+
+        match key.code {
+            KeyCode::Enter if self.can_confirm() => DialogOutcome::Confirmed,
+            KeyCode::Backspace => {
+                self.input.pop();
+                DialogOutcome::Pending
+            }
+            KeyCode::Char(c) => {
+                self.input.push(c);
+                DialogOutcome::Pending
+            }
+            _ => DialogOutcome::Pending,
+        }
+    }
+}
+
+/// Confirmation for killing every process in one or more cgroup subtrees
+#[derive(Debug, Clone)]
+pub struct ConfirmKill {
+    pub cgroups: Vec<PathBuf>,
+    /// PID and command of every process that would be killed
+    pub processes: Vec<(u32, String)>,
+}
+
+/// Confirmation for freezing or thawing several cgroups at once
+#[derive(Debug, Clone)]
+pub struct ConfirmFreeze {
+    pub cgroups: Vec<PathBuf>,
+    /// Freeze them all, or thaw them all
+    pub freeze: bool,
+}
+
+/// Name prompt for a new child cgroup
+#[derive(Debug, Clone)]
+pub struct NewChild {
+    pub parent: PathBuf,
+    pub input: String,
+}
+
+/// Toggles for the controllers a cgroup hands down to its children
+#[derive(Debug, Clone)]
+pub struct SubtreeControlEditor {
+    pub cgroup: PathBuf,
+    /// Controllers available to the cgroup and whether children get them
+    pub controllers: Vec<(String, bool)>,
+    pub selected: usize,
+}
+
+impl SubtreeControlEditor {
+    /// Read `cgroup.controllers` and `cgroup.subtree_control`, or `None` if they are missing
+    pub fn load(cgroup: &Path) -> Option<Self> {
+        if !cgroup.join("cgroup.subtree_control").exists() {
+            return None;
+        }
+
+        let mut editor = Self {
+            cgroup: cgroup.to_path_buf(),
+            controllers: Vec::new(),
+            selected: 0,
+        };
+        editor.reload();
+        Some(editor)
+    }
+
+    /// Pick up the state after a write
+    pub fn reload(&mut self) {
+        let enabled = read_controller_list(&self.cgroup, "cgroup.subtree_control");
+        self.controllers = read_controller_list(&self.cgroup, "cgroup.controllers")
+            .into_iter()
+            .map(|name| {
+                let is_enabled = enabled.contains(&name);
+                (name, is_enabled)
+            })
+            .collect();
+        self.selected = self.selected.min(self.controllers.len().saturating_sub(1));
+    }
+
+    pub fn selected_controller(&self) -> Option<&(String, bool)> {
+        self.controllers.get(self.selected)
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) -> DialogOutcome {
+        match key.code {
+            KeyCode::Enter | KeyCode::Char(' ') => return DialogOutcome::Confirmed,
+            KeyCode::Up | KeyCode::Char('k') => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.selected = (self.selected + 1).min(self.controllers.len().saturating_sub(1));
+            }
+            _ => {}
+        }
+        DialogOutcome::Pending
+    }
+}
+
+/// Tree of cgroups to pick a migration target from
+#[derive(Debug, Clone)]
+pub struct MoveChooser {
+    pub pids: Vec<u32>,
+    /// Copy of the main cgroup tree, expanded and navigated on its own
+    pub tree: CGroupTreeState,
+    /// Typed text narrowing the tree down to matching cgroups
+    pub filter: String,
+}
+
+impl MoveChooser {
+    pub fn new(pids: Vec<u32>, mut tree: CGroupTreeState) -> Self {
+        // The root is a valid target, marks are not relevant here
+        tree.show_root = true;
+        tree.marked.clear();
+        tree.set_filter("");
+        Self {
+            pids,
+            tree,
+            filter: String::new(),
+        }
+    }
+
+    /// Full paths of the rows currently shown, in tree order
+    pub fn rows(&self) -> Vec<&str> {
+        self.tree
+            .visible_nodes
+            .iter()
+            .filter_map(|key| self.tree.nodes.get(key))
+            .map(|node| node.path.as_str())
+            .collect()
+    }
+
+    pub fn target(&self) -> Option<&str> {
+        let selected = self.tree.selected.as_ref()?;
+        self.tree.nodes.get(selected).map(|node| node.path.as_str())
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) -> DialogOutcome {
+        match key.code {
+            KeyCode::Enter if self.target().is_some() => return DialogOutcome::Confirmed,
+            KeyCode::Up => self.tree.select_previous(),
+            KeyCode::Down => self.tree.select_next(),
+            // Expanding and collapsing only applies to the unfiltered tree
+            KeyCode::Right | KeyCode::Left if self.filter.is_empty() => {
+                let expand = key.code == KeyCode::Right;
+                if let Some(selected) = self.tree.selected.clone()
+                    && self
+                        .tree
+                        .nodes
+                        .get(&selected)
+                        .is_some_and(|node| node.expanded != expand && !node.children.is_empty())
+                {
+                    self.tree.toggle_expand(&selected);
+                }
+            }
+            KeyCode::Backspace => {
+                self.filter.pop();
+                self.tree.set_filter(&self.filter);
+            }
+            KeyCode::Char(c) => {
+                self.filter.push(c);
+                self.tree.set_filter(&self.filter);
+            }
+            _ => {}
+        }
+        DialogOutcome::Pending
+    }
+}
+
+/// Signal picker for the selected processes
+#[derive(Debug, Clone)]
+pub struct SignalMenu {
+    pub pids: Vec<u32>,
+    /// Index into `SIGNAL_MENU`
+    pub selected: usize,
+    /// Typed signal name or number; overrides the menu when non-empty
+    pub input: String,
+}
+
+impl SignalMenu {
+    pub fn new(pids: Vec<u32>) -> Self {
+        Self {
+            pids,
+            selected: 0,
+            input: String::new(),
+        }
+    }
+
+    /// The signal that Enter would send
+    pub fn signal(&self) -> Result<Signal, String> {
+        if self.input.is_empty() {
+            Ok(SIGNAL_MENU[self.selected])
+        } else {
+            parse_signal(&self.input)
+        }
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) -> DialogOutcome {
+        match key.code {
+            KeyCode::Enter => return DialogOutcome::Confirmed,
+            KeyCode::Up => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Down => self.selected = (self.selected + 1).min(SIGNAL_MENU.len() - 1),
+            KeyCode::Backspace => {
+                self.input.pop();
+            }
+            KeyCode::Char(c) => self.input.push(c),
+            _ => {}
+        }
+        DialogOutcome::Pending
+    }
+}
+
+/// Second confirmation before signalling init or kernel threads
+#[derive(Debug, Clone)]
+pub struct ConfirmSignal {
+    pub pids: Vec<u32>,
+    pub signal: Signal,
+    /// PIDs that need the confirmation, and why
+    pub warnings: Vec<(u32, &'static str)>,
+}
+
+/// Writes recorded in the audit log during this session
+#[derive(Debug, Clone, Default)]
+pub struct HistoryView {
+    /// Row counted from the newest entry
+    pub selected: usize,
+    /// Number of entries in the audit log, the last row `selected` can reach
+    pub entries: usize,
+}
+
+impl HistoryView {
+    pub fn new(entries: usize) -> Self {
+        Self {
+            selected: 0,
+            entries,
+        }
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) -> DialogOutcome {
+        match key.code {
+            // Undo the last change
+            KeyCode::Char('u') => return DialogOutcome::Confirmed,
+            KeyCode::Char('q') => return DialogOutcome::Cancelled,
+            KeyCode::Up | KeyCode::Char('k') => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.selected = (self.selected + 1).min(self.entries.saturating_sub(1));
+            }
+            _ => {}
+        }
+        DialogOutcome::Pending
+    }
+}
+
+/// Which group of control files a `LimitEditor` edits
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LimitKind {
+    Memory,
+    Cpu,
+    Pids,
+    Io,
+}
+
+/// Files edited by the IO editor, in field order
+pub const IO_FILES: [&str; 2] = ["io.max", "io.weight"];
+
+/// One editable control file
+#[derive(Debug, Clone)]
+pub struct LimitField {
+    pub file: &'static str,
+    /// Current value, formatted for display
+    pub current: String,
+}
+
+/// Edit dialog for the control files of one controller, shown for a single
+/// cgroup and written to every cgroup in `targets`
+#[derive(Debug, Clone)]
+pub struct LimitEditor {
+    pub kind: LimitKind,
+    /// cgroup whose current values are shown
+    pub cgroup: PathBuf,
+    /// cgroups the value is written to, `cgroup` first
+    pub targets: Vec<PathBuf>,
+    pub fields: Vec<LimitField>,
+    pub selected: usize,
+    pub input: String,
+    /// Read-only context shown under the fields (usage, inherited limits)
+    pub details: Vec<String>,
+    /// Devices to pick from for per-device files such as `io.max`
+    pub devices: Vec<BlockDevice>,
+    pub device: usize,
+}
+
+impl LimitEditor {
+    /// Editor for the memory knobs of `cgroup`, or `None` if the memory controller is not enabled
+    pub fn memory(cgroup: &Path, root: &Path) -> Option<Self> {
+        read_limit(cgroup, MemoryKnob::Max.file_name())?;
+
+        let fields = MemoryKnob::ALL
+            .iter()
+            .map(|knob| LimitField {
+                file: knob.file_name(),
+                current: read_limit(cgroup, knob.file_name())
+                    .map(format_limit)
+                    .unwrap_or_else(|| "n/a".to_string()),
+            })
+            .collect();
+
+        let usage = |file| read_counter(cgroup, file).map_or("n/a".to_string(), format_bytes);
+        let parent_limit = |file| {
+            cgroup
+                .parent()
+                .and_then(|parent| effective_limit(parent, root, file))
+                .map_or("unlimited".to_string(), format_bytes)
+        };
+        let details = vec![
+            format!(
+                "Usage: {} (swap {})",
+                usage("memory.current"),
+                usage("memory.swap.current")
+            ),
+            format!(
+                "Parent limit: {} (swap {})",
+                parent_limit("memory.max"),
+                parent_limit("memory.swap.max")
+            ),
+        ];
+
+        Some(Self {
+            kind: LimitKind::Memory,
+            cgroup: cgroup.to_path_buf(),
+            targets: vec![cgroup.to_path_buf()],
+            fields,
+            selected: 0,
+            input: String::new(),
+            details,
+            devices: Vec::new(),
+            device: 0,
+        })
+    }
+
+    /// Editor for the CPU knobs of `cgroup`, or `None` if the cpu controller is not enabled
+    pub fn cpu(cgroup: &Path) -> Option<Self> {
+        let (quota, period) = read_cpu_max(cgroup)?;
+
+        let read = |file: &str, unit: &str| {
+            std::fs::read_to_string(cgroup.join(file))
+                .map(|content| format!("{}{}", content.trim(), unit))
+                .unwrap_or_else(|_| "n/a".to_string())
+        };
+        let fields = CpuKnob::ALL
+            .iter()
+            .map(|knob| LimitField {
+                file: knob.file_name(),
+                current: match knob {
+                    CpuKnob::Max => format_cores(quota, period),
+                    CpuKnob::Weight => read(knob.file_name(), ""),
+                    // cpu.max.burst only exists since Linux 5.14
+                    CpuKnob::MaxBurst => read(knob.file_name(), "us"),
+                },
+            })
+            .collect();
+
+        let stat = std::fs::read_to_string(cgroup.join("cpu.stat")).unwrap_or_default();
+        let stat_value = |key: &str| {
+            stat.lines()
+                .find_map(|line| line.strip_prefix(key)?.trim().parse::<u64>().ok())
+                .unwrap_or(0)
+        };
+        let details = vec![
+            format!(
+                "Quota: {} per {}us period",
+                quota.map_or("unlimited".to_string(), |quota| format!("{}us", quota)),
+                period
+            ),
+            format!(
+                "Throttled {} times ({:.1}s in total)",
+                stat_value("nr_throttled "),
+                stat_value("throttled_usec ") as f64 / 1_000_000.0
+            ),
+        ];
+
+        Some(Self {
+            kind: LimitKind::Cpu,
+            cgroup: cgroup.to_path_buf(),
+            targets: vec![cgroup.to_path_buf()],
+            fields,
+            selected: 0,
+            input: String::new(),
+            details,
+            devices: Vec::new(),
+            device: 0,
+        })
+    }
+
+    /// Editor for `pids.max`, or `None` if the pids controller is not enabled
+    pub fn pids(cgroup: &Path, root: &Path) -> Option<Self> {
+        let current = read_limit(cgroup, "pids.max")?;
+
+        let parent_limit = cgroup
+            .parent()
+            .and_then(|parent| effective_limit(parent, root, "pids.max"))
+            .map_or("unlimited".to_string(), |limit| limit.to_string());
+        let details = vec![
+            format!(
+                "Tasks: {} (peak {})",
+                read_counter(cgroup, "pids.current").map_or("n/a".to_string(), |n| n.to_string()),
+                read_counter(cgroup, "pids.peak").map_or("n/a".to_string(), |n| n.to_string())
+            ),
+            format!("Parent limit: {}", parent_limit),
+        ];
+
+        Some(Self {
+            kind: LimitKind::Pids,
+            cgroup: cgroup.to_path_buf(),
+            targets: vec![cgroup.to_path_buf()],
+            fields: vec![LimitField {
+                file: "pids.max",
+                current: current.to_string(),
+            }],
+            selected: 0,
+            input: String::new(),
+            details,
+            devices: Vec::new(),
+            device: 0,
+        })
+    }
+
+    /// Editor for `io.max` and `io.weight` on one of `devices`, or `None` if
+    /// the io controller is not enabled or there is no device to pick
+    pub fn io(cgroup: &Path, devices: Vec<BlockDevice>) -> Option<Self> {
+        if !cgroup.join("io.max").exists() || devices.is_empty() {
+            return None;
+        }
+
+        let mut editor = Self {
+            kind: LimitKind::Io,
+            cgroup: cgroup.to_path_buf(),
+            targets: vec![cgroup.to_path_buf()],
+            fields: IO_FILES
+                .iter()
+                .map(|file| LimitField {
+                    file,
+                    current: String::new(),
+                })
+                .collect(),
+            selected: 0,
+            input: String::new(),
+            details: Vec::new(),
+            devices,
+            device: 0,
+        };
+        editor.load_device_values();
+        Some(editor)
+    }
+
+    /// Also write the value to `others`, for a batch edit of marked cgroups
+    pub fn with_targets(mut self, others: &[PathBuf]) -> Self {
+        for other in others {
+            if !self.targets.contains(other) {
+                self.targets.push(other.clone());
+            }
+        }
+        self
+    }
+
+    pub fn selected_device(&self) -> Option<&BlockDevice> {
+        self.devices.get(self.device)
+    }
+
+    /// Re-read the per-device values after switching devices
+    fn load_device_values(&mut self) {
+        let Some(device) = self.devices.get(self.device) else {
+            return;
+        };
+
+        for field in &mut self.fields {
+            field.current = read_device_entry(&self.cgroup, field.file, &device.dev)
+                .unwrap_or_else(|| match field.file {
+                    "io.weight" => read_device_entry(&self.cgroup, field.file, "default")
+                        .map_or("n/a".to_string(), |weight| format!("default {}", weight)),
+                    _ => "no limit".to_string(),
+                });
+        }
+        self.details = vec![format!(
+            "Limits apply to {} ({}), ←/→ picks another device",
+            device.name, device.dev
+        )];
+    }
+
+    pub fn selected_file(&self) -> &'static str {
+        self.fields[self.selected].file
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) -> DialogOutcome {
+        match key.code {
+            KeyCode::Enter => return DialogOutcome::Confirmed,
+            KeyCode::Up | KeyCode::BackTab => {
+                self.selected = (self.selected + self.fields.len() - 1) % self.fields.len();
+                self.input.clear();
+            }
+            KeyCode::Down | KeyCode::Tab => {
+                self.selected = (self.selected + 1) % self.fields.len();
+                self.input.clear();
+            }
+            KeyCode::Left | KeyCode::Right if !self.devices.is_empty() => {
+                let count = self.devices.len();
+                self.device = if key.code == KeyCode::Left {
+                    (self.device + count - 1) % count
+                } else {
+                    (self.device + 1) % count
+                };
+                self.load_device_values();
+            }
+            KeyCode::Backspace => {
+                self.input.pop();
+            }
+            KeyCode::Char(c) => self.input.push(c),
+            _ => {}
+        }
+        DialogOutcome::Pending
+    }
+}
+
+fn format_cores(quota: Option<u64>, period: u64) -> String {
+    match quota {
+        Some(quota) => format!("{:.2} cores", quota as f64 / period as f64),
+        None => "max".to_string(),
+    }
+}
+
+fn format_limit(value: LimitValue) -> String {
+    match value {
+        LimitValue::Value(bytes) => format_bytes(bytes),
+        LimitValue::Max => "max".to_string(),
+    }
+}
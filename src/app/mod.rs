@@ -1,9 +1,50 @@
+pub mod dialog;
+
+use crate::actions::limits::{
+    CpuKnob, DEFAULT_CPU_PERIOD_USEC, LimitValue, MemoryKnob, controller_enabled, effective_limit,
+    list_block_devices, parse_io_max, parse_io_weight, parse_memory_value, parse_pids_max,
+    read_counter, read_cpu_max, validate_against_parent,
+};
+use crate::actions::{
+    Eviction, KillMethod, MoveReport, RemovalOutcome, RemovalPlan, RemoveOptions, is_frozen,
+    kill_all, move_processes, plan_removal, read_procs, remove_cgroup_tree, send_signal,
+    signal_warning, validate_cgroup_name, write_freeze,
+};
+use crate::audit::{self, AuditEntry, AuditLog};
 use crate::collection::CGroupMetrics;
+use crate::columns::{CsvColumn, csv_header, csv_rows};
+use crate::events::CollectionCommand;
 use crate::notifications::NotificationManager;
-use crate::widgets::CGroupTreeState;
-use crossbeam::channel::Receiver;
-use std::path::PathBuf;
-use std::time::Instant;
+use crate::theme::Theme;
+use crate::utils::SafetyPolicy;
+use crate::widgets::{CGroupTreeState, ProcessListWidget};
+use anyhow::Context;
+use crossbeam::channel::{Receiver, Sender};
+use crossterm::event::KeyEvent;
+use dialog::{
+    ConfirmDelete, ConfirmFreeze, ConfirmKill, ConfirmSignal, Dialog, DialogOutcome, HistoryView,
+    LimitEditor, LimitKind, MemberAction, MoveChooser, NewChild, SignalMenu, SubtreeControlEditor,
+};
+use nix::errno::Errno;
+use nix::sys::signal::Signal;
+use ratatui::layout::Rect;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Default time between two samples of the cgroup hierarchy
+pub const DEFAULT_UPDATE_INTERVAL_MS: u64 = 1000;
+
+/// Shortest interval accepted from the command line or config file
+pub const MIN_UPDATE_INTERVAL_MS: u64 = 100;
+
+/// How long a freeze or thaw may take before we stop waiting for `cgroup.events`
+pub const FREEZE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Intervals the `+`/`-` keys step through
+pub const UPDATE_INTERVAL_STEPS_MS: &[u64] = &[
+    100, 200, 250, 500, 1000, 2000, 3000, 5000, 10_000, 30_000, 60_000,
+];
 
 pub struct App {
     pub cgroup_data: CGroupData,
@@ -11,13 +52,24 @@ pub struct App {
     pub config: Config,
     pub filters: FilterState,
     pub notifications: NotificationManager,
+    /// Every write made to cgroupfs, with undo information
+    pub audit: AuditLog,
+    pub theme: Theme,
     pub input_receiver: Option<Receiver<InputEvent>>,
     pub data_receiver: Option<Receiver<CGroupMetrics>>,
+    pub control_sender: Option<Sender<CollectionCommand>>,
 }
 
+/// Tabs shown above the right-hand panel
+pub const TAB_TITLES: [&str; 2] = ["Resources", "Processes"];
+pub const RESOURCES_TAB: usize = 0;
+pub const PROCESSES_TAB: usize = 1;
+
 #[derive(Default)]
 pub struct CGroupData {
     pub metrics: Option<Box<CGroupMetrics>>,
+    /// The sample before `metrics`, for per-second rates
+    pub previous: Option<Box<CGroupMetrics>>,
     pub last_update: Option<Instant>,
 }
 
@@ -29,13 +81,74 @@ pub struct UiState {
     pub scroll_offset: usize,
     pub key_sequence: Vec<char>,
     pub last_key_time: Option<std::time::Instant>,
+    pub sampling_paused: bool,
+    pub process_sort: ProcessSort,
+    pub layout: ScreenLayout,
+    /// Modal dialog that currently owns the keyboard
+    pub dialog: Option<Dialog>,
+    /// Row of the highlighted process in the process list
+    pub process_cursor: usize,
+    /// Processes marked for the next process action
+    pub marked_pids: BTreeSet<u32>,
+    /// cgroup to open a shell in once the main loop can hand over the terminal
+    pub pending_shell: Option<PathBuf>,
+    /// Freezes and thaws still waiting for `cgroup.events` to confirm them
+    pub pending_freezes: Vec<PendingFreeze>,
+}
+
+/// A freeze or thaw written to `cgroup.freeze` that the kernel has not finished
+#[derive(Debug, Clone)]
+pub struct PendingFreeze {
+    /// cgroups not yet in the requested state
+    pub cgroups: Vec<PathBuf>,
+    pub freeze: bool,
+    /// What was asked for, for the final notification
+    pub targets: String,
+    /// Give up waiting and warn after this
+    pub deadline: Instant,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ProcessSortColumn {
+    #[default]
+    Pid,
+    Command,
+    Cgroup,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ProcessSort {
+    pub column: ProcessSortColumn,
+    pub descending: bool,
+}
+
+impl ProcessSort {
+    /// Sort by `column`, flipping the direction if it is already the sort column
+    pub fn toggle(&mut self, column: ProcessSortColumn) {
+        if self.column == column {
+            self.descending = !self.descending;
+        } else {
+            self.column = column;
+            self.descending = false;
+        }
+    }
+}
+
+/// Screen regions recorded during the last draw, used for mouse hit-testing
+#[derive(Debug, Clone, Default)]
+pub struct ScreenLayout {
+    pub tree: Rect,
+    pub tabs: Vec<Rect>,
+    pub process_list: Rect,
+    pub process_header: Vec<(ProcessSortColumn, Rect)>,
 }
 
 impl UiState {
     pub fn new(cgroup_root: PathBuf) -> Self {
-        let mut ui_state = Self::default();
-        ui_state.tree_state = CGroupTreeState::new(cgroup_root);
-        ui_state
+        Self {
+            tree_state: CGroupTreeState::new(cgroup_root),
+            ..Self::default()
+        }
     }
 }
 
@@ -43,14 +156,33 @@ pub struct Config {
     pub update_interval_ms: u64,
     pub data_retention_seconds: u64,
     pub cgroup_root: PathBuf,
+    /// Decides which cgroups below the root may be deleted
+    pub safety: SafetyPolicy,
+    /// Only log destructive actions instead of performing them
+    pub dry_run: bool,
+    /// Whether the UI captures the mouse
+    pub mouse: bool,
+    /// Refuse every action that writes to cgroupfs or touches processes
+    pub read_only: bool,
+    /// Columns of the CSV export
+    pub csv_columns: Vec<CsvColumn>,
+    /// Where CSV exports are written
+    pub export_dir: PathBuf,
 }
 
 impl Default for Config {
     fn default() -> Self {
+        let cgroup_root = PathBuf::from("/sys/fs/cgroup");
         Self {
-            update_interval_ms: 0,
+            update_interval_ms: DEFAULT_UPDATE_INTERVAL_MS,
             data_retention_seconds: 0,
-            cgroup_root: PathBuf::from("/sys/fs/cgroup"),
+            safety: SafetyPolicy::new(cgroup_root.clone()),
+            cgroup_root,
+            dry_run: false,
+            mouse: true,
+            read_only: false,
+            csv_columns: CsvColumn::DEFAULT.to_vec(),
+            export_dir: PathBuf::from("."),
         }
     }
 }
@@ -75,14 +207,20 @@ impl App {
             config: Config::default(),
             filters: FilterState::default(),
             notifications: NotificationManager::new(),
+            audit: AuditLog::in_memory(),
+            theme: Theme::default(),
             input_receiver: None,
             data_receiver: None,
+            control_sender: None,
         }
     }
 
     pub fn new_with_path(cgroup_root: PathBuf) -> Self {
-        let mut config = Config::default();
-        config.cgroup_root = cgroup_root;
+        let config = Config {
+            safety: SafetyPolicy::new(cgroup_root.clone()),
+            cgroup_root,
+            ..Config::default()
+        };
 
         Self {
             cgroup_data: CGroupData::default(),
@@ -90,8 +228,11 @@ impl App {
             config,
             filters: FilterState::default(),
             notifications: NotificationManager::new(),
+            audit: AuditLog::in_memory(),
+            theme: Theme::default(),
             input_receiver: None,
             data_receiver: None,
+            control_sender: None,
         }
     }
 
@@ -104,6 +245,1317 @@ impl App {
         self.data_receiver = Some(data_rx);
     }
 
+    /// Replace the current sample with `metrics` and rebuild the tree around it
+    pub fn apply_metrics(&mut self, metrics: Box<CGroupMetrics>) {
+        let cgroup_count = metrics.resource_usage.len();
+        let process_count = metrics.processes.len();
+
+        // Update tree state with new data
+        self.ui_state
+            .tree_state
+            .build_from_paths(&metrics.resource_usage);
+
+        // Forget marks on processes that have exited
+        self.ui_state
+            .marked_pids
+            .retain(|pid| metrics.processes.contains_key(pid));
+        self.ui_state.process_cursor = self
+            .ui_state
+            .process_cursor
+            .min(process_count.saturating_sub(1));
+
+        self.cgroup_data.previous = self.cgroup_data.metrics.replace(metrics);
+        self.cgroup_data.last_update = Some(Instant::now());
+
+        log::info!(
+            "Updated cgroup metrics: {} cgroups, {} processes",
+            cgroup_count,
+            process_count
+        );
+    }
+
+    /// Point the resource panel at whatever the tree currently has selected
+    pub fn sync_selected_cgroup(&mut self) {
+        self.ui_state.selected_cgroup = self
+            .ui_state
+            .tree_state
+            .selected
+            .as_ref()
+            .and_then(|key| self.ui_state.tree_state.nodes.get(key))
+            .map(|node| node.path.clone());
+    }
+
+    pub fn select_tab(&mut self, tab: usize) {
+        self.ui_state.current_tab = tab % TAB_TITLES.len();
+        log::info!("Switched to tab {}", self.ui_state.current_tab);
+    }
+
+    /// Scroll the process list by `delta` rows
+    pub fn scroll_processes(&mut self, delta: isize) {
+        let total = self
+            .cgroup_data
+            .metrics
+            .as_ref()
+            .map_or(0, |metrics| metrics.processes.len());
+
+        self.ui_state.scroll_offset = self
+            .ui_state
+            .scroll_offset
+            .saturating_add_signed(delta)
+            .min(total.saturating_sub(1));
+    }
+
+    /// Move the process list highlight by `delta` rows, scrolling to keep it visible
+    pub fn move_process_cursor(&mut self, delta: isize) {
+        let total = self
+            .cgroup_data
+            .metrics
+            .as_ref()
+            .map_or(0, |metrics| metrics.processes.len());
+        if total == 0 {
+            return;
+        }
+
+        let cursor = self
+            .ui_state
+            .process_cursor
+            .saturating_add_signed(delta)
+            .min(total - 1);
+        self.ui_state.process_cursor = cursor;
+
+        // Header row and borders take three lines
+        let visible = (self.ui_state.layout.process_list.height as usize)
+            .saturating_sub(3)
+            .max(1);
+        if cursor < self.ui_state.scroll_offset {
+            self.ui_state.scroll_offset = cursor;
+        } else if cursor >= self.ui_state.scroll_offset + visible {
+            self.ui_state.scroll_offset = cursor + 1 - visible;
+        }
+    }
+
+    /// PID under the process list highlight
+    pub fn process_at_cursor(&self) -> Option<u32> {
+        ProcessListWidget::sorted_processes(self)
+            .get(self.ui_state.process_cursor)
+            .map(|(pid, _)| *pid)
+    }
+
+    /// Mark or unmark the highlighted process
+    pub fn toggle_process_mark(&mut self) {
+        if let Some(pid) = self.process_at_cursor()
+            && !self.ui_state.marked_pids.remove(&pid)
+        {
+            self.ui_state.marked_pids.insert(pid);
+        }
+    }
+
+    /// Marked processes, or the highlighted one if nothing is marked
+    pub fn target_pids(&self) -> Vec<u32> {
+        if self.ui_state.marked_pids.is_empty() {
+            self.process_at_cursor().into_iter().collect()
+        } else {
+            self.ui_state.marked_pids.iter().copied().collect()
+        }
+    }
+
+    /// Mark or unmark the selected cgroup for the next cgroup action
+    pub fn toggle_cgroup_mark(&mut self) {
+        self.ui_state.tree_state.toggle_mark();
+    }
+
+    /// Drop every cgroup and process mark
+    pub fn clear_marks(&mut self) {
+        let count = self.ui_state.tree_state.marked.len() + self.ui_state.marked_pids.len();
+        self.ui_state.tree_state.marked.clear();
+        self.ui_state.marked_pids.clear();
+        if count > 0 {
+            self.show_info(format!("Cleared {} mark(s)", count));
+        }
+    }
+
+    /// Marked cgroups, or the selected one if nothing is marked
+    pub fn target_cgroups(&self) -> Vec<PathBuf> {
+        let marked = self.ui_state.tree_state.marked_paths();
+        if marked.is_empty() {
+            self.ui_state
+                .selected_cgroup
+                .iter()
+                .map(PathBuf::from)
+                .collect()
+        } else {
+            marked.into_iter().map(PathBuf::from).collect()
+        }
+    }
+
+    pub fn set_control_sender(&mut self, control_tx: Sender<CollectionCommand>) {
+        self.control_sender = Some(control_tx);
+    }
+
+    fn send_collection_command(&mut self, command: CollectionCommand) {
+        let Some(sender) = &self.control_sender else {
+            log::debug!("No collection thread attached, dropping {:?}", command);
+            return;
+        };
+
+        if sender.send(command).is_err() {
+            log::warn!("Collection thread is gone, dropping {:?}", command);
+            self.show_error("Collection thread is not running".to_string());
+        }
+    }
+
+    pub fn update_interval(&self) -> Duration {
+        Duration::from_millis(self.config.update_interval_ms)
+    }
+
+    /// Ask the collection thread for a fresh sample right away
+    pub fn request_refresh(&mut self) {
+        self.send_collection_command(CollectionCommand::Refresh);
+    }
+
+    /// Pause or resume periodic sampling
+    pub fn toggle_pause(&mut self) {
+        self.ui_state.sampling_paused = !self.ui_state.sampling_paused;
+
+        if self.ui_state.sampling_paused {
+            self.send_collection_command(CollectionCommand::Pause);
+            self.show_info("Sampling paused (p: resume, r: sample once)".to_string());
+        } else {
+            self.send_collection_command(CollectionCommand::Resume);
+            self.show_info("Sampling resumed".to_string());
+        }
+    }
+
+    /// Step the update interval up or down through `UPDATE_INTERVAL_STEPS_MS`
+    pub fn adjust_update_interval(&mut self, faster: bool) {
+        let current = self.config.update_interval_ms;
+        let next = if faster {
+            UPDATE_INTERVAL_STEPS_MS
+                .iter()
+                .rev()
+                .find(|&&step| step < current)
+                .copied()
+                .unwrap_or(current)
+        } else {
+            UPDATE_INTERVAL_STEPS_MS
+                .iter()
+                .find(|&&step| step > current)
+                .copied()
+                .unwrap_or(current)
+        };
+
+        if next == current {
+            self.show_warning(format!("Update interval already at {}ms", current));
+            return;
+        }
+
+        self.config.update_interval_ms = next;
+        self.send_collection_command(CollectionCommand::SetInterval(self.update_interval()));
+        self.show_info(format!("Update interval: {}ms", next));
+    }
+
+    /// Write the marked cgroups, or every cgroup visible in the tree, to a CSV
+    /// file in the export directory; returns the file written
+    pub fn export_csv(&mut self) -> Option<PathBuf> {
+        let metrics = self.cgroup_data.metrics.as_deref()?;
+        let tree_state = &self.ui_state.tree_state;
+
+        let mut paths = tree_state.marked_paths();
+        if paths.is_empty() {
+            paths = tree_state
+                .visible_nodes
+                .iter()
+                .filter_map(|key| tree_state.nodes.get(key))
+                .map(|node| node.path.clone())
+                .collect();
+        }
+        let paths: Vec<&str> = paths.iter().map(String::as_str).collect();
+
+        let columns = &self.config.csv_columns;
+        let mut content = csv_header(columns);
+        content.push_str(&csv_rows(
+            columns,
+            &paths,
+            &self.config.cgroup_root,
+            metrics,
+            self.cgroup_data.previous.as_deref(),
+        ));
+        let file = self.config.export_dir.join(format!(
+            "cgtop-{}.csv",
+            metrics.collected_at.format("%Y%m%d-%H%M%S")
+        ));
+
+        match std::fs::write(&file, content) {
+            Ok(()) => {
+                log::info!("Exported {} cgroups to {}", paths.len(), file.display());
+                self.show_success(format!(
+                    "Exported {} cgroups to {}",
+                    paths.len(),
+                    file.display()
+                ));
+                Some(file)
+            }
+            Err(e) => {
+                log::error!("Failed to export to {}: {}", file.display(), e);
+                self.show_error(format!("Cannot write {}: {}", file.display(), e));
+                None
+            }
+        }
+    }
+
+    /// Open a confirmation dialog for removing the cgroup tree at `path`
+    pub fn request_delete(&mut self, title: String, path: &Path) {
+        self.request_delete_all(title, &[path.to_path_buf()]);
+    }
+
+    /// Open a single confirmation for removing every marked cgroup tree
+    pub fn request_delete_marked(&mut self) {
+        let marked: Vec<PathBuf> = self
+            .ui_state
+            .tree_state
+            .marked_paths()
+            .into_iter()
+            .map(PathBuf::from)
+            .collect();
+        if marked.is_empty() {
+            return;
+        }
+        self.request_delete_all(format!("Delete {} marked cgroups", marked.len()), &marked);
+    }
+
+    fn request_delete_all(&mut self, title: String, paths: &[PathBuf]) {
+        if !self.allow_write("delete cgroups") {
+            return;
+        }
+
+        // A marked cgroup inside another marked tree goes away with that tree
+        let roots: Vec<&PathBuf> = paths
+            .iter()
+            .filter(|path| {
+                !paths
+                    .iter()
+                    .any(|other| other != *path && path.starts_with(other))
+            })
+            .collect();
+
+        let mut plans = Vec::new();
+        for path in roots {
+            log::info!("Delete requested for cgroup: {}", path.display());
+
+            // One unsafe target refuses the whole batch
+            if let Err(reason) = self.config.safety.check_removal(path) {
+                log::error!(
+                    "Refusing to delete unsafe path {}: {}",
+                    path.display(),
+                    reason
+                );
+                self.show_error(format!("Refusing to delete {}: {}", path.display(), reason));
+                return;
+            }
+
+            match plan_removal(path) {
+                Ok(plan) => plans.push(plan),
+                Err(e) => {
+                    log::error!("Failed to inspect cgroup {}: {}", path.display(), e);
+                    self.show_error(format!("Cannot read {}: {}", path.display(), e));
+                    return;
+                }
+            }
+        }
+
+        self.ui_state.dialog = Some(Dialog::ConfirmDelete(ConfirmDelete::new(title, plans)));
+    }
+
+    /// Forward a key to the open dialog and carry out the action once confirmed
+    pub fn handle_dialog_key(&mut self, key: KeyEvent) {
+        let Some(dialog) = &mut self.ui_state.dialog else {
+            return;
+        };
+
+        match dialog.handle_key(key) {
+            DialogOutcome::Pending => {}
+            DialogOutcome::Cancelled => {
+                log::info!("Dialog cancelled");
+                self.ui_state.dialog = None;
+            }
+            DialogOutcome::Confirmed => match self.ui_state.dialog.take() {
+                Some(Dialog::ConfirmDelete(confirm)) => {
+                    self.delete_cgroup_trees(&confirm.plans, confirm.members);
+                }
+                Some(Dialog::ConfirmKill(confirm)) => self.kill_cgroups(&confirm.cgroups),
+                Some(Dialog::ConfirmFreeze(confirm)) => {
+                    self.freeze_cgroups(&confirm.cgroups, confirm.freeze);
+                }
+                Some(Dialog::EditLimits(editor)) => {
+                    if let Err(e) = self.apply_limit_edit(&editor) {
+                        // Keep the dialog open so the value can be corrected
+                        self.show_error(format!("{}: {:#}", editor.selected_file(), e));
+                        self.ui_state.dialog = Some(Dialog::EditLimits(editor));
+                    }
+                }
+                Some(Dialog::NewChild(new_child)) => {
+                    if let Err(e) = self.create_child_cgroup(&new_child) {
+                        self.show_error(format!("{:#}", e));
+                        self.ui_state.dialog = Some(Dialog::NewChild(new_child));
+                    }
+                }
+                Some(Dialog::MoveProcesses(chooser)) => {
+                    if let Some(target) = chooser.target() {
+                        self.move_pids(&chooser.pids, Path::new(target));
+                    }
+                }
+                Some(Dialog::MoveResult(_)) | Some(Dialog::SignalResult(_)) => {}
+                Some(Dialog::SignalMenu(menu)) => match menu.signal() {
+                    Ok(signal) => self.request_signal(menu.pids, signal),
+                    Err(e) => {
+                        self.show_error(e);
+                        self.ui_state.dialog = Some(Dialog::SignalMenu(menu));
+                    }
+                },
+                Some(Dialog::History(mut view)) => {
+                    self.undo_last_change();
+                    // The undo is logged as a new entry
+                    view.entries = self.audit.entries().len();
+                    self.ui_state.dialog = Some(Dialog::History(view));
+                }
+                Some(Dialog::ConfirmSignal(confirm)) => {
+                    self.signal_pids(&confirm.pids, confirm.signal);
+                }
+                Some(Dialog::SubtreeControl(mut editor)) => {
+                    self.toggle_subtree_controller(&editor);
+                    editor.reload();
+                    self.ui_state.dialog = Some(Dialog::SubtreeControl(editor));
+                }
+                None => {}
+            },
+        }
+    }
+
+    /// Freeze the selected cgroup, or thaw it if it is frozen; with marked
+    /// cgroups, ask once and then freeze them all (or thaw them if all are frozen)
+    pub fn toggle_freeze(&mut self) {
+        if !self.allow_write("freeze or thaw cgroups") {
+            return;
+        }
+        let cgroups = self.target_cgroups();
+        if cgroups.is_empty() {
+            return;
+        }
+
+        for cgroup in &cgroups {
+            if let Err(reason) = self.config.safety.check_process_control(cgroup) {
+                self.show_error(format!(
+                    "Refusing to freeze {}: {}",
+                    cgroup.display(),
+                    reason
+                ));
+                return;
+            }
+            if !cgroup.join("cgroup.freeze").exists() {
+                self.show_warning(format!("{} has no cgroup.freeze", cgroup.display()));
+                return;
+            }
+        }
+
+        let freeze = !cgroups.iter().all(|cgroup| is_frozen(cgroup));
+        if cgroups.len() == 1 {
+            self.freeze_cgroups(&cgroups, freeze);
+        } else {
+            self.ui_state.dialog = Some(Dialog::ConfirmFreeze(ConfirmFreeze { cgroups, freeze }));
+        }
+    }
+
+    fn freeze_cgroups(&mut self, cgroups: &[PathBuf], freeze: bool) {
+        if !self.allow_write("freeze or thaw cgroups") {
+            return;
+        }
+        let action = if freeze { "freeze" } else { "thaw" };
+        let targets = describe_cgroups(cgroups);
+        if self.config.dry_run {
+            for cgroup in cgroups {
+                log::info!("[dry-run] would {} {}", action, cgroup.display());
+            }
+            self.show_info(format!("Dry run: would {} {}", action, targets));
+            return;
+        }
+
+        let new = if freeze { "1" } else { "0" };
+        let mut written = Vec::new();
+        let mut failures = Vec::new();
+        for cgroup in cgroups {
+            // A marked set can mix frozen and thawed cgroups, so undo needs
+            // what each one was set to rather than the opposite of `freeze`
+            let old = std::fs::read_to_string(cgroup.join("cgroup.freeze")).ok();
+            let result = write_freeze(cgroup, freeze);
+            self.audit.record(AuditEntry::new(
+                cgroup,
+                "cgroup.freeze",
+                old,
+                new,
+                result.as_ref().map(|_| ()).map_err(ToString::to_string),
+            ));
+
+            match result {
+                Ok(()) => written.push(cgroup.clone()),
+                Err(e) => {
+                    log::error!("Failed to {} {}: {}", action, cgroup.display(), e);
+                    failures.push(format!("{}: {}", cgroup.display(), e));
+                }
+            }
+        }
+
+        if !failures.is_empty() {
+            self.show_error(format!("Failed to {} {}", action, failures.join(", ")));
+        }
+        if !written.is_empty() {
+            // Stopping every task takes a moment; the tick picks up the result
+            // from cgroup.events instead of blocking the UI here
+            self.ui_state.pending_freezes.push(PendingFreeze {
+                targets: describe_cgroups(&written),
+                cgroups: written,
+                freeze,
+                deadline: Instant::now() + FREEZE_TIMEOUT,
+            });
+            self.poll_pending_freezes();
+        }
+        self.request_refresh();
+    }
+
+    /// Report freezes and thaws that `cgroup.events` confirmed, or that took
+    /// longer than `FREEZE_TIMEOUT`; called on every tick
+    pub fn poll_pending_freezes(&mut self) {
+        let now = Instant::now();
+        for mut pending in std::mem::take(&mut self.ui_state.pending_freezes) {
+            let action = if pending.freeze { "freeze" } else { "thaw" };
+            pending
+                .cgroups
+                .retain(|cgroup| is_frozen(cgroup) != pending.freeze);
+
+            if pending.cgroups.is_empty() {
+                log::info!(
+                    "{} {}",
+                    if pending.freeze { "Froze" } else { "Thawed" },
+                    pending.targets
+                );
+                self.show_success(format!(
+                    "{}: {}",
+                    if pending.freeze { "Frozen" } else { "Thawed" },
+                    pending.targets
+                ));
+            } else if now >= pending.deadline {
+                let waiting = describe_cgroups(&pending.cgroups);
+                log::warn!("Timed out waiting to {} {}", action, waiting);
+                self.show_warning(format!(
+                    "Asked to {} {}, the kernel has not finished yet",
+                    action, waiting
+                ));
+            } else {
+                self.ui_state.pending_freezes.push(pending);
+            }
+        }
+    }
+
+    /// Ask for confirmation before killing every process in the selected (or marked) cgroups
+    pub fn request_kill(&mut self) {
+        if !self.allow_write("kill processes") {
+            return;
+        }
+        let cgroups = self.target_cgroups();
+        if cgroups.is_empty() {
+            return;
+        }
+
+        let mut processes = Vec::new();
+        for cgroup in &cgroups {
+            if let Err(reason) = self.config.safety.check_process_control(cgroup) {
+                self.show_error(format!("Refusing to kill {}: {}", cgroup.display(), reason));
+                return;
+            }
+
+            match plan_removal(cgroup) {
+                Ok(plan) => {
+                    processes.extend(plan.cgroups.into_iter().flat_map(|cgroup| cgroup.processes))
+                }
+                Err(e) => {
+                    self.show_error(format!("Cannot read {}: {}", cgroup.display(), e));
+                    return;
+                }
+            }
+        }
+
+        if processes.is_empty() {
+            self.show_info(format!("No processes in {}", describe_cgroups(&cgroups)));
+            return;
+        }
+        // Nested marks would list the same process twice
+        processes.sort();
+        processes.dedup();
+        self.ui_state.dialog = Some(Dialog::ConfirmKill(ConfirmKill { cgroups, processes }));
+    }
+
+    fn kill_cgroups(&mut self, cgroups: &[PathBuf]) {
+        if !self.allow_write("kill processes") {
+            return;
+        }
+        let targets = describe_cgroups(cgroups);
+
+        if self.config.dry_run {
+            for cgroup in cgroups {
+                log::info!("[dry-run] would kill every process in {}", cgroup.display());
+            }
+            self.show_info(format!("Dry run: would kill {}", targets));
+            return;
+        }
+
+        let mut signalled = 0;
+        let mut failures = Vec::new();
+        for cgroup in cgroups {
+            let result = kill_all(cgroup);
+            match &result {
+                Ok(KillMethod::CgroupKill) => {
+                    self.audit
+                        .record(AuditEntry::new(cgroup, "cgroup.kill", None, "1", Ok(())));
+                }
+                Err(e) => {
+                    self.audit.record(AuditEntry::new(
+                        cgroup,
+                        "cgroup.kill",
+                        None,
+                        "1",
+                        Err(e.to_string()),
+                    ));
+                }
+                // Plain kill(2) calls, nothing was written
+                Ok(KillMethod::Signalled { .. }) => {}
+            }
+
+            match result {
+                Ok(KillMethod::CgroupKill) => {
+                    log::info!("Killed {} through cgroup.kill", cgroup.display());
+                }
+                Ok(KillMethod::Signalled { processes }) => {
+                    log::info!(
+                        "Sent SIGKILL to {} processes in {}",
+                        processes,
+                        cgroup.display()
+                    );
+                    signalled += processes;
+                }
+                Err(e) => {
+                    log::error!("Failed to kill {}: {}", cgroup.display(), e);
+                    failures.push(format!("{}: {}", cgroup.display(), e));
+                }
+            }
+        }
+
+        if !failures.is_empty() {
+            self.show_error(format!("Kill failed for {}", failures.join(", ")));
+        } else if signalled > 0 {
+            self.show_success(format!(
+                "Sent SIGKILL to {} processes in {} (no cgroup.kill)",
+                signalled, targets
+            ));
+        } else {
+            self.show_success(format!("Killed all processes in {}", targets));
+        }
+        self.request_refresh();
+    }
+
+    /// Pick a target cgroup for the marked (or highlighted) processes
+    pub fn request_move_processes(&mut self) {
+        if !self.allow_write("move processes") {
+            return;
+        }
+        let pids = self.target_pids();
+        if pids.is_empty() {
+            return;
+        }
+        self.open_move_chooser(pids);
+    }
+
+    /// Pick a target cgroup for every process of the selected cgroup
+    pub fn request_move_all(&mut self) {
+        if !self.allow_write("move processes") {
+            return;
+        }
+        let Some(selected) = self.ui_state.selected_cgroup.clone() else {
+            return;
+        };
+
+        let pids = read_procs(Path::new(&selected));
+        if pids.is_empty() {
+            self.show_info(format!("No processes in {}", selected));
+            return;
+        }
+        self.open_move_chooser(pids);
+    }
+
+    fn open_move_chooser(&mut self, pids: Vec<u32>) {
+        let tree = self.ui_state.tree_state.clone();
+        self.ui_state.dialog = Some(Dialog::MoveProcesses(MoveChooser::new(pids, tree)));
+    }
+
+    fn move_pids(&mut self, pids: &[u32], target: &Path) {
+        if !self.allow_write("move processes") {
+            return;
+        }
+        if self.config.dry_run {
+            log::info!(
+                "[dry-run] would move PIDs {:?} to {}",
+                pids,
+                target.display()
+            );
+            self.show_info(format!(
+                "Dry run: would move {} processes to {}",
+                pids.len(),
+                target.display()
+            ));
+            return;
+        }
+
+        let report = move_processes(pids, target);
+        self.audit_moves(&report);
+        log::info!(
+            "Moved {}/{} processes to {}",
+            report.moved_count(),
+            pids.len(),
+            target.display()
+        );
+
+        if report.failures().next().is_some() {
+            self.ui_state.dialog = Some(Dialog::MoveResult(report));
+        } else {
+            self.show_success(format!(
+                "Moved {} processes to {}",
+                report.moved_count(),
+                target.display()
+            ));
+            self.ui_state.marked_pids.clear();
+        }
+        self.request_refresh();
+    }
+
+    /// Record one `cgroup.procs` write per PID of `report`
+    fn audit_moves(&mut self, report: &MoveReport) {
+        for pid_move in &report.moves {
+            self.audit.record(AuditEntry::new(
+                &report.target,
+                "cgroup.procs",
+                None,
+                &pid_move.pid.to_string(),
+                pid_move.result.map_err(|errno| errno.desc().to_string()),
+            ));
+        }
+    }
+
+    /// Choose a signal for the marked (or highlighted) processes
+    pub fn open_signal_menu(&mut self) {
+        if !self.allow_write("send signals") {
+            return;
+        }
+        let pids = self.target_pids();
+        if pids.is_empty() {
+            return;
+        }
+        self.ui_state.dialog = Some(Dialog::SignalMenu(SignalMenu::new(pids)));
+    }
+
+    /// Choose a signal for every process in the selected (or marked) cgroups and below
+    pub fn open_cgroup_signal_menu(&mut self) {
+        if !self.allow_write("send signals") {
+            return;
+        }
+        let cgroups = self.target_cgroups();
+        let mut pids = Vec::new();
+        for cgroup in &cgroups {
+            if let Err(reason) = self.config.safety.check_process_control(cgroup) {
+                self.show_error(format!(
+                    "Refusing to signal {}: {}",
+                    cgroup.display(),
+                    reason
+                ));
+                return;
+            }
+            match plan_removal(cgroup) {
+                Ok(plan) => pids.extend(
+                    plan.cgroups
+                        .iter()
+                        .flat_map(|cgroup| cgroup.processes.iter().map(|(pid, _)| *pid)),
+                ),
+                Err(e) => {
+                    self.show_error(format!("Cannot read {}: {}", cgroup.display(), e));
+                    return;
+                }
+            }
+        }
+
+        pids.sort_unstable();
+        pids.dedup();
+        if pids.is_empty() {
+            if !cgroups.is_empty() {
+                self.show_info(format!("No processes in {}", describe_cgroups(&cgroups)));
+            }
+            return;
+        }
+        self.ui_state.dialog = Some(Dialog::SignalMenu(SignalMenu::new(pids)));
+    }
+
+    /// Send `signal`, asking once more if init or kernel threads are among `pids`
+    fn request_signal(&mut self, pids: Vec<u32>, signal: Signal) {
+        let warnings: Vec<_> = pids
+            .iter()
+            .filter_map(|&pid| signal_warning(pid).map(|reason| (pid, reason)))
+            .collect();
+
+        if warnings.is_empty() {
+            self.signal_pids(&pids, signal);
+        } else {
+            self.ui_state.dialog = Some(Dialog::ConfirmSignal(ConfirmSignal {
+                pids,
+                signal,
+                warnings,
+            }));
+        }
+    }
+
+    fn signal_pids(&mut self, pids: &[u32], signal: Signal) {
+        if !self.allow_write("send signals") {
+            return;
+        }
+        if self.config.dry_run {
+            log::info!("[dry-run] would send {} to PIDs {:?}", signal, pids);
+            self.show_info(format!(
+                "Dry run: would send {} to {} processes",
+                signal,
+                pids.len()
+            ));
+            return;
+        }
+
+        let report = send_signal(pids, signal);
+        if report.failures().next().is_some() {
+            self.ui_state.dialog = Some(Dialog::SignalResult(report));
+        } else {
+            self.show_success(format!(
+                "Sent {} to {} processes",
+                signal,
+                report.delivered_count()
+            ));
+            self.ui_state.marked_pids.clear();
+        }
+        self.request_refresh();
+    }
+
+    /// Suspend the UI and run `$SHELL` inside the selected cgroup
+    pub fn request_shell(&mut self) {
+        if !self.allow_write("start processes in a cgroup") {
+            return;
+        }
+        let Some(selected) = self.ui_state.selected_cgroup.clone() else {
+            return;
+        };
+        self.ui_state.pending_shell = Some(PathBuf::from(selected));
+    }
+
+    /// Ask for the name of a new cgroup below the selected one
+    pub fn request_new_child(&mut self) {
+        if !self.allow_write("create cgroups") {
+            return;
+        }
+        let Some(selected) = self.ui_state.selected_cgroup.clone() else {
+            return;
+        };
+
+        self.ui_state.dialog = Some(Dialog::NewChild(NewChild {
+            parent: PathBuf::from(selected),
+            input: String::new(),
+        }));
+    }
+
+    /// Create the cgroup named in `new_child`
+    fn create_child_cgroup(&mut self, new_child: &NewChild) -> anyhow::Result<()> {
+        if self.config.read_only {
+            anyhow::bail!("Read-only mode: cannot create cgroups");
+        }
+        let name = new_child.input.as_str();
+        validate_cgroup_name(name).map_err(|reason| anyhow::anyhow!("Invalid name: {}", reason))?;
+
+        let path = new_child.parent.join(name);
+        if self.config.dry_run {
+            log::info!("[dry-run] would create cgroup {}", path.display());
+            self.show_info(format!("Dry run: would create {}", path.display()));
+            return Ok(());
+        }
+
+        let result = std::fs::create_dir(&path);
+        self.audit.record(AuditEntry::new(
+            &path,
+            audit::MKDIR,
+            None,
+            "",
+            result.as_ref().map_err(ToString::to_string).copied(),
+        ));
+        result.map_err(|e| {
+            log::error!("Failed to create cgroup {}: {}", path.display(), e);
+            anyhow::anyhow!("Cannot create {}: {}", path.display(), e)
+        })?;
+
+        log::info!("Created cgroup {}", path.display());
+        self.show_success(format!("Created {}", path.display()));
+        self.request_refresh();
+        Ok(())
+    }
+
+    /// Open the `cgroup.subtree_control` editor on the selected cgroup
+    pub fn open_subtree_control_editor(&mut self) {
+        if !self.allow_write("change controllers") {
+            return;
+        }
+        let Some(selected) = self.ui_state.selected_cgroup.clone() else {
+            return;
+        };
+
+        match SubtreeControlEditor::load(Path::new(&selected)) {
+            Some(editor) => self.ui_state.dialog = Some(Dialog::SubtreeControl(editor)),
+            None => self.show_warning(format!("{} has no cgroup.subtree_control", selected)),
+        }
+    }
+
+    fn toggle_subtree_controller(&mut self, editor: &SubtreeControlEditor) {
+        let Some((name, enabled)) = editor.selected_controller() else {
+            return;
+        };
+        let value = format!("{}{}", if *enabled { "-" } else { "+" }, name);
+
+        let Err(e) = self.write_control_file(&editor.cgroup, "cgroup.subtree_control", &value)
+        else {
+            return;
+        };
+
+        let errno = e
+            .downcast_ref::<std::io::Error>()
+            .and_then(std::io::Error::raw_os_error)
+            .map(Errno::from_raw);
+        let message = match errno {
+            Some(Errno::EBUSY) if !enabled => format!(
+                "Cannot enable {}: {} has processes of its own. cgroup v2 only hands \
+                 controllers to children of cgroups without member processes \
+                 (\"no internal processes\"); move them into a leaf child first",
+                name,
+                editor.cgroup.display()
+            ),
+            Some(Errno::EBUSY) => format!(
+                "Cannot disable {}: a child cgroup still enables it in its own subtree_control",
+                name
+            ),
+            _ => format!("cgroup.subtree_control: {:#}", e),
+        };
+        self.show_error(message);
+    }
+
+    /// cgroup whose values a limit editor shows, and every cgroup it writes to:
+    /// the selection if it is marked (or nothing is), else the first marked cgroup
+    fn limit_targets(&self) -> Option<(PathBuf, Vec<PathBuf>)> {
+        let targets = self.target_cgroups();
+        let shown = self
+            .ui_state
+            .selected_cgroup
+            .as_ref()
+            .map(PathBuf::from)
+            .filter(|selected| targets.contains(selected))
+            .or_else(|| targets.first().cloned())?;
+        Some((shown, targets))
+    }
+
+    /// Open the memory limit editor on the selected (or marked) cgroups
+    pub fn open_memory_editor(&mut self) {
+        if !self.allow_write("edit limits") {
+            return;
+        }
+        let Some((cgroup, targets)) = self.limit_targets() else {
+            return;
+        };
+
+        match LimitEditor::memory(&cgroup, &self.config.cgroup_root) {
+            Some(editor) => {
+                self.ui_state.dialog = Some(Dialog::EditLimits(editor.with_targets(&targets)));
+            }
+            None => self.show_warning(format!(
+                "Memory controller not enabled for {}",
+                cgroup.display()
+            )),
+        }
+    }
+
+    /// Open the CPU limit editor on the selected (or marked) cgroups
+    pub fn open_cpu_editor(&mut self) {
+        if !self.allow_write("edit limits") {
+            return;
+        }
+        let Some((cgroup, targets)) = self.limit_targets() else {
+            return;
+        };
+
+        // Without it the cpu.* files are missing or writes have no effect
+        let parent = cgroup.parent().unwrap_or(&cgroup).to_path_buf();
+        let enabled_in_parent = controller_enabled(&parent, "cpu");
+        let subtree_control = parent.join("cgroup.subtree_control");
+
+        match LimitEditor::cpu(&cgroup) {
+            Some(editor) => {
+                if !enabled_in_parent {
+                    self.show_warning(format!(
+                        "cpu is not enabled in {}",
+                        subtree_control.display()
+                    ));
+                }
+                self.ui_state.dialog = Some(Dialog::EditLimits(editor.with_targets(&targets)));
+            }
+            None => self.show_warning(format!(
+                "CPU controller not enabled for {} (see {})",
+                cgroup.display(),
+                subtree_control.display()
+            )),
+        }
+    }
+
+    /// Open the `pids.max` editor on the selected (or marked) cgroups
+    pub fn open_pids_editor(&mut self) {
+        if !self.allow_write("edit limits") {
+            return;
+        }
+        let Some((cgroup, targets)) = self.limit_targets() else {
+            return;
+        };
+
+        match LimitEditor::pids(&cgroup, &self.config.cgroup_root) {
+            Some(editor) => {
+                self.ui_state.dialog = Some(Dialog::EditLimits(editor.with_targets(&targets)));
+            }
+            None => self.show_warning(format!(
+                "pids controller not enabled for {}",
+                cgroup.display()
+            )),
+        }
+    }
+
+    /// Open the `io.max`/`io.weight` editor on the selected (or marked) cgroups
+    pub fn open_io_editor(&mut self) {
+        if !self.allow_write("edit limits") {
+            return;
+        }
+        let Some((cgroup, targets)) = self.limit_targets() else {
+            return;
+        };
+
+        let devices = list_block_devices(Path::new("/sys/block"));
+        if devices.is_empty() {
+            self.show_warning("No block devices found in /sys/block".to_string());
+            return;
+        }
+
+        match LimitEditor::io(&cgroup, devices) {
+            Some(editor) => {
+                self.ui_state.dialog = Some(Dialog::EditLimits(editor.with_targets(&targets)));
+            }
+            None => self.show_warning(format!(
+                "io controller not enabled for {}",
+                cgroup.display()
+            )),
+        }
+    }
+
+    /// Write the edited value to every target of `editor`, stopping at the first rejection
+    fn apply_limit_edit(&mut self, editor: &LimitEditor) -> anyhow::Result<()> {
+        let mut written = None;
+        for cgroup in &editor.targets {
+            let result = self.apply_limit_to(editor, cgroup);
+            written = Some(if editor.targets.len() > 1 {
+                result.with_context(|| cgroup.display().to_string())?
+            } else {
+                result?
+            });
+        }
+
+        if let Some((file, value)) = written {
+            if editor.targets.len() > 1 {
+                self.notify_written(
+                    file,
+                    &format!("{} on {} cgroups", value, editor.targets.len()),
+                );
+            } else {
+                self.notify_written(file, &value);
+            }
+        }
+        Ok(())
+    }
+
+    /// Parse the edited value for `cgroup` and write it; returns the file and the value written
+    fn apply_limit_to(
+        &mut self,
+        editor: &LimitEditor,
+        cgroup: &Path,
+    ) -> anyhow::Result<(&'static str, String)> {
+        match editor.kind {
+            LimitKind::Memory => {
+                let knob = MemoryKnob::ALL[editor.selected];
+                let parent_limit = cgroup.parent().and_then(|parent| {
+                    effective_limit(parent, &self.config.cgroup_root, knob.limit_file())
+                });
+
+                let value = parse_memory_value(&editor.input, parent_limit)?;
+                validate_against_parent(value, parent_limit)?;
+                self.write_audited(cgroup, knob.file_name(), &value.to_string(), None)?;
+
+                let usage = read_counter(cgroup, knob.usage_file());
+                if let (LimitValue::Value(limit), Some(usage)) = (value, usage)
+                    && matches!(knob, MemoryKnob::Max | MemoryKnob::High)
+                    && limit < usage
+                {
+                    self.show_warning(format!(
+                        "{} is below current usage, the kernel will reclaim memory",
+                        knob.file_name()
+                    ));
+                }
+                Ok((knob.file_name(), value.to_string()))
+            }
+            LimitKind::Cpu => {
+                let knob = CpuKnob::ALL[editor.selected];
+                let period =
+                    read_cpu_max(cgroup).map_or(DEFAULT_CPU_PERIOD_USEC, |(_, period)| period);
+
+                let value = knob.parse(&editor.input, period)?;
+                self.write_audited(cgroup, knob.file_name(), &value, None)?;
+                Ok((knob.file_name(), value))
+            }
+            LimitKind::Pids => {
+                let parent_limit = cgroup.parent().and_then(|parent| {
+                    effective_limit(parent, &self.config.cgroup_root, "pids.max")
+                });
+
+                let value = parse_pids_max(&editor.input)?;
+                validate_against_parent(value, parent_limit)?;
+                self.write_audited(cgroup, "pids.max", &value.to_string(), None)?;
+                Ok(("pids.max", value.to_string()))
+            }
+            LimitKind::Io => {
+                let Some(device) = editor.selected_device() else {
+                    anyhow::bail!("no device selected");
+                };
+                let file = editor.selected_file();
+
+                let value = match file {
+                    "io.weight" => parse_io_weight(&editor.input, &device.dev)?,
+                    _ => parse_io_max(&editor.input, &device.dev)?,
+                };
+                self.write_audited(cgroup, file, &value, None)?;
+                Ok((file, value))
+            }
+        }
+    }
+
+    /// Write `value` to a control file of `cgroup`, or only log it in dry-run mode
+    fn write_control_file(&mut self, cgroup: &Path, file: &str, value: &str) -> anyhow::Result<()> {
+        self.write_audited(cgroup, file, value, None)?;
+        self.notify_written(file, value);
+        Ok(())
+    }
+
+    fn notify_written(&mut self, file: &str, value: &str) {
+        if self.config.dry_run {
+            self.show_info(format!("Dry run: {} = {}", file, value));
+        } else {
+            self.show_success(format!("{} = {}", file, value));
+        }
+    }
+
+    /// Show the writes made during this session
+    pub fn open_history(&mut self) {
+        let entries = self.audit.entries().len();
+        self.ui_state.dialog = Some(Dialog::History(HistoryView::new(entries)));
+    }
+
+    /// Revert the newest reversible change recorded in the audit log
+    pub fn undo_last_change(&mut self) {
+        if !self.allow_write("undo changes") {
+            return;
+        }
+        let Some(index) = self.audit.last_undoable() else {
+            self.show_info("Nothing to undo".to_string());
+            return;
+        };
+        let entry = self.audit.entries()[index].clone();
+        let Some(value) = entry.undo_value() else {
+            return;
+        };
+
+        log::info!(
+            "Undoing {} = {:?} on {}",
+            entry.file,
+            entry.new,
+            entry.path.display()
+        );
+        match self.write_audited(&entry.path, &entry.file, &value, Some(index)) {
+            Ok(()) => self.notify_written(&entry.file, &value),
+            Err(e) => self.show_error(format!("Undo failed: {:#}", e)),
+        }
+    }
+
+    fn write_audited(
+        &mut self,
+        cgroup: &Path,
+        file: &str,
+        value: &str,
+        undo_of: Option<usize>,
+    ) -> anyhow::Result<()> {
+        // Last line of defence, whichever action got here
+        if self.config.read_only {
+            anyhow::bail!("Read-only mode: {} was not written", file);
+        }
+        let target = cgroup.join(file);
+
+        if self.config.dry_run {
+            log::info!("[dry-run] would write {:?} to {}", value, target.display());
+            return Ok(());
+        }
+
+        let old = std::fs::read_to_string(&target).ok();
+        let result = std::fs::write(&target, value);
+        self.audit.record(AuditEntry {
+            undo_of,
+            ..AuditEntry::new(
+                cgroup,
+                file,
+                old,
+                value,
+                result.as_ref().map_err(ToString::to_string).copied(),
+            )
+        });
+
+        // Keep the io::Error in the chain so callers can look at the errno
+        result.map_err(|e| {
+            log::error!("Failed to write {:?} to {}: {}", value, target.display(), e);
+            anyhow::Error::new(e).context(format!("kernel rejected {:?}", value))
+        })?;
+
+        log::info!("Wrote {:?} to {}", value, target.display());
+        self.request_refresh();
+        Ok(())
+    }
+
+    fn delete_cgroup_trees(&mut self, plans: &[RemovalPlan], members: MemberAction) {
+        if !self.allow_write("delete cgroups") {
+            return;
+        }
+        let targets = describe_cgroups(
+            &plans
+                .iter()
+                .map(|plan| plan.target.clone())
+                .collect::<Vec<_>>(),
+        );
+        let cgroup_count: usize = plans.iter().map(|plan| plan.cgroups.len()).sum();
+
+        if self.config.dry_run {
+            for plan in plans {
+                log::info!(
+                    "[dry-run] would apply {:?} to the members of {}",
+                    members.policy_for(&plan.target),
+                    plan.target.display()
+                );
+            }
+            for cgroup in plans.iter().flat_map(|plan| &plan.cgroups) {
+                log::info!(
+                    "[dry-run] would remove {} ({} processes)",
+                    cgroup.path.display(),
+                    cgroup.processes.len()
+                );
+            }
+            self.show_info(format!(
+                "Dry run: would delete {} ({} cgroups)",
+                targets, cgroup_count
+            ));
+            return;
+        }
+
+        let mut removed = 0;
+        let mut failures = Vec::new();
+        for plan in plans {
+            let options = RemoveOptions {
+                member_policy: members.policy_for(&plan.target),
+                ..RemoveOptions::default()
+            };
+            let report = remove_cgroup_tree(&plan.target, &options);
+            match &report.eviction {
+                Some(Eviction::Killed(Ok(KillMethod::CgroupKill))) => {
+                    self.audit.record(AuditEntry::new(
+                        &plan.target,
+                        "cgroup.kill",
+                        None,
+                        "1",
+                        Ok(()),
+                    ));
+                }
+                Some(Eviction::Killed(Err(e))) => {
+                    self.audit.record(AuditEntry::new(
+                        &plan.target,
+                        "cgroup.kill",
+                        None,
+                        "1",
+                        Err(e.clone()),
+                    ));
+                }
+                Some(Eviction::Migrated(moves)) => self.audit_moves(moves),
+                // Plain kill(2) calls or nothing at all, nothing was written
+                Some(Eviction::Killed(Ok(KillMethod::Signalled { .. }))) | None => {}
+            }
+            for entry in &report.entries {
+                let result = match &entry.outcome {
+                    RemovalOutcome::Removed { .. } => Ok(()),
+                    RemovalOutcome::Failed(reason) => Err(reason.clone()),
+                    // Never attempted
+                    RemovalOutcome::Populated | RemovalOutcome::Skipped => continue,
+                };
+                self.audit
+                    .record(AuditEntry::new(&entry.path, audit::RMDIR, None, "", result));
+            }
+
+            removed += report.removed_count();
+            if report.is_success() {
+                log::info!(
+                    "Successfully deleted cgroup tree: {}",
+                    plan.target.display()
+                );
+            } else {
+                log::error!(
+                    "Failed to delete cgroup tree {}: {}",
+                    plan.target.display(),
+                    report
+                );
+                failures.push(report.to_string());
+            }
+        }
+
+        if failures.is_empty() {
+            self.show_success(format!("Deleted: {} ({} cgroups)", targets, removed));
+        } else {
+            self.show_error(format!("Delete failed: {}", failures.join("; ")));
+        }
+        self.ui_state.tree_state.marked.clear();
+
+        // Show the result without waiting for the next scheduled sample
+        self.request_refresh();
+    }
+
+    /// Whether a mutating action may run; refuses and says so in read-only mode
+    fn allow_write(&mut self, action: &str) -> bool {
+        if self.config.read_only {
+            log::warn!("Read-only mode: refused to {}", action);
+            self.show_warning(format!("Read-only mode: cannot {}", action));
+            return false;
+        }
+        true
+    }
+
     pub fn show_error(&mut self, message: String) {
         self.notifications.add_error(message);
     }
@@ -124,3 +1576,17 @@ impl App {
         self.notifications.update();
     }
 }
+
+/// A single cgroup by path, or how many there are
+fn describe_cgroups(cgroups: &[PathBuf]) -> String {
+    match cgroups {
+        [cgroup] => cgroup.display().to_string(),
+        cgroups => format!("{} cgroups", cgroups.len()),
+    }
+}
+
+impl Default for App {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,192 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// File name of the audit log, kept in the same directory as the main log file
+pub const AUDIT_LOG_NAME: &str = "cgtop-audit.log";
+
+/// Pseudo file names for changes that are not writes to a control file
+pub const MKDIR: &str = "(mkdir)";
+pub const RMDIR: &str = "(rmdir)";
+
+/// One change cgtop made (or tried to make) to cgroupfs
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub timestamp: DateTime<Utc>,
+    pub user: String,
+    /// The cgroup directory
+    pub path: PathBuf,
+    /// Control file written, or `MKDIR`/`RMDIR`
+    pub file: String,
+    /// Content before the write, if it could be read
+    pub old: Option<String>,
+    pub new: String,
+    /// "ok" or the error the kernel returned
+    pub result: String,
+    /// Index of the entry this write reverted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub undo_of: Option<usize>,
+}
+
+impl AuditEntry {
+    pub fn new(
+        path: &Path,
+        file: &str,
+        old: Option<String>,
+        new: &str,
+        result: Result<(), String>,
+    ) -> Self {
+        Self {
+            timestamp: Utc::now(),
+            user: current_user(),
+            path: path.to_path_buf(),
+            file: file.to_string(),
+            old,
+            new: new.to_string(),
+            result: result.err().unwrap_or_else(|| "ok".to_string()),
+            undo_of: None,
+        }
+    }
+
+    pub fn succeeded(&self) -> bool {
+        self.result == "ok"
+    }
+
+    /// Value that restores the state before this write, if the write can be reverted
+    pub fn undo_value(&self) -> Option<String> {
+        if !self.succeeded() {
+            return None;
+        }
+
+        match self.file.as_str() {
+            // Toggles carry their own inverse
+            "cgroup.subtree_control" => {
+                if let Some(name) = self.new.strip_prefix('+') {
+                    Some(format!("-{}", name))
+                } else {
+                    self.new.strip_prefix('-').map(|name| format!("+{}", name))
+                }
+            }
+            "io.max" | "io.weight" => self.undo_device_line(),
+            "cgroup.freeze" | "pids.max" | "cpu.max" | "cpu.weight" | "cpu.max.burst"
+            | "memory.max" | "memory.high" | "memory.low" | "memory.min" | "memory.swap.max" => {
+                self.old.as_ref().map(|old| old.trim().to_string())
+            }
+            _ => None,
+        }
+    }
+
+    /// io files hold one line per device; restore the line of the device that was written
+    fn undo_device_line(&self) -> Option<String> {
+        let key = self.new.split_whitespace().next()?;
+        let old = self.old.as_deref().unwrap_or_default();
+
+        if let Some(line) = old
+            .lines()
+            .find(|line| line.split_whitespace().next() == Some(key))
+        {
+            return Some(line.trim().to_string());
+        }
+
+        // The device had no entry before, so put it back to the kernel defaults
+        match self.file.as_str() {
+            "io.max" => Some(format!("{} rbps=max wbps=max riops=max wiops=max", key)),
+            _ if key != "default" => Some(format!("{} default", key)),
+            _ => None,
+        }
+    }
+}
+
+/// Append-only record of writes, mirrored to a file when one is configured
+#[derive(Debug, Default)]
+pub struct AuditLog {
+    path: Option<PathBuf>,
+    entries: Vec<AuditEntry>,
+}
+
+impl AuditLog {
+    /// Record entries in memory only
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+
+    /// Append entries to `path`, creating it if needed
+    pub fn open(path: PathBuf) -> io::Result<Self> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        // Fail early rather than on the first write
+        OpenOptions::new().create(true).append(true).open(&path)?;
+
+        Ok(Self {
+            path: Some(path),
+            entries: Vec::new(),
+        })
+    }
+
+    /// Audit log path belonging to the main log file at `log_path`
+    pub fn path_for_log(log_path: &Path) -> PathBuf {
+        log_path.with_file_name(AUDIT_LOG_NAME)
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// Entries recorded during this session, oldest first
+    pub fn entries(&self) -> &[AuditEntry] {
+        &self.entries
+    }
+
+    /// Store `entry` and append it to the file; returns its index
+    pub fn record(&mut self, entry: AuditEntry) -> usize {
+        if let Some(path) = &self.path
+            && let Err(e) = append_entry(path, &entry)
+        {
+            log::error!("Failed to append to audit log {}: {}", path.display(), e);
+        }
+
+        self.entries.push(entry);
+        self.entries.len() - 1
+    }
+
+    /// Newest change that can still be undone: a successful, reversible write that is neither
+    /// an undo itself nor already undone
+    pub fn last_undoable(&self) -> Option<usize> {
+        self.entries
+            .iter()
+            .enumerate()
+            .rev()
+            .find_map(|(index, entry)| {
+                (entry.undo_of.is_none() && !self.is_undone(index) && entry.undo_value().is_some())
+                    .then_some(index)
+            })
+    }
+
+    /// Whether a successful later write reverted entry `index`
+    pub fn is_undone(&self, index: usize) -> bool {
+        self.entries
+            .iter()
+            .any(|entry| entry.undo_of == Some(index) && entry.succeeded())
+    }
+}
+
+fn append_entry(path: &Path, entry: &AuditEntry) -> io::Result<()> {
+    let mut line = serde_json::to_string(entry).map_err(io::Error::other)?;
+    line.push('\n');
+
+    // O_APPEND keeps each line intact even with several cgtop instances writing
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    file.write_all(line.as_bytes())
+}
+
+/// Name of the user cgtop runs as, or the numeric uid if it has no passwd entry
+pub fn current_user() -> String {
+    let uid = nix::unistd::getuid();
+    match nix::unistd::User::from_uid(uid) {
+        Ok(Some(user)) => user.name,
+        _ => uid.to_string(),
+    }
+}
@@ -0,0 +1,189 @@
+use anyhow::Result;
+use chrono::Utc;
+use std::io::Write;
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+
+use crate::collection::{CGroupCollector, CGroupMetrics, Snapshot};
+use crate::columns::{CgroupColumn, CsvColumn, csv_header, csv_rows};
+use crate::widgets::ProcessListWidget;
+
+/// How each sample is written
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum OutputFormat {
+    /// Aligned tables for people
+    #[default]
+    Text,
+    /// One pretty-printed `Snapshot` document per sample
+    Json,
+    /// One compact `Snapshot` document per line and sample
+    Ndjson,
+    /// A header, then one row per cgroup and sample
+    Csv,
+}
+
+/// Settings of `cgtop --batch` and `cgtop snapshot`
+#[derive(Debug, Clone)]
+pub struct BatchOptions {
+    pub format: OutputFormat,
+    /// Samples to print before exiting; `None` runs until interrupted
+    pub iterations: Option<u64>,
+    pub interval: Duration,
+    /// Column the cgroup table is sorted by
+    pub sort: CgroupColumn,
+    /// Also print the process table
+    pub processes: bool,
+    /// Columns of the CSV output
+    pub columns: Vec<CsvColumn>,
+}
+
+/// Sample the hierarchy every `options.interval` and write it to `out` in `options.format`
+pub fn run(
+    collector: &CGroupCollector,
+    options: &BatchOptions,
+    out: &mut impl Write,
+) -> Result<()> {
+    let root = &collector.cgroup_root;
+    let mut iteration = 0;
+    // Earlier sample for the per-second rates in CSV rows
+    let mut previous: Option<CGroupMetrics> = None;
+    loop {
+        let metrics = collector.collect_metrics()?;
+        match options.format {
+            OutputFormat::Text => write_sample(&metrics, root, options, out)?,
+            OutputFormat::Json => {
+                serde_json::to_writer_pretty(&mut *out, &Snapshot::new(root, metrics))?;
+                writeln!(out)?;
+            }
+            OutputFormat::Ndjson => {
+                serde_json::to_writer(&mut *out, &Snapshot::new(root, metrics))?;
+                writeln!(out)?;
+            }
+            OutputFormat::Csv => {
+                if iteration == 0 {
+                    write!(out, "{}", csv_header(&options.columns))?;
+                }
+                let mut paths: Vec<_> = metrics.resource_usage.keys().map(String::as_str).collect();
+                paths.sort_unstable();
+                write!(
+                    out,
+                    "{}",
+                    csv_rows(&options.columns, &paths, root, &metrics, previous.as_ref())
+                )?;
+                previous = Some(metrics);
+            }
+        }
+        // Output usually goes to a pipe or file, show each sample as soon as it is complete
+        out.flush()?;
+
+        iteration += 1;
+        if options
+            .iterations
+            .is_some_and(|iterations| iteration >= iterations)
+        {
+            return Ok(());
+        }
+        thread::sleep(options.interval);
+    }
+}
+
+/// One sample: a summary line, the cgroup table and optionally the process table
+pub fn write_sample(
+    metrics: &CGroupMetrics,
+    root: &Path,
+    options: &BatchOptions,
+    out: &mut impl Write,
+) -> Result<()> {
+    writeln!(
+        out,
+        "cgtop - {} - {} cgroups, {} processes",
+        Utc::now().format("%Y-%m-%d %H:%M:%S UTC"),
+        metrics.resource_usage.len(),
+        metrics.processes.len()
+    )?;
+    writeln!(out)?;
+    write!(out, "{}", cgroup_table(metrics, root, options.sort))?;
+    if options.processes {
+        writeln!(out)?;
+        write!(out, "{}", process_table(metrics, root))?;
+    }
+    writeln!(out)?;
+    Ok(())
+}
+
+/// Every cgroup in `metrics` with the columns of `CgroupColumn::ALL`, ordered by `sort`
+pub fn cgroup_table(metrics: &CGroupMetrics, root: &Path, sort: CgroupColumn) -> String {
+    let mut cgroups: Vec<_> = metrics.resource_usage.iter().collect();
+    cgroups.sort_by(|(a_path, a), (b_path, b)| sort.compare((a_path, a), (b_path, b)));
+
+    let rows = cgroups
+        .into_iter()
+        .map(|(path, stats)| {
+            CgroupColumn::ALL
+                .iter()
+                .map(|column| column.format(path, root, stats))
+                .collect()
+        })
+        .collect();
+
+    let columns: Vec<_> = CgroupColumn::ALL
+        .iter()
+        .map(|column| (column.header(), column.is_numeric()))
+        .collect();
+    format_table(&columns, rows)
+}
+
+/// Every process in `metrics` with the columns of the process list, ordered by PID
+pub fn process_table(metrics: &CGroupMetrics, root: &Path) -> String {
+    let mut processes: Vec<_> = metrics.processes.iter().collect();
+    processes.sort_by_key(|(pid, _)| **pid);
+
+    let rows = processes
+        .into_iter()
+        .map(|(pid, cgroup)| ProcessListWidget::cells(*pid, cgroup, root).to_vec())
+        .collect();
+
+    let columns: Vec<_> = ProcessListWidget::COLUMNS
+        .iter()
+        .enumerate()
+        // Only the PID is a number
+        .map(|(index, (_, label))| (*label, index == 0))
+        .collect();
+    format_table(&columns, rows)
+}
+
+/// Lay out `rows` under a header, padding every column to its widest cell
+fn format_table(columns: &[(&str, bool)], rows: Vec<Vec<String>>) -> String {
+    let mut widths: Vec<usize> = columns
+        .iter()
+        .map(|(header, _)| header.chars().count())
+        .collect();
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let header = columns
+        .iter()
+        .map(|(header, _)| header.to_string())
+        .collect();
+    let mut table = String::new();
+    for row in std::iter::once(header).chain(rows) {
+        let cells: Vec<String> = row
+            .iter()
+            .zip(columns.iter().zip(&widths))
+            .map(|(cell, ((_, right_aligned), width))| {
+                if *right_aligned {
+                    format!("{:>width$}", cell, width = width)
+                } else {
+                    format!("{:<width$}", cell, width = width)
+                }
+            })
+            .collect();
+        table.push_str(cells.join("  ").trim_end());
+        table.push('\n');
+    }
+    table
+}
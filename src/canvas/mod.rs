@@ -1,14 +1,15 @@
 use ratatui::{
     Frame,
     layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
     text::{Line, Span},
     widgets::{Block, Borders, Paragraph},
 };
 
-use crate::app::App;
+use crate::app::{App, PROCESSES_TAB};
 use crate::notifications::render_notifications;
-use crate::widgets::{CGroupTreeWidget, ResourceGraphWidget};
+use crate::widgets::{
+    CGroupTreeWidget, DialogWidget, ProcessListWidget, ResourceGraphWidget, TabBarWidget,
+};
 
 pub struct Canvas;
 
@@ -26,33 +27,32 @@ impl Canvas {
         Self::draw_title_bar(f, app, chunks[0]);
         Self::draw_main_content(f, app, chunks[1]);
         Self::draw_status_bar(f, app, chunks[2]);
+        DialogWidget::draw(f, app, f.area());
 
         // Render notifications over everything else
-        render_notifications(f, &app.notifications, f.area());
+        render_notifications(f, &app.notifications, &app.theme, f.area());
     }
 
     fn draw_title_bar(f: &mut Frame, app: &mut App, area: Rect) {
+        let theme = &app.theme;
         // Truncate long paths to keep title readable
         let root_path = app.config.cgroup_root.display().to_string();
 
-        let title_line = Line::from(vec![
-            Span::styled(
-                "cgroup Monitor v0.1.0 - ",
-                Style::default()
-                    .fg(Color::White)
-                    .add_modifier(Modifier::BOLD),
-            ),
-            Span::styled(
-                root_path,
-                Style::default()
-                    .fg(Color::Cyan)
-                    .add_modifier(Modifier::BOLD),
-            ),
-        ]);
+        let mut spans = vec![
+            Span::styled("cgroup Monitor v0.1.0 - ", theme.bold(theme.text)),
+            Span::styled(root_path, theme.bold(theme.accent)),
+        ];
+        if app.config.read_only {
+            spans.push(Span::styled(" [RO]", theme.bold(theme.warning)));
+        }
+        if app.config.dry_run {
+            spans.push(Span::styled(" [DRY RUN]", theme.bold(theme.warning)));
+        }
+        let title_line = Line::from(spans);
         let title = Paragraph::new(title_line).block(
             Block::default()
                 .borders(Borders::ALL)
-                .style(Style::default().fg(Color::Blue)),
+                .style(theme.fg(theme.primary)),
         );
         f.render_widget(title, area);
     }
@@ -70,32 +70,61 @@ impl Canvas {
                 .tree_state
                 .adjust_scroll_for_area_height(tree_area.height as usize);
             CGroupTreeWidget::draw(f, app, &app.ui_state.tree_state, tree_area);
+            app.ui_state.layout.tree = tree_area;
         }
 
-        // Right side: resource usage
-        ResourceGraphWidget::draw(f, app, main_chunks[1]);
+        // Right side: tab bar above resource usage or the process list
+        let right_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(1), Constraint::Min(0)])
+            .split(main_chunks[1]);
+
+        TabBarWidget::draw(f, app, right_chunks[0]);
+        app.ui_state.layout.tabs = TabBarWidget::title_areas(right_chunks[0]);
+
+        let panel_area = right_chunks[1];
+        if app.ui_state.current_tab == PROCESSES_TAB {
+            ProcessListWidget::draw(f, app, panel_area);
+            app.ui_state.layout.process_list = panel_area;
+            app.ui_state.layout.process_header = ProcessListWidget::header_areas(panel_area);
+        } else {
+            ResourceGraphWidget::draw(f, app, panel_area);
+            app.ui_state.layout.process_list = Rect::default();
+            app.ui_state.layout.process_header.clear();
+        }
     }
 
     fn draw_status_bar(f: &mut Frame, app: &mut App, area: Rect) {
+        let theme = &app.theme;
+        let sampling = if app.ui_state.sampling_paused {
+            "PAUSED".to_string()
+        } else {
+            format!("every {}ms", app.config.update_interval_ms)
+        };
+
         let status_text = if let Some(ref data) = app.cgroup_data.metrics {
             format!(
-                "Last update: {:?} ago | cgroups: {} | Press 'q' to quit",
+                "Last update: {:.1}s ago | Sampling: {} | cgroups: {} | p: pause, r: refresh, +/-: rate | Press 'q' to quit",
                 app.cgroup_data
                     .last_update
-                    .map(|t| t.elapsed())
+                    .map(|t| t.elapsed().as_secs_f64())
                     .unwrap_or_default(),
+                sampling,
                 data.resource_usage.len()
             )
         } else {
-            "Collecting data... | Press 'q' to quit".to_string()
+            format!(
+                "Collecting data... | Sampling: {} | Press 'q' to quit",
+                sampling
+            )
         };
 
         let status = Paragraph::new(status_text)
-            .style(Style::default().fg(Color::White))
+            .style(theme.fg(theme.text))
             .block(
                 Block::default()
                     .borders(Borders::ALL)
-                    .style(Style::default().fg(Color::Blue)),
+                    .style(theme.fg(theme.primary)),
             );
         f.render_widget(status, area);
     }
@@ -1,29 +1,73 @@
 use anyhow::Result;
+use chrono::{DateTime, Utc};
 use hashbrown::HashMap;
 use procfs::process::{Process, all_processes};
+use serde::{Deserialize, Serialize, Serializer};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::time::Instant;
 
+/// Version of the JSON document written by `cgtop snapshot`; bumped whenever a
+/// field is renamed or removed or its meaning changes (adding fields keeps it)
+pub const SCHEMA_VERSION: u32 = 1;
+
 pub struct CGroupCollector {
     pub cgroup_root: PathBuf,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CGroupMetrics {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub hierarchies: Vec<CGroupHierarchy>,
+    #[serde(serialize_with = "serialize_sorted")]
     pub processes: HashMap<u32, String>, // PID -> cgroup path
+    #[serde(serialize_with = "serialize_sorted")]
     pub resource_usage: HashMap<String, ResourceStats>, // cgroup path -> stats
+    /// Monotonic time of the sample, for intervals within one run
+    #[serde(skip, default = "Instant::now")]
     pub timestamp: Instant,
+    /// Wall-clock time of the sample
+    pub collected_at: DateTime<Utc>,
+}
+
+/// A sample as written by `cgtop snapshot`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// `SCHEMA_VERSION` of the writer
+    pub schema_version: u32,
+    /// cgroup filesystem root the paths are under
+    pub root: PathBuf,
+    pub metrics: CGroupMetrics,
+}
+
+impl Snapshot {
+    pub fn new(root: &Path, metrics: CGroupMetrics) -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            root: root.to_path_buf(),
+            metrics,
+        }
+    }
 }
 
-#[derive(Debug, Clone)]
+/// Write maps ordered by key so that snapshots of the same state are identical
+fn serialize_sorted<K, V, S>(map: &HashMap<K, V>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    K: Ord + Serialize,
+    V: Serialize,
+    S: Serializer,
+{
+    map.iter().collect::<BTreeMap<_, _>>().serialize(serializer)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CGroupHierarchy {
     pub root: CGroupNode,
     pub flat_map: HashMap<String, CGroupNode>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CGroupNode {
     pub path: String,
     pub name: String,
@@ -32,16 +76,17 @@ pub struct CGroupNode {
     pub processes: Vec<ProcessInfo>,
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ResourceStats {
     pub memory: MemoryStats,
     pub cpu: CpuStats,
     pub io: IoStats,
     pub pids: PidStats,
     pub cgroup_procs: Vec<u32>, // PIDs in this cgroup from cgroup.procs
+    pub frozen: bool,           // frozen 1 in cgroup.events
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct MemoryStats {
     pub current: u64,
     pub max: Option<u64>,
@@ -64,7 +109,7 @@ pub struct MemoryStats {
     pub pressure: Option<MemoryPressure>,
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct MemoryEvents {
     pub low: u64,
     pub high: u64,
@@ -73,7 +118,7 @@ pub struct MemoryEvents {
     pub oom_kill: u64,
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct MemoryPressure {
     // PSI "some" metrics (at least one task delayed)
     pub some_avg10: f64,  // 10-second average percentage
@@ -87,7 +132,7 @@ pub struct MemoryPressure {
     pub full_total: u64,  // Total time in microseconds
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct CpuStats {
     pub usage_usec: u64,
     pub user_usec: u64,
@@ -97,7 +142,7 @@ pub struct CpuStats {
     pub throttled_usec: u64,
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct IoStats {
     pub rbytes: u64,
     pub wbytes: u64,
@@ -105,13 +150,13 @@ pub struct IoStats {
     pub wios: u64,
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct PidStats {
     pub current: u64,
     pub max: Option<u64>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProcessInfo {
     pub pid: u32,
     pub command: String,
@@ -129,6 +174,7 @@ impl CGroupCollector {
             processes: HashMap::new(),
             resource_usage: HashMap::new(),
             timestamp: Instant::now(),
+            collected_at: Utc::now(),
         };
 
         // Collect cgroup tree and resource stats
@@ -167,24 +213,20 @@ impl CGroupCollector {
     }
 
     fn read_cgroup_stats(&self, cgroup_path: &Path) -> Result<ResourceStats> {
-        let mut stats = ResourceStats::default();
-
-        // Read memory stats
-        stats.memory = self.read_memory_stats(cgroup_path)?;
-
-        // Read CPU stats
-        stats.cpu = self.read_cpu_stats(cgroup_path)?;
-
-        // Read IO stats
-        stats.io = self.read_io_stats(cgroup_path)?;
-
-        // Read PID stats
-        stats.pids = self.read_pid_stats(cgroup_path)?;
-
-        // Read cgroup.procs
-        stats.cgroup_procs = self.read_cgroup_procs(cgroup_path)?;
-
-        Ok(stats)
+        Ok(ResourceStats {
+            // Read memory stats
+            memory: self.read_memory_stats(cgroup_path)?,
+            // Read CPU stats
+            cpu: self.read_cpu_stats(cgroup_path)?,
+            // Read IO stats
+            io: self.read_io_stats(cgroup_path)?,
+            // Read PID stats
+            pids: self.read_pid_stats(cgroup_path)?,
+            // Read cgroup.procs
+            cgroup_procs: self.read_cgroup_procs(cgroup_path)?,
+            // Read the freezer state from cgroup.events
+            frozen: crate::actions::is_frozen(cgroup_path),
+        })
     }
 
     pub fn read_memory_stats(&self, cgroup_path: &Path) -> Result<MemoryStats> {
@@ -196,17 +238,17 @@ impl CGroupCollector {
         }
 
         // Read memory.max
-        if let Ok(content) = fs::read_to_string(cgroup_path.join("memory.max")) {
-            if content.trim() != "max" {
-                memory_stats.max = content.trim().parse().ok();
-            }
+        if let Ok(content) = fs::read_to_string(cgroup_path.join("memory.max"))
+            && content.trim() != "max"
+        {
+            memory_stats.max = content.trim().parse().ok();
         }
 
         // Read memory.high
-        if let Ok(content) = fs::read_to_string(cgroup_path.join("memory.high")) {
-            if content.trim() != "max" {
-                memory_stats.high = content.trim().parse().ok();
-            }
+        if let Ok(content) = fs::read_to_string(cgroup_path.join("memory.high"))
+            && content.trim() != "max"
+        {
+            memory_stats.high = content.trim().parse().ok();
         }
 
         // Read memory.peak
@@ -354,10 +396,10 @@ impl CGroupCollector {
             pid_stats.current = content.trim().parse().unwrap_or(0);
         }
 
-        if let Ok(content) = fs::read_to_string(cgroup_path.join("pids.max")) {
-            if content.trim() != "max" {
-                pid_stats.max = content.trim().parse().ok();
-            }
+        if let Ok(content) = fs::read_to_string(cgroup_path.join("pids.max"))
+            && content.trim() != "max"
+        {
+            pid_stats.max = content.trim().parse().ok();
         }
 
         Ok(pid_stats)
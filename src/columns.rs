@@ -0,0 +1,287 @@
+use chrono::SecondsFormat;
+use serde::Deserialize;
+use std::cmp::Ordering;
+use std::path::Path;
+
+use crate::canvas::{format_bytes, format_duration_usec};
+use crate::collection::{CGroupMetrics, ResourceStats};
+
+/// Columns of the cgroup table, shared by the tree view and the batch output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum CgroupColumn {
+    Path,
+    /// memory.current
+    Memory,
+    /// memory.peak
+    Peak,
+    /// Total CPU time from cpu.stat
+    Cpu,
+    /// pids.current, with pids.max if set
+    Pids,
+    /// Freezer state
+    State,
+}
+
+impl CgroupColumn {
+    /// Columns of a batch table, in print order
+    pub const ALL: [CgroupColumn; 6] = [
+        CgroupColumn::Path,
+        CgroupColumn::Memory,
+        CgroupColumn::Peak,
+        CgroupColumn::Cpu,
+        CgroupColumn::Pids,
+        CgroupColumn::State,
+    ];
+
+    pub fn header(self) -> &'static str {
+        match self {
+            CgroupColumn::Path => "CGROUP",
+            CgroupColumn::Memory => "MEM",
+            CgroupColumn::Peak => "PEAK",
+            CgroupColumn::Cpu => "CPU",
+            CgroupColumn::Pids => "PIDS",
+            CgroupColumn::State => "STATE",
+        }
+    }
+
+    /// Whether values are aligned to the right
+    pub fn is_numeric(self) -> bool {
+        !matches!(self, CgroupColumn::Path | CgroupColumn::State)
+    }
+
+    /// Value shown for the cgroup at `path`; paths are shown relative to `root`
+    pub fn format(self, path: &str, root: &Path, stats: &ResourceStats) -> String {
+        match self {
+            CgroupColumn::Path => display_path(path, root),
+            CgroupColumn::Memory => format_bytes(stats.memory.current),
+            CgroupColumn::Peak => format_bytes(stats.memory.peak),
+            CgroupColumn::Cpu => format_duration_usec(stats.cpu.usage_usec),
+            CgroupColumn::Pids => match stats.pids.max {
+                Some(max) => format!("{}/{}", stats.pids.current, max),
+                None => stats.pids.current.to_string(),
+            },
+            CgroupColumn::State => if stats.frozen { "frozen" } else { "" }.to_string(),
+        }
+    }
+
+    /// Order two cgroups by this column: paths A to Z, everything else largest first
+    pub fn compare(self, a: (&str, &ResourceStats), b: (&str, &ResourceStats)) -> Ordering {
+        let (a_path, a) = a;
+        let (b_path, b) = b;
+        let by_value = match self {
+            CgroupColumn::Path => Ordering::Equal,
+            CgroupColumn::Memory => b.memory.current.cmp(&a.memory.current),
+            CgroupColumn::Peak => b.memory.peak.cmp(&a.memory.peak),
+            CgroupColumn::Cpu => b.cpu.usage_usec.cmp(&a.cpu.usage_usec),
+            CgroupColumn::Pids => b.pids.current.cmp(&a.pids.current),
+            CgroupColumn::State => b.frozen.cmp(&a.frozen),
+        };
+        by_value.then_with(|| a_path.cmp(b_path))
+    }
+}
+
+/// `path` relative to `root`, with the root itself shown as `/`
+pub fn display_path(path: &str, root: &Path) -> String {
+    match path.strip_prefix(root.to_string_lossy().as_ref()) {
+        Some("") => "/".to_string(),
+        Some(relative) => relative.to_string(),
+        None => path.to_string(),
+    }
+}
+
+/// Columns of the CSV export, named after the cgroup files they come from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, clap::ValueEnum)]
+pub enum CsvColumn {
+    #[serde(rename = "path")]
+    #[value(name = "path")]
+    Path,
+    /// Wall-clock time of the sample
+    #[serde(rename = "timestamp")]
+    #[value(name = "timestamp")]
+    Timestamp,
+    #[serde(rename = "memory.current")]
+    #[value(name = "memory.current")]
+    MemoryCurrent,
+    #[serde(rename = "memory.peak")]
+    #[value(name = "memory.peak")]
+    MemoryPeak,
+    #[serde(rename = "memory.max")]
+    #[value(name = "memory.max")]
+    MemoryMax,
+    /// Total CPU time in microseconds
+    #[serde(rename = "cpu.usage_usec")]
+    #[value(name = "cpu.usage_usec")]
+    CpuUsage,
+    /// CPUs kept busy since the previous sample
+    #[serde(rename = "cpu.rate")]
+    #[value(name = "cpu.rate")]
+    CpuRate,
+    #[serde(rename = "io.rbytes")]
+    #[value(name = "io.rbytes")]
+    IoReadBytes,
+    #[serde(rename = "io.wbytes")]
+    #[value(name = "io.wbytes")]
+    IoWriteBytes,
+    /// Bytes read per second since the previous sample
+    #[serde(rename = "io.read_bps")]
+    #[value(name = "io.read_bps")]
+    IoReadRate,
+    /// Bytes written per second since the previous sample
+    #[serde(rename = "io.write_bps")]
+    #[value(name = "io.write_bps")]
+    IoWriteRate,
+    #[serde(rename = "pids.current")]
+    #[value(name = "pids.current")]
+    PidsCurrent,
+    #[serde(rename = "pids.max")]
+    #[value(name = "pids.max")]
+    PidsMax,
+    /// Share of time some tasks stalled on memory, 10s average
+    #[serde(rename = "memory.pressure.some_avg10")]
+    #[value(name = "memory.pressure.some_avg10")]
+    MemoryPressureSome,
+    /// Share of time all tasks stalled on memory, 10s average
+    #[serde(rename = "memory.pressure.full_avg10")]
+    #[value(name = "memory.pressure.full_avg10")]
+    MemoryPressureFull,
+}
+
+impl CsvColumn {
+    /// Columns written when none are configured
+    pub const DEFAULT: [CsvColumn; 8] = [
+        CsvColumn::Path,
+        CsvColumn::Timestamp,
+        CsvColumn::MemoryCurrent,
+        CsvColumn::CpuRate,
+        CsvColumn::IoReadRate,
+        CsvColumn::IoWriteRate,
+        CsvColumn::PidsCurrent,
+        CsvColumn::MemoryPressureSome,
+    ];
+
+    /// Header of the column, the same name it is configured with
+    pub fn name(self) -> &'static str {
+        match self {
+            CsvColumn::Path => "path",
+            CsvColumn::Timestamp => "timestamp",
+            CsvColumn::MemoryCurrent => "memory.current",
+            CsvColumn::MemoryPeak => "memory.peak",
+            CsvColumn::MemoryMax => "memory.max",
+            CsvColumn::CpuUsage => "cpu.usage_usec",
+            CsvColumn::CpuRate => "cpu.rate",
+            CsvColumn::IoReadBytes => "io.rbytes",
+            CsvColumn::IoWriteBytes => "io.wbytes",
+            CsvColumn::IoReadRate => "io.read_bps",
+            CsvColumn::IoWriteRate => "io.write_bps",
+            CsvColumn::PidsCurrent => "pids.current",
+            CsvColumn::PidsMax => "pids.max",
+            CsvColumn::MemoryPressureSome => "memory.pressure.some_avg10",
+            CsvColumn::MemoryPressureFull => "memory.pressure.full_avg10",
+        }
+    }
+
+    /// Unformatted value for the cgroup at `path` in `sample`; rates are empty
+    /// without an earlier sample of the same cgroup
+    pub fn value(
+        self,
+        path: &str,
+        root: &Path,
+        sample: &CGroupMetrics,
+        previous: Option<&CGroupMetrics>,
+    ) -> String {
+        // Known even for a cgroup missing from the sample
+        match self {
+            CsvColumn::Path => return display_path(path, root),
+            CsvColumn::Timestamp => {
+                return sample
+                    .collected_at
+                    .to_rfc3339_opts(SecondsFormat::Secs, true);
+            }
+            _ => {}
+        }
+        let Some(stats) = sample.resource_usage.get(path) else {
+            return String::new();
+        };
+        let rate = |counter: fn(&ResourceStats) -> u64| {
+            let previous = previous?;
+            let elapsed = sample
+                .timestamp
+                .checked_duration_since(previous.timestamp)?
+                .as_secs_f64();
+            let delta = counter(stats).checked_sub(counter(previous.resource_usage.get(path)?))?;
+            (elapsed > 0.0).then(|| delta as f64 / elapsed)
+        };
+
+        match self {
+            CsvColumn::Path | CsvColumn::Timestamp => unreachable!("handled above"),
+            CsvColumn::MemoryCurrent => stats.memory.current.to_string(),
+            CsvColumn::MemoryPeak => stats.memory.peak.to_string(),
+            CsvColumn::MemoryMax => stats
+                .memory
+                .max
+                .map_or("max".to_string(), |max| max.to_string()),
+            CsvColumn::CpuUsage => stats.cpu.usage_usec.to_string(),
+            // CPU seconds per wall-clock second, i.e. busy CPUs
+            CsvColumn::CpuRate => rate(|stats| stats.cpu.usage_usec)
+                .map_or(String::new(), |rate| format!("{:.3}", rate / 1_000_000.0)),
+            CsvColumn::IoReadBytes => stats.io.rbytes.to_string(),
+            CsvColumn::IoWriteBytes => stats.io.wbytes.to_string(),
+            CsvColumn::IoReadRate => {
+                rate(|stats| stats.io.rbytes).map_or(String::new(), |rate| format!("{:.0}", rate))
+            }
+            CsvColumn::IoWriteRate => {
+                rate(|stats| stats.io.wbytes).map_or(String::new(), |rate| format!("{:.0}", rate))
+            }
+            CsvColumn::PidsCurrent => stats.pids.current.to_string(),
+            CsvColumn::PidsMax => stats
+                .pids
+                .max
+                .map_or("max".to_string(), |max| max.to_string()),
+            CsvColumn::MemoryPressureSome => stats
+                .memory
+                .pressure
+                .as_ref()
+                .map_or(String::new(), |pressure| pressure.some_avg10.to_string()),
+            CsvColumn::MemoryPressureFull => stats
+                .memory
+                .pressure
+                .as_ref()
+                .map_or(String::new(), |pressure| pressure.full_avg10.to_string()),
+        }
+    }
+}
+
+/// Header line for `columns`
+pub fn csv_header(columns: &[CsvColumn]) -> String {
+    let names: Vec<_> = columns.iter().map(|column| column.name()).collect();
+    format!("{}\n", names.join(","))
+}
+
+/// One line per cgroup in `paths`, in that order
+pub fn csv_rows(
+    columns: &[CsvColumn],
+    paths: &[&str],
+    root: &Path,
+    sample: &CGroupMetrics,
+    previous: Option<&CGroupMetrics>,
+) -> String {
+    let mut rows = String::new();
+    for path in paths {
+        let cells: Vec<_> = columns
+            .iter()
+            .map(|column| csv_escape(&column.value(path, root, sample, previous)))
+            .collect();
+        rows.push_str(&cells.join(","));
+        rows.push('\n');
+    }
+    rows
+}
+
+/// Quote a field if it holds a separator, a quote or a line break
+fn csv_escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
@@ -0,0 +1,90 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use crate::columns::CsvColumn;
+use crate::theme::ThemeName;
+use crate::utils::{SafetyPolicy, has_write_access};
+
+/// Settings read from `config.toml`; anything given on the command line wins
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FileConfig {
+    /// Colour scheme: dark, light, high-contrast or monochrome
+    pub theme: Option<ThemeName>,
+    /// Time between two samples, in milliseconds
+    pub interval_ms: Option<u64>,
+    /// Disable every mutating action (default: only without write access to the root)
+    pub read_only: Option<bool>,
+    /// Columns of CSV output and exports, e.g. ["path", "timestamp", "cpu.rate"]
+    pub csv_columns: Option<Vec<CsvColumn>>,
+    /// Directory the `e` key writes CSV exports to (default: the working directory)
+    pub export_dir: Option<PathBuf>,
+    /// Which cgroups may be deleted from the UI
+    pub safety: SafetyConfig,
+}
+
+/// The `[safety]` table
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SafetyConfig {
+    /// Glob patterns relative to the root that are never deleted; replaces the built-in list
+    pub denylist: Option<Vec<String>>,
+    /// If set, only cgroups matching one of these patterns (or below them) can be deleted
+    pub allowlist: Vec<String>,
+    /// Refuse deletions unless the root is on a cgroup2 mount (default: true)
+    pub require_cgroup2_mount: Option<bool>,
+}
+
+impl SafetyConfig {
+    /// Apply these settings on top of the default policy for `policy.root`
+    pub fn apply_to(&self, policy: &mut SafetyPolicy) -> Result<()> {
+        if let Some(denylist) = &self.denylist {
+            policy.set_denylist(denylist)?;
+        }
+        policy.set_allowlist(&self.allowlist)?;
+        if let Some(require) = self.require_cgroup2_mount {
+            policy.require_cgroup2 = require;
+        }
+        Ok(())
+    }
+}
+
+impl FileConfig {
+    pub fn parse(content: &str) -> Result<Self> {
+        Ok(toml::from_str(content)?)
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("failed to read config file {}", path.display()))?;
+        Self::parse(&content).with_context(|| format!("invalid config file {}", path.display()))
+    }
+
+    /// Load the config from `path`, or from the default location if it exists
+    pub fn load_or_default(path: Option<&Path>) -> Result<Self> {
+        match path {
+            Some(path) => Self::load(path),
+            None => match default_config_path() {
+                Some(path) if path.exists() => Self::load(&path),
+                _ => Ok(Self::default()),
+            },
+        }
+    }
+}
+
+/// Read-only mode: forced by the flag, else as configured, else whenever `root` is not writable
+pub fn resolve_read_only(flag: bool, configured: Option<bool>, root: &Path) -> bool {
+    flag || configured.unwrap_or_else(|| !has_write_access(root))
+}
+
+/// `$XDG_CONFIG_HOME/cgtop/config.toml`, falling back to `~/.config`
+pub fn default_config_path() -> Option<PathBuf> {
+    env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .map(|dir| dir.join("cgtop").join("config.toml"))
+}
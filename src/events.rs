@@ -33,9 +33,62 @@ impl CGroupEvent {
         }
     }
 
-    /// Check if this is a quit key (q or Esc)
+    /// Check if this is a quit key (q, Esc or Ctrl+C)
     pub fn is_quit_key(&self) -> bool {
-        self.is_key(crossterm::event::KeyCode::Char('q'))
-            || self.is_key(crossterm::event::KeyCode::Esc)
+        use crossterm::event::{KeyCode, KeyModifiers};
+
+        let is_ctrl_c = matches!(
+            self,
+            CGroupEvent::KeyInput(key_event)
+                if key_event.code == KeyCode::Char('c')
+                    && key_event.modifiers.contains(KeyModifiers::CONTROL)
+        );
+
+        self.is_key(KeyCode::Char('q')) || self.is_key(KeyCode::Esc) || is_ctrl_c
     }
 }
+
+/// Collect `first` plus every event already queued behind it.
+///
+/// Only the newest `Update` survives and it is moved to the end, so a slow
+/// terminal applies one sample per frame instead of working through a backlog.
+pub fn drain_coalesced(
+    first: CGroupEvent,
+    receiver: &crossbeam::channel::Receiver<CGroupEvent>,
+) -> Vec<CGroupEvent> {
+    let mut events = Vec::new();
+    let mut latest_update = None;
+    let mut dropped_updates = 0;
+
+    for event in std::iter::once(first).chain(receiver.try_iter()) {
+        if let CGroupEvent::Update(_) = event {
+            if latest_update.replace(event).is_some() {
+                dropped_updates += 1;
+            }
+        } else {
+            events.push(event);
+        }
+    }
+
+    if dropped_updates > 0 {
+        log::debug!("Coalesced {} stale data updates", dropped_updates);
+    }
+
+    events.extend(latest_update);
+    events
+}
+
+/// Commands sent from the UI to the collection thread
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CollectionCommand {
+    /// Change the sampling interval
+    SetInterval(std::time::Duration),
+    /// Stop sampling until resumed
+    Pause,
+    /// Resume periodic sampling
+    Resume,
+    /// Take a sample right away, even while paused
+    Refresh,
+    /// Exit the collection thread
+    Shutdown,
+}
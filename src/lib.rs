@@ -1,8 +1,15 @@
+pub mod actions;
 pub mod app;
+pub mod audit;
+pub mod batch;
 pub mod canvas;
 pub mod collection;
+pub mod columns;
+pub mod config;
 pub mod events;
 pub mod notifications;
+pub mod prometheus;
+pub mod theme;
 pub mod threads;
 pub mod utils;
 pub mod widgets;
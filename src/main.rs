@@ -1,17 +1,25 @@
-mod app;
-mod canvas;
-mod collection;
-mod events;
-mod notifications;
-mod threads;
-mod widgets;
-use events::CGroupEvent;
-use threads::EventThreads;
+use cgtop::{
+    actions::{
+        apply::{Spec, apply_change, plan},
+        exec::{command_in_cgroup, resolve_cgroup, shell_in_cgroup},
+    },
+    app::{App, DEFAULT_UPDATE_INTERVAL_MS, MIN_UPDATE_INTERVAL_MS, PROCESSES_TAB},
+    audit::{AuditEntry, AuditLog},
+    batch::{self, BatchOptions, OutputFormat},
+    canvas::Canvas,
+    collection::CGroupCollector,
+    columns::{CgroupColumn, CsvColumn},
+    config::{FileConfig, resolve_read_only},
+    events::{CGroupEvent, drain_coalesced},
+    prometheus::{self, ExporterOptions, MetricsCache},
+    theme::{ColorSupport, Theme, ThemeName},
+    threads::EventThreads,
+    utils::SafetyPolicy,
+};
 
 use anyhow::{Context, Result};
-use app::App;
-use canvas::Canvas;
-use clap::Parser;
+use clap::{Parser, Subcommand};
+use crossbeam::channel::RecvTimeoutError;
 use crossterm::{
     event::{DisableMouseCapture, EnableMouseCapture},
     execute,
@@ -20,14 +28,26 @@ use crossterm::{
 use env_logger::{Env, Target, WriteStyle};
 use log::LevelFilter;
 use ratatui::{Terminal, backend::CrosstermBackend};
+use signal_hook::consts::{SIGHUP, SIGINT, SIGTERM};
 use std::{
     env,
     fs::OpenOptions,
     io,
+    net::{SocketAddr, TcpListener},
+    os::unix::process::ExitStatusExt,
+    panic,
     path::{Path, PathBuf},
-    time::Instant,
+    process::{self, ExitStatus},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
 };
 
+/// Upper bound on the time between two redraws
+const TICK_RATE: Duration = Duration::from_millis(250);
+
 // ===================================Set up logging=============================================
 
 const PRIMARY_LOG_PATH: &str = "/var/log/cgtop.log";
@@ -118,41 +138,291 @@ struct Cli {
     #[arg(long, short, default_value = "/sys/fs/cgroup", value_parser = normalize_path)]
     path: PathBuf,
 
+    /// Time between two samples, in milliseconds (change live with +/-) [default: 1000]
+    #[arg(long, short, value_parser = clap::value_parser!(u64).range(MIN_UPDATE_INTERVAL_MS..))]
+    interval: Option<u64>,
+
+    /// Colour scheme [default: dark]; NO_COLOR or a dumb terminal forces monochrome
+    #[arg(long, value_enum)]
+    theme: Option<ThemeName>,
+
+    /// Config file to read instead of ~/.config/cgtop/config.toml
+    #[arg(long)]
+    config: Option<PathBuf>,
+
+    /// Leave the mouse to the terminal (keeps native text selection working)
+    #[arg(long)]
+    no_mouse: bool,
+
+    /// Only log what destructive actions would do instead of doing it
+    #[arg(long)]
+    dry_run: bool,
+
+    /// Disable every action that changes cgroups or processes [default: on without write access to the root]
+    #[arg(long)]
+    read_only: bool,
+
+    /// Print plain-text tables to stdout instead of starting the TUI, like `top -b`
+    #[arg(long, short)]
+    batch: bool,
+
+    /// Number of samples to print in batch mode [default: until interrupted]
+    #[arg(long, short = 'n', requires = "batch")]
+    iterations: Option<u64>,
+
+    /// Column the batch table is sorted by [default: cpu]
+    #[arg(long, value_enum, requires = "batch")]
+    sort: Option<CgroupColumn>,
+
+    /// Also print every process in batch mode
+    #[arg(long, requires = "batch")]
+    processes: bool,
+
+    /// Output of batch mode: text tables, json, ndjson or csv [default: text]
+    #[arg(long, value_enum, requires = "batch")]
+    format: Option<OutputFormat>,
+
+    /// Comma-separated columns of CSV output and of the `e` export
+    #[arg(long, value_enum, value_delimiter = ',')]
+    columns: Vec<CsvColumn>,
+
     /// Enable verbose logging
     #[arg(long, short)]
     verbose: bool,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run a command inside a cgroup, e.g. to reproduce a workload under its limits
+    Exec {
+        /// Target cgroup, absolute or relative to --path
+        #[arg(long)]
+        cgroup: PathBuf,
+
+        /// Program and arguments, after `--`
+        #[arg(last = true, required = true)]
+        command: Vec<String>,
+    },
+
+    /// Bring cgroups in line with a TOML or YAML spec of limits, printing the plan first
+    Apply {
+        /// Spec mapping cgroup globs (relative to --path) to control file values
+        spec: PathBuf,
+
+        /// Only show the plan; exit with status 1 if anything differs
+        #[arg(long)]
+        check: bool,
+    },
+
+    /// Print samples for other programs: one JSON document, or a stream of ndjson lines
+    /// or CSV rows every interval
+    Snapshot {
+        #[arg(long, value_enum, default_value_t = OutputFormat::Json)]
+        format: OutputFormat,
+
+        /// Number of samples to stream with ndjson or csv [default: until interrupted]
+        #[arg(long, short = 'n')]
+        iterations: Option<u64>,
+    },
+
+    /// Serve the metrics of every cgroup to Prometheus at http://<listen>/metrics,
+    /// sampling at most once per interval
+    Serve {
+        #[arg(long, default_value = "127.0.0.1:9256")]
+        listen: SocketAddr,
+
+        /// Only export cgroups up to this many levels below --path
+        #[arg(long)]
+        max_depth: Option<usize>,
+    },
+
+    /// Write the metrics of `serve` to <dir>/cgtop.prom every interval, for the
+    /// textfile collector of node_exporter
+    Textfile {
+        #[arg(long)]
+        dir: PathBuf,
+
+        /// Only export cgroups up to this many levels below --path
+        #[arg(long)]
+        max_depth: Option<usize>,
+
+        /// Number of files to write [default: until interrupted]
+        #[arg(long, short = 'n')]
+        iterations: Option<u64>,
+    },
 }
 
 fn main() -> Result<()> {
     let cli = Cli::parse();
 
-    init_logging(cli.verbose)?;
+    let log_path = init_logging(cli.verbose)?;
+    let audit_path = AuditLog::path_for_log(&log_path);
+    let mut audit = AuditLog::open(audit_path.clone()).unwrap_or_else(|e| {
+        log::warn!(
+            "Cannot open audit log {}: {}; changes are only kept in memory",
+            audit_path.display(),
+            e
+        );
+        AuditLog::in_memory()
+    });
+
+    let file_config = FileConfig::load_or_default(cli.config.as_deref())?;
+
+    // Only an explicit setting applies to subcommands; without write access the kernel
+    // refuses anyway
+    let read_only = cli.read_only || file_config.read_only == Some(true);
+    match &cli.command {
+        Some(Command::Exec { cgroup, command }) => {
+            anyhow::ensure!(!read_only, "exec is disabled in read-only mode");
+            let status = run_exec(&resolve_cgroup(&cli.path, cgroup), command, &mut audit)?;
+            process::exit(exit_code(status));
+        }
+        Some(Command::Apply { spec, check }) => {
+            let write = !check && !cli.dry_run;
+            anyhow::ensure!(
+                !(write && read_only),
+                "apply is disabled in read-only mode (use --check)"
+            );
+            let code = run_apply(&cli.path, spec, write, &mut audit)?;
+            process::exit(code);
+        }
+        Some(Command::Snapshot { .. } | Command::Serve { .. } | Command::Textfile { .. })
+        | None => {}
+    }
+
+    let update_interval_ms = cli
+        .interval
+        .or(file_config.interval_ms)
+        .unwrap_or(DEFAULT_UPDATE_INTERVAL_MS)
+        .max(MIN_UPDATE_INTERVAL_MS);
+    let columns = if !cli.columns.is_empty() {
+        cli.columns.clone()
+    } else {
+        file_config
+            .csv_columns
+            .clone()
+            .unwrap_or_else(|| CsvColumn::DEFAULT.to_vec())
+    };
+    if let Some(Command::Serve { listen, max_depth }) = cli.command {
+        let options = ExporterOptions { max_depth };
+        return run_serve(&cli.path, listen, options, update_interval_ms);
+    }
+    if let Some(Command::Textfile {
+        dir,
+        max_depth,
+        iterations,
+    }) = &cli.command
+    {
+        log::info!(
+            "Writing metrics of {} to {} every {}ms",
+            cli.path.display(),
+            dir.join(prometheus::TEXTFILE_NAME).display(),
+            update_interval_ms
+        );
+        return prometheus::run_textfile(
+            &CGroupCollector::new(cli.path.clone()),
+            &ExporterOptions {
+                max_depth: *max_depth,
+            },
+            dir,
+            Duration::from_millis(update_interval_ms),
+            *iterations,
+        );
+    }
+    if let Some(Command::Snapshot { format, iterations }) = cli.command {
+        let options = BatchOptions {
+            format,
+            // A JSON dump is a single document
+            iterations: match format {
+                OutputFormat::Json => Some(1),
+                _ => iterations,
+            },
+            interval: Duration::from_millis(update_interval_ms),
+            sort: CgroupColumn::Path,
+            processes: true,
+            columns,
+        };
+        return run_batch(&cli.path, &options);
+    }
+    if cli.batch {
+        let options = BatchOptions {
+            format: cli.format.unwrap_or_default(),
+            iterations: cli.iterations,
+            interval: Duration::from_millis(update_interval_ms),
+            sort: cli.sort.unwrap_or(CgroupColumn::Cpu),
+            processes: cli.processes,
+            columns,
+        };
+        return run_batch(&cli.path, &options);
+    }
+
+    let mut safety = SafetyPolicy::new(cli.path.clone());
+    file_config.safety.apply_to(&mut safety)?;
 
     log::info!(
         "cgroup TUI Monitor starting with root path: {}",
         cli.path.display()
     );
 
+    // Worker threads, signal handlers and the panic hook share one stop flag
+    let mut event_threads = EventThreads::new();
+    let stop = event_threads.stop_flag();
+    for signal in [SIGTERM, SIGINT, SIGHUP] {
+        signal_hook::flag::register(signal, Arc::clone(&stop))
+            .with_context(|| format!("failed to install handler for signal {}", signal))?;
+    }
+    install_panic_hook(stop);
+
     // Setup terminal
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+    execute!(stdout, EnterAlternateScreen)?;
+    if !cli.no_mouse {
+        execute!(stdout, EnableMouseCapture)?;
+    }
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
     // Create app with custom cgroup path
     let mut app = App::new_with_path(cli.path);
+    app.config.mouse = !cli.no_mouse;
+    app.audit = audit;
+    app.config.safety = safety;
+    app.config.dry_run = cli.dry_run;
+    app.config.read_only = resolve_read_only(
+        cli.read_only,
+        file_config.read_only,
+        &app.config.cgroup_root,
+    );
+    if app.config.read_only {
+        log::info!("Running in read-only mode");
+    }
+    app.config.update_interval_ms = update_interval_ms;
+    app.config.csv_columns = columns;
+    if let Some(export_dir) = file_config.export_dir.clone() {
+        app.config.export_dir = export_dir;
+    }
+
+    let color_support = ColorSupport::detect();
+    let theme_name = cli.theme.or(file_config.theme).unwrap_or_default();
+    app.theme = Theme::for_terminal(theme_name, color_support);
+    log::info!(
+        "Using {:?} theme (colour support: {:?})",
+        app.theme.name,
+        color_support
+    );
 
     // Run the application
-    let result = run_app(&mut terminal, &mut app);
+    let result = run_app(&mut terminal, &mut app, &mut event_threads);
+
+    // Stop and join the worker threads before handing the terminal back
+    event_threads.shutdown();
 
     // Restore terminal
-    disable_raw_mode()?;
-    execute!(
-        terminal.backend_mut(),
-        LeaveAlternateScreen,
-        DisableMouseCapture
-    )?;
+    restore_terminal()?;
     terminal.show_cursor()?;
 
     if let Err(err) = result {
@@ -162,131 +432,400 @@ fn main() -> Result<()> {
     Ok(())
 }
 
-fn run_app(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>, app: &mut App) -> Result<()> {
-    let mut event_threads = EventThreads::new();
-    let event_rx = event_threads.start(app.config.cgroup_root.clone())?;
+/// `cgtop exec`: run `command` inside `cgroup` and wait for it
+fn run_exec(cgroup: &Path, command: &[String], audit: &mut AuditLog) -> Result<ExitStatus> {
+    let (program, args) = command.split_first().context("no command given")?;
+    log::info!("Running {:?} in {}", command, cgroup.display());
+
+    // The child only starts once it has joined the cgroup
+    spawn_audited(
+        &mut command_in_cgroup(cgroup, program, args)?,
+        cgroup,
+        audit,
+    )
+    .and_then(|mut child| child.wait())
+    .with_context(|| format!("failed to run {} in {}", program, cgroup.display()))
+}
+
+/// `cgtop --batch`: print samples to stdout until the iterations are done
+fn run_batch(root: &Path, options: &BatchOptions) -> Result<()> {
+    log::info!("Batch mode with root path: {}", root.display());
+    let collector = CGroupCollector::new(root.to_path_buf());
+
+    match batch::run(&collector, options, &mut io::stdout().lock()) {
+        // The reader went away (`cgtop -b | head`), which is not an error
+        Err(e)
+            if e.downcast_ref::<io::Error>()
+                .is_some_and(|e| e.kind() == io::ErrorKind::BrokenPipe) =>
+        {
+            Ok(())
+        }
+        result => result,
+    }
+}
+
+/// `cgtop serve`: answer scrapes until the process is killed
+fn run_serve(
+    root: &Path,
+    listen: SocketAddr,
+    options: ExporterOptions,
+    update_interval_ms: u64,
+) -> Result<()> {
+    let listener =
+        TcpListener::bind(listen).with_context(|| format!("failed to listen on {}", listen))?;
+    log::info!(
+        "Serving metrics of {} on http://{}/metrics",
+        root.display(),
+        listen
+    );
+    eprintln!("Serving metrics on http://{}/metrics", listen);
+
+    let cache = MetricsCache::new(
+        CGroupCollector::new(root.to_path_buf()),
+        options,
+        Duration::from_millis(update_interval_ms),
+    );
+    prometheus::serve(listener, cache)
+}
+
+/// `cgtop apply`: print the plan for `spec` and, if `write` is set, carry it out.
+/// Returns the exit code: 1 on drift when only checking, or when a write failed.
+fn run_apply(root: &Path, spec: &Path, write: bool, audit: &mut AuditLog) -> Result<i32> {
+    let spec = Spec::load(spec)?;
+    let plan = plan(&spec, root)?;
+
+    for pattern in &plan.unmatched {
+        println!("! {} matches no cgroup", pattern);
+    }
+    for change in &plan.changes {
+        println!("{}", change);
+    }
+    println!(
+        "{} to change, {} up to date",
+        plan.changes.len(),
+        plan.unchanged
+    );
+
+    if !write {
+        return Ok(if plan.has_drift() { 1 } else { 0 });
+    }
+
+    let mut failed = 0;
+    for change in &plan.changes {
+        if let Err(e) = apply_change(change, audit) {
+            println!("✗ {} {}: {}", change.cgroup.display(), change.file, e);
+            failed += 1;
+        }
+    }
+    println!(
+        "Applied {} of {} changes",
+        plan.changes.len() - failed,
+        plan.changes.len()
+    );
+    Ok(if failed > 0 { 1 } else { 0 })
+}
+
+/// Start `command`, recording its move into `cgroup` in the audit log
+fn spawn_audited(
+    command: &mut process::Command,
+    cgroup: &Path,
+    audit: &mut AuditLog,
+) -> io::Result<process::Child> {
+    let spawned = command.spawn();
+    let pid = spawned
+        .as_ref()
+        .map_or_else(|_| "0".to_string(), |child| child.id().to_string());
+    audit.record(AuditEntry::new(
+        cgroup,
+        "cgroup.procs",
+        None,
+        &pid,
+        spawned.as_ref().map(|_| ()).map_err(ToString::to_string),
+    ));
+    spawned
+}
+
+/// Shell-style exit code: the child's own code, or 128 + the signal that killed it
+fn exit_code(status: ExitStatus) -> i32 {
+    status
+        .code()
+        .or_else(|| status.signal().map(|signal| 128 + signal))
+        .unwrap_or(1)
+}
+
+/// Hand the terminal to a shell running in `cgroup`, then take it back
+fn run_shell(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    app: &mut App,
+    event_threads: &EventThreads,
+    cgroup: &Path,
+) -> Result<()> {
+    let mut command = match shell_in_cgroup(cgroup) {
+        Ok(command) => command,
+        Err(e) => {
+            app.show_error(format!("{:#}", e));
+            return Ok(());
+        }
+    };
+
+    event_threads.pause_input();
+    restore_terminal()?;
+    println!("cgtop: shell in {} (exit to return)", cgroup.display());
+    log::info!("Starting shell in {}", cgroup.display());
+    let status =
+        spawn_audited(&mut command, cgroup, &mut app.audit).and_then(|mut child| child.wait());
+
+    enable_raw_mode()?;
+    execute!(io::stdout(), EnterAlternateScreen)?;
+    if app.config.mouse {
+        execute!(io::stdout(), EnableMouseCapture)?;
+    }
+    terminal.clear()?;
+    event_threads.resume_input();
+
+    match status {
+        Ok(status) if status.success() => {
+            app.show_info(format!("Shell in {} exited", cgroup.display()))
+        }
+        Ok(status) => app.show_warning(format!(
+            "Shell in {} exited with status {}",
+            cgroup.display(),
+            exit_code(status)
+        )),
+        Err(e) => app.show_error(format!("Failed to start shell: {}", e)),
+    }
+    app.request_refresh();
+    Ok(())
+}
+
+fn restore_terminal() -> io::Result<()> {
+    disable_raw_mode()?;
+    execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture)
+}
+
+/// Put the terminal back into a usable state before the panic message is printed
+fn install_panic_hook(stop: Arc<AtomicBool>) {
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        stop.store(true, Ordering::SeqCst);
+        let _ = restore_terminal();
+        default_hook(info);
+    }));
+}
+
+fn run_app(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    app: &mut App,
+    event_threads: &mut EventThreads,
+) -> Result<()> {
+    let event_rx = event_threads.start(app.config.cgroup_root.clone(), app.update_interval())?;
+    if let Some(control_tx) = event_threads.control_sender() {
+        app.set_control_sender(control_tx);
+    }
 
     loop {
+        // A signal or a panicking worker asked us to stop
+        if event_threads.is_stopping() {
+            log::info!("Stop requested, leaving the main loop");
+            return Ok(());
+        }
+
         // Update notifications (remove expired ones)
         app.update_notifications();
+        app.poll_pending_freezes();
 
         terminal.draw(|f| Canvas::draw(f, app))?;
 
-        match event_rx.recv() {
-            Ok(event) => match event {
+        // Wake up at least once per tick so timers and notifications keep moving
+        let first_event = match event_rx.recv_timeout(TICK_RATE) {
+            Ok(event) => event,
+            Err(RecvTimeoutError::Timeout) => continue,
+            Err(RecvTimeoutError::Disconnected) => {
+                log::error!("Event channel closed, shutting down");
+                return Ok(());
+            }
+        };
+
+        for event in drain_coalesced(first_event, &event_rx) {
+            match event {
+                CGroupEvent::Terminate => {
+                    log::info!("Terminate event received");
+                    return Ok(());
+                }
                 CGroupEvent::KeyInput(key_event) => {
+                    // An open dialog takes every key, including q and Esc
+                    if app.ui_state.dialog.is_some() {
+                        app.handle_dialog_key(key_event);
+                        continue;
+                    }
                     if event.is_quit_key() {
                         return Ok(());
                     }
                     handle_key_event(app, key_event);
                 }
+                // The main view does not react to the mouse while a dialog is open
+                CGroupEvent::MouseInput(mouse_event) if app.ui_state.dialog.is_none() => {
+                    handle_mouse_event(app, mouse_event);
+                }
                 CGroupEvent::Update(metrics) => {
-                    let cgroup_count = metrics.resource_usage.len();
-                    let process_count = metrics.processes.len();
-
-                    // Update tree state with new data
-                    app.ui_state
-                        .tree_state
-                        .build_from_paths(&metrics.resource_usage);
-
-                    // log::info!("metrics.resource_usage: {:?}", metrics.resource_usage);
-
-                    app.cgroup_data.metrics = Some(metrics);
-                    app.cgroup_data.last_update = Some(Instant::now());
-
-                    log::info!(
-                        "Updated cgroup metrics: {} cgroups, {} processes",
-                        cgroup_count,
-                        process_count
-                    );
+                    app.apply_metrics(metrics);
+                }
+                CGroupEvent::Resize => {
+                    // The next draw picks up the new size
+                    log::debug!("Terminal resized");
                 }
                 CGroupEvent::UpdateDummy => {}
                 _ => {}
-            },
-            Err(e) => {
-                log::error!("Error receiving event: {:?}", e);
             }
         }
+
+        if let Some(cgroup) = app.ui_state.pending_shell.take() {
+            run_shell(terminal, app, event_threads, &cgroup)?;
+        }
     }
 }
 
 fn handle_key_event(app: &mut App, key_event: crossterm::event::KeyEvent) {
-    use crossterm::event::{KeyCode, KeyModifiers};
+    use crossterm::event::KeyCode;
 
     match key_event.code {
         KeyCode::Char('D') => {
-            if let Some(selected_key) = &app.ui_state.tree_state.selected {
-                if let Some(node) = app.ui_state.tree_state.nodes.get(selected_key) {
-                    let parent_key = selected_key
-                        .rsplit_once('/')
-                        .map(|(parent, _)| parent.to_string())
-                        .unwrap_or_default();
-
-                    if parent_key.is_empty() {
-                        let root_path = app.ui_state.tree_state.root_path_string();
-                        let warning = format!("Cannot clean the root cgroup ({})", root_path);
-                        log::warn!("{}", warning);
-                        app.show_warning(warning);
-                    } else if let Some(parent_node) = app.ui_state.tree_state.nodes.get(&parent_key)
-                    {
-                        let parent_path = parent_node.path.clone();
-                        log::info!(
-                            "Clean parent requested for cgroup: {} (selected child: {})",
-                            parent_path,
-                            node.path
-                        );
-                        handle_delete_cgroup(app, &parent_path);
-                    } else {
-                        let warning = format!("Parent cgroup not found for {}", node.path);
-                        log::warn!("{}", warning);
-                        app.show_warning(warning);
-                    }
+            if let Some(selected_key) = &app.ui_state.tree_state.selected
+                && let Some(node) = app.ui_state.tree_state.nodes.get(selected_key)
+            {
+                let parent_key = selected_key
+                    .rsplit_once('/')
+                    .map(|(parent, _)| parent.to_string())
+                    .unwrap_or_default();
+
+                if parent_key.is_empty() {
+                    let root_path = app.ui_state.tree_state.root_path_string();
+                    let warning = format!("Cannot clean the root cgroup ({})", root_path);
+                    log::warn!("{}", warning);
+                    app.show_warning(warning);
+                } else if let Some(parent_node) = app.ui_state.tree_state.nodes.get(&parent_key) {
+                    let parent_path = parent_node.path.clone();
+                    log::info!(
+                        "Clean parent requested for cgroup: {} (selected child: {})",
+                        parent_path,
+                        node.path
+                    );
+                    app.request_delete(
+                        format!("Delete parent of {}", node.name),
+                        Path::new(&parent_path),
+                    );
+                } else {
+                    let warning = format!("Parent cgroup not found for {}", node.path);
+                    log::warn!("{}", warning);
+                    app.show_warning(warning);
                 }
             }
         }
+        KeyCode::Char('d') if !app.ui_state.tree_state.marked.is_empty() => {
+            app.request_delete_marked();
+        }
         KeyCode::Char('d') => {
-            // Execute recursive directory removal
-            if let Some(selected) = &app.ui_state.tree_state.selected {
-                if let Some(node) = app.ui_state.tree_state.nodes.get(selected) {
-                    let path = node.path.clone();
-                    handle_delete_cgroup(app, &path);
-                }
+            // Remove the selected cgroup and everything below it
+            if let Some(selected) = &app.ui_state.tree_state.selected
+                && let Some(node) = app.ui_state.tree_state.nodes.get(selected)
+            {
+                let path = node.path.clone();
+                app.request_delete("Delete cgroup".to_string(), Path::new(&path));
             }
         }
+        KeyCode::Char('f') => {
+            app.toggle_freeze();
+        }
+        KeyCode::Char('K') => {
+            app.request_kill();
+        }
+        KeyCode::Char('n') => {
+            app.request_new_child();
+        }
+        KeyCode::Char('S') => {
+            app.open_subtree_control_editor();
+        }
+        KeyCode::Char('M') => {
+            app.open_memory_editor();
+        }
+        KeyCode::Char('C') => {
+            app.open_cpu_editor();
+        }
+        KeyCode::Char('P') => {
+            app.open_pids_editor();
+        }
+        KeyCode::Char('I') => {
+            app.open_io_editor();
+        }
+        KeyCode::Char('v') => {
+            app.request_move_processes();
+        }
+        KeyCode::Char('V') => {
+            app.request_move_all();
+        }
+        KeyCode::Char('!') => {
+            app.request_shell();
+        }
+        KeyCode::Char('H') => {
+            app.open_history();
+        }
+        KeyCode::Char('e') => {
+            app.export_csv();
+        }
+        KeyCode::Char('s') if app.ui_state.current_tab == PROCESSES_TAB => {
+            app.open_signal_menu();
+        }
+        KeyCode::Char('s') => {
+            app.open_cgroup_signal_menu();
+        }
+        KeyCode::Char(' ') | KeyCode::Char('m') if app.ui_state.current_tab == PROCESSES_TAB => {
+            app.toggle_process_mark();
+            app.move_process_cursor(1);
+        }
+        KeyCode::Char(' ') | KeyCode::Char('m') => {
+            app.toggle_cgroup_mark();
+            app.ui_state.tree_state.select_next();
+            app.sync_selected_cgroup();
+        }
+        KeyCode::Char('c') => {
+            app.clear_marks();
+        }
+        KeyCode::Down if app.ui_state.current_tab == PROCESSES_TAB => {
+            app.move_process_cursor(1);
+        }
+        KeyCode::Up if app.ui_state.current_tab == PROCESSES_TAB => {
+            app.move_process_cursor(-1);
+        }
         KeyCode::Char('r') => {
             log::info!("Manual refresh requested");
-            // The collection thread will automatically provide updates
+            app.request_refresh();
+        }
+        KeyCode::Char('p') => {
+            app.toggle_pause();
+        }
+        KeyCode::Char('+') | KeyCode::Char('=') => {
+            app.adjust_update_interval(true);
+        }
+        KeyCode::Char('-') => {
+            app.adjust_update_interval(false);
         }
         KeyCode::Char('j') | KeyCode::Down => {
             // Navigate down in the tree
             app.ui_state.tree_state.select_next();
             // Update selected cgroup for resource display
-            app.ui_state.selected_cgroup = app
-                .ui_state
-                .tree_state
-                .selected
-                .clone()
-                .and_then(|path| app.ui_state.tree_state.nodes.get(&path))
-                .map(|node| node.path.clone());
+            app.sync_selected_cgroup();
         }
         KeyCode::Char('k') | KeyCode::Up => {
             // Navigate up in the tree
             app.ui_state.tree_state.select_previous();
             // Update selected cgroup for resource display
-            app.ui_state.selected_cgroup = app
-                .ui_state
-                .tree_state
-                .selected
-                .clone()
-                .and_then(|path| app.ui_state.tree_state.nodes.get(&path))
-                .map(|node| node.path.clone());
+            app.sync_selected_cgroup();
         }
         KeyCode::Tab => {
             // Switch between tabs/panels
-            app.ui_state.current_tab = (app.ui_state.current_tab + 1) % 3;
-            log::info!("Switched to tab {}", app.ui_state.current_tab);
+            app.select_tab(app.ui_state.current_tab + 1);
         }
-        KeyCode::Enter | KeyCode::Right | KeyCode::Char(' ') => {
+        KeyCode::Enter | KeyCode::Right => {
             // Expand/collapse selected node
             if let Some(selected) = app.ui_state.tree_state.selected.clone() {
                 app.ui_state.tree_state.toggle_expand(&selected);
@@ -295,18 +834,14 @@ fn handle_key_event(app: &mut App, key_event: crossterm::event::KeyEvent) {
         }
         KeyCode::Left => {
             // Collapse selected node
-            if let Some(selected) = app.ui_state.tree_state.selected.clone() {
-                if let Some(node) = app.ui_state.tree_state.nodes.get_mut(&selected) {
-                    if node.expanded {
-                        app.ui_state.tree_state.toggle_expand(&selected);
-                        log::info!("Collapsed: {}", selected);
-                    }
-                }
+            if let Some(selected) = app.ui_state.tree_state.selected.clone()
+                && let Some(node) = app.ui_state.tree_state.nodes.get_mut(&selected)
+                && node.expanded
+            {
+                app.ui_state.tree_state.toggle_expand(&selected);
+                log::info!("Collapsed: {}", selected);
             }
         }
-        KeyCode::Char('c') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
-            log::info!("Ctrl+C pressed - should quit");
-        }
         KeyCode::Char('?') => {
             log::info!("Help requested");
             // Could show help overlay
@@ -318,30 +853,79 @@ fn handle_key_event(app: &mut App, key_event: crossterm::event::KeyEvent) {
     }
 }
 
-fn handle_delete_cgroup(app: &mut app::App, cgroup_path: &str) {
-    use cgtop::utils::{is_safe_to_remove, remove_dir_recursive_safe};
+fn handle_mouse_event(app: &mut App, mouse_event: crossterm::event::MouseEvent) {
+    use crossterm::event::{MouseButton, MouseEventKind};
+    use ratatui::layout::Position;
+
+    // Rows moved per wheel notch in the process list
+    const SCROLL_STEP: isize = 3;
+
+    let position = Position::new(mouse_event.column, mouse_event.row);
+    let layout = app.ui_state.layout.clone();
+
+    match mouse_event.kind {
+        MouseEventKind::Down(MouseButton::Left) => {
+            if let Some(tab) = layout.tabs.iter().position(|area| area.contains(position)) {
+                app.select_tab(tab);
+            } else if let Some((column, _)) = layout
+                .process_header
+                .iter()
+                .find(|(_, area)| area.contains(position))
+            {
+                app.ui_state.process_sort.toggle(*column);
+                log::info!("Sorting processes by {:?}", app.ui_state.process_sort);
+            } else if layout.tree.contains(position) {
+                handle_tree_click(app, position);
+            }
+        }
+        MouseEventKind::ScrollDown => {
+            if layout.tree.contains(position) {
+                app.ui_state.tree_state.select_next();
+                app.sync_selected_cgroup();
+            } else if layout.process_list.contains(position) {
+                app.scroll_processes(SCROLL_STEP);
+            }
+        }
+        MouseEventKind::ScrollUp => {
+            if layout.tree.contains(position) {
+                app.ui_state.tree_state.select_previous();
+                app.sync_selected_cgroup();
+            } else if layout.process_list.contains(position) {
+                app.scroll_processes(-SCROLL_STEP);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn handle_tree_click(app: &mut App, position: ratatui::layout::Position) {
+    use cgtop::widgets::CGroupTreeWidget;
 
-    log::info!("Delete requested for cgroup: {}", cgroup_path);
+    let tree_area = app.ui_state.layout.tree;
 
-    // Safety check
-    if !is_safe_to_remove(cgroup_path) {
-        let error_msg = format!("Unsafe path: {}", cgroup_path);
-        log::error!("Refusing to delete unsafe path: {}", cgroup_path);
-        app.show_error(error_msg);
+    // Ignore clicks on the border
+    if position.y <= tree_area.y || position.y + 1 >= tree_area.bottom() {
         return;
     }
 
-    // Attempt to remove the directory
-    match remove_dir_recursive_safe(cgroup_path) {
-        Ok(_) => {
-            let success_msg = format!("Deleted: {}", cgroup_path);
-            log::info!("Successfully deleted cgroup directory: {}", cgroup_path);
-            app.show_success(success_msg);
-        }
-        Err(e) => {
-            let error_msg = format!("Delete failed: {}", e);
-            log::error!("Failed to delete cgroup directory {}: {}", cgroup_path, e);
-            app.show_error(error_msg);
-        }
+    let row = (position.y - tree_area.y - 1) as usize;
+    let tree_state = &mut app.ui_state.tree_state;
+    let Some(key) = tree_state.node_at_row(row).cloned() else {
+        return;
+    };
+    let Some(node) = tree_state.nodes.get(&key) else {
+        return;
+    };
+
+    // The glyph is two columns wide ("▶ " / "▼ ")
+    let glyph_x =
+        tree_area.x + 1 + CGroupTreeWidget::expand_indicator_offset(node, tree_state) as u16;
+    let on_glyph = !node.children.is_empty() && (glyph_x..glyph_x + 2).contains(&position.x);
+
+    tree_state.selected = Some(key.clone());
+    if on_glyph {
+        tree_state.toggle_expand(&key);
+        log::info!("Toggled expand for: {}", key);
     }
+    app.sync_selected_cgroup();
 }
@@ -1,11 +1,13 @@
 use ratatui::{
     Frame,
     layout::Rect,
-    style::{Color, Style},
+    style::Style,
     widgets::{Block, Borders, Clear, Paragraph},
 };
 use std::time::{Duration, Instant};
 
+use crate::theme::Theme;
+
 #[derive(Debug, Clone)]
 pub struct Notification {
     pub message: String,
@@ -127,7 +129,12 @@ fn bottom_right_popup_area(area: Rect, width: u16, height: u16) -> Rect {
     }
 }
 
-pub fn render_notifications(frame: &mut Frame, notifications: &NotificationManager, area: Rect) {
+pub fn render_notifications(
+    frame: &mut Frame,
+    notifications: &NotificationManager,
+    theme: &Theme,
+    area: Rect,
+) {
     if let Some(notification) = notifications.get_latest() {
         let notification_width = 50;
         let notification_height = 3;
@@ -139,10 +146,10 @@ pub fn render_notifications(frame: &mut Frame, notifications: &NotificationManag
 
         // Style based on notification type
         let (border_color, text_color, title) = match notification.notification_type {
-            NotificationType::Error => (Color::Red, Color::White, "Error"),
-            NotificationType::Warning => (Color::Yellow, Color::Black, "Warning"),
-            NotificationType::Info => (Color::Blue, Color::White, "Info"),
-            NotificationType::Success => (Color::Green, Color::White, "Success"),
+            NotificationType::Error => (theme.critical, theme.text, "Error"),
+            NotificationType::Warning => (theme.warning, theme.text, "Warning"),
+            NotificationType::Info => (theme.primary, theme.text, "Info"),
+            NotificationType::Success => (theme.good, theme.text, "Success"),
         };
 
         // Create the notification widget
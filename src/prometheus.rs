@@ -0,0 +1,468 @@
+use anyhow::{Context, Result};
+use std::fmt::Write as _;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::collection::{CGroupCollector, CGroupMetrics, ResourceStats};
+use crate::columns::display_path;
+
+/// Content type of the Prometheus text exposition format
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Suffixes of the systemd unit types that own cgroups
+const UNIT_SUFFIXES: &[&str] = &[".service", ".scope", ".slice", ".socket", ".mount", ".swap"];
+
+/// File maintained by `cgtop textfile`; node_exporter reads every `*.prom` file
+pub const TEXTFILE_NAME: &str = "cgtop.prom";
+
+/// How long a client may take to send its whole request before it is dropped
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Bytes of request line and headers read before the rest is ignored
+const MAX_REQUEST_BYTES: u64 = 8192;
+
+/// Connections answered at once; further clients are closed until one finishes
+const MAX_CONNECTIONS: usize = 32;
+
+/// Which cgroups are exported
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExporterOptions {
+    /// Skip cgroups more than this many levels below the root (the root is level 0)
+    pub max_depth: Option<usize>,
+}
+
+/// One metric family, read from the stats of every exported cgroup
+struct Family {
+    name: &'static str,
+    kind: &'static str,
+    help: &'static str,
+    /// `None` leaves the cgroup out, e.g. for an unset limit
+    value: fn(&ResourceStats) -> Option<f64>,
+}
+
+fn usec_to_secs(usec: u64) -> f64 {
+    usec as f64 / 1_000_000.0
+}
+
+const FAMILIES: &[Family] = &[
+    Family {
+        name: "cgtop_memory_current_bytes",
+        kind: "gauge",
+        help: "Memory in use (memory.current)",
+        value: |stats| Some(stats.memory.current as f64),
+    },
+    Family {
+        name: "cgtop_memory_peak_bytes",
+        kind: "gauge",
+        help: "Largest memory use recorded (memory.peak)",
+        value: |stats| Some(stats.memory.peak as f64),
+    },
+    Family {
+        name: "cgtop_memory_max_bytes",
+        kind: "gauge",
+        help: "Hard memory limit (memory.max), absent when unlimited",
+        value: |stats| stats.memory.max.map(|max| max as f64),
+    },
+    Family {
+        name: "cgtop_memory_high_bytes",
+        kind: "gauge",
+        help: "Memory throttling limit (memory.high), absent when unlimited",
+        value: |stats| stats.memory.high.map(|high| high as f64),
+    },
+    Family {
+        name: "cgtop_memory_events_high_total",
+        kind: "counter",
+        help: "Times the cgroup was throttled for exceeding memory.high",
+        value: |stats| Some(stats.memory.events.high as f64),
+    },
+    Family {
+        name: "cgtop_memory_events_max_total",
+        kind: "counter",
+        help: "Times the cgroup reached memory.max",
+        value: |stats| Some(stats.memory.events.max as f64),
+    },
+    Family {
+        name: "cgtop_memory_events_oom_total",
+        kind: "counter",
+        help: "Times the cgroup ran out of memory",
+        value: |stats| Some(stats.memory.events.oom as f64),
+    },
+    Family {
+        name: "cgtop_memory_events_oom_kill_total",
+        kind: "counter",
+        help: "Processes killed by the OOM killer",
+        value: |stats| Some(stats.memory.events.oom_kill as f64),
+    },
+    Family {
+        name: "cgtop_memory_pressure_some_seconds_total",
+        kind: "counter",
+        help: "Time some tasks stalled on memory (memory.pressure)",
+        value: |stats| {
+            let pressure = stats.memory.pressure.as_ref()?;
+            Some(usec_to_secs(pressure.some_total))
+        },
+    },
+    Family {
+        name: "cgtop_memory_pressure_full_seconds_total",
+        kind: "counter",
+        help: "Time all tasks stalled on memory (memory.pressure)",
+        value: |stats| {
+            let pressure = stats.memory.pressure.as_ref()?;
+            Some(usec_to_secs(pressure.full_total))
+        },
+    },
+    Family {
+        name: "cgtop_cpu_usage_seconds_total",
+        kind: "counter",
+        help: "CPU time used (usage_usec in cpu.stat)",
+        value: |stats| Some(usec_to_secs(stats.cpu.usage_usec)),
+    },
+    Family {
+        name: "cgtop_cpu_user_seconds_total",
+        kind: "counter",
+        help: "CPU time used in user mode",
+        value: |stats| Some(usec_to_secs(stats.cpu.user_usec)),
+    },
+    Family {
+        name: "cgtop_cpu_system_seconds_total",
+        kind: "counter",
+        help: "CPU time used in kernel mode",
+        value: |stats| Some(usec_to_secs(stats.cpu.system_usec)),
+    },
+    Family {
+        name: "cgtop_cpu_throttled_periods_total",
+        kind: "counter",
+        help: "Enforcement periods in which cpu.max throttled the cgroup",
+        value: |stats| Some(stats.cpu.nr_throttled as f64),
+    },
+    Family {
+        name: "cgtop_cpu_throttled_seconds_total",
+        kind: "counter",
+        help: "Time the cgroup was throttled by cpu.max",
+        value: |stats| Some(usec_to_secs(stats.cpu.throttled_usec)),
+    },
+    Family {
+        name: "cgtop_io_read_bytes_total",
+        kind: "counter",
+        help: "Bytes read from block devices (io.stat)",
+        value: |stats| Some(stats.io.rbytes as f64),
+    },
+    Family {
+        name: "cgtop_io_written_bytes_total",
+        kind: "counter",
+        help: "Bytes written to block devices (io.stat)",
+        value: |stats| Some(stats.io.wbytes as f64),
+    },
+    Family {
+        name: "cgtop_io_reads_total",
+        kind: "counter",
+        help: "Read operations on block devices (io.stat)",
+        value: |stats| Some(stats.io.rios as f64),
+    },
+    Family {
+        name: "cgtop_io_writes_total",
+        kind: "counter",
+        help: "Write operations on block devices (io.stat)",
+        value: |stats| Some(stats.io.wios as f64),
+    },
+    Family {
+        name: "cgtop_pids_current",
+        kind: "gauge",
+        help: "Tasks in the cgroup (pids.current)",
+        value: |stats| Some(stats.pids.current as f64),
+    },
+    Family {
+        name: "cgtop_pids_max",
+        kind: "gauge",
+        help: "Task limit (pids.max), absent when unlimited",
+        value: |stats| stats.pids.max.map(|max| max as f64),
+    },
+    Family {
+        name: "cgtop_frozen",
+        kind: "gauge",
+        help: "1 if the cgroup is frozen (cgroup.events)",
+        value: |stats| Some(if stats.frozen { 1.0 } else { 0.0 }),
+    },
+];
+
+/// Render `metrics` in the Prometheus text format. Every series is labelled with
+/// the cgroup path relative to `root` and, when the path names them, the systemd
+/// unit and the container id.
+pub fn render(metrics: &CGroupMetrics, root: &Path, options: &ExporterOptions) -> String {
+    let mut cgroups: Vec<_> = metrics
+        .resource_usage
+        .iter()
+        .filter_map(|(path, stats)| {
+            let cgroup = display_path(path, root);
+            let depth = cgroup.split('/').filter(|part| !part.is_empty()).count();
+            let included = options.max_depth.is_none_or(|max_depth| depth <= max_depth);
+            included.then(|| (labels(&cgroup), stats))
+        })
+        .collect();
+    cgroups.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    let mut out = String::new();
+    for family in FAMILIES {
+        let _ = writeln!(out, "# HELP {} {}", family.name, family.help);
+        let _ = writeln!(out, "# TYPE {} {}", family.name, family.kind);
+        for (labels, stats) in &cgroups {
+            if let Some(value) = (family.value)(stats) {
+                let _ = writeln!(out, "{}{{{}}} {}", family.name, labels, value);
+            }
+        }
+    }
+    out
+}
+
+/// Label set of the cgroup shown as `cgroup` (`/system.slice/nginx.service`)
+fn labels(cgroup: &str) -> String {
+    let mut labels = format!("cgroup=\"{}\"", escape_label(cgroup));
+    if let Some(unit) = systemd_unit(cgroup) {
+        let _ = write!(labels, ",unit=\"{}\"", escape_label(unit));
+    }
+    if let Some(id) = container_id(cgroup) {
+        let _ = write!(labels, ",container_id=\"{}\"", id);
+    }
+    labels
+}
+
+/// The innermost systemd unit on the path
+pub fn systemd_unit(cgroup: &str) -> Option<&str> {
+    cgroup.rsplit('/').find(|part| {
+        UNIT_SUFFIXES
+            .iter()
+            .any(|suffix| part.len() > suffix.len() && part.ends_with(suffix))
+    })
+}
+
+/// The innermost 64-digit container id on the path, as used by Docker, Podman
+/// and containerd (`docker-<id>.scope`, `cri-containerd-<id>.scope`, `/docker/<id>`)
+pub fn container_id(cgroup: &str) -> Option<&str> {
+    cgroup.rsplit('/').find_map(|part| {
+        let name = part.strip_suffix(".scope").unwrap_or(part);
+        let id = name.rsplit(['-', ':']).next()?;
+        (id.len() == 64 && id.bytes().all(|byte| byte.is_ascii_hexdigit())).then_some(id)
+    })
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Samples the hierarchy at most once per `max_age`, however often it is scraped
+pub struct MetricsCache {
+    collector: CGroupCollector,
+    options: ExporterOptions,
+    max_age: Duration,
+    rendered: Option<(Instant, String)>,
+}
+
+impl MetricsCache {
+    pub fn new(collector: CGroupCollector, options: ExporterOptions, max_age: Duration) -> Self {
+        Self {
+            collector,
+            options,
+            max_age,
+            rendered: None,
+        }
+    }
+
+    /// The exposition of the newest sample, collecting a new one if it is too old
+    pub fn get(&mut self) -> Result<&str> {
+        let fresh = self
+            .rendered
+            .as_ref()
+            .is_some_and(|(rendered_at, _)| rendered_at.elapsed() < self.max_age);
+        if !fresh {
+            let metrics = self.collector.collect_metrics()?;
+            let body = render(&metrics, &self.collector.cgroup_root, &self.options);
+            self.rendered = Some((Instant::now(), body));
+        }
+        Ok(self.rendered.as_ref().map_or("", |(_, body)| body))
+    }
+}
+
+/// Answer HTTP requests on `listener`, each connection on its own thread, serving
+/// `/metrics` from `cache`
+pub fn serve(listener: TcpListener, cache: MetricsCache) -> Result<()> {
+    let cache = Arc::new(Mutex::new(cache));
+    let active = Arc::new(AtomicUsize::new(0));
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                log::warn!("Failed to accept a scrape: {}", e);
+                continue;
+            }
+        };
+        if active.fetch_add(1, Ordering::SeqCst) >= MAX_CONNECTIONS {
+            active.fetch_sub(1, Ordering::SeqCst);
+            log::warn!(
+                "Dropping a scrape, {} are already in progress",
+                MAX_CONNECTIONS
+            );
+            continue;
+        }
+
+        let cache = Arc::clone(&cache);
+        let active = Arc::clone(&active);
+        thread::spawn(move || {
+            if let Err(e) = handle_connection(stream, &cache) {
+                log::warn!("Failed to answer a scrape: {}", e);
+            }
+            active.fetch_sub(1, Ordering::SeqCst);
+        });
+    }
+    Ok(())
+}
+
+/// Reads from a stream until a fixed point in time, however slowly the peer sends
+struct DeadlineReader {
+    stream: TcpStream,
+    deadline: Instant,
+}
+
+impl Read for DeadlineReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let remaining = self.deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                "client did not send its request in time",
+            ));
+        }
+        self.stream.set_read_timeout(Some(remaining))?;
+        self.stream.read(buf)
+    }
+}
+
+fn handle_connection(mut stream: TcpStream, cache: &Mutex<MetricsCache>) -> io::Result<()> {
+    let reader = DeadlineReader {
+        stream: stream.try_clone()?,
+        deadline: Instant::now() + REQUEST_TIMEOUT,
+    };
+    let mut reader = BufReader::new(reader.take(MAX_REQUEST_BYTES));
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // Headers and bodies are not needed for any route
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 0 && !header.trim().is_empty() {
+        header.clear();
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default();
+    let target = parts.next().unwrap_or_default();
+    let path = target.split('?').next().unwrap_or_default();
+
+    let (status, content_type, body) = match (method, path) {
+        _ if !request_line.ends_with('\n') => (
+            "400 Bad Request",
+            "text/plain; charset=utf-8",
+            "request line too long\n".to_string(),
+        ),
+        ("GET", "/metrics") => match cache.lock().unwrap_or_else(PoisonError::into_inner).get() {
+            Ok(body) => ("200 OK", CONTENT_TYPE, body.to_string()),
+            Err(e) => {
+                log::error!("Failed to collect metrics: {}", e);
+                (
+                    "500 Internal Server Error",
+                    "text/plain; charset=utf-8",
+                    format!("failed to collect metrics: {}\n", e),
+                )
+            }
+        },
+        ("GET", "/") => (
+            "200 OK",
+            "text/plain; charset=utf-8",
+            "cgtop exporter, metrics are at /metrics\n".to_string(),
+        ),
+        ("GET", _) => (
+            "404 Not Found",
+            "text/plain; charset=utf-8",
+            "not found\n".to_string(),
+        ),
+        _ => (
+            "405 Method Not Allowed",
+            "text/plain; charset=utf-8",
+            "only GET is supported\n".to_string(),
+        ),
+    };
+    log::debug!("{} {} -> {}", method, target, status);
+
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    )?;
+    stream.flush()
+}
+
+/// Replace `dir/cgtop.prom` with `content` in one step: the text goes to a hidden
+/// file in the same directory first and is renamed over the old one, so
+/// node_exporter never reads a partial file
+pub fn write_textfile(dir: &Path, content: &str) -> io::Result<PathBuf> {
+    let target = dir.join(TEXTFILE_NAME);
+    let temp = dir.join(format!(".{}.{}.tmp", TEXTFILE_NAME, process::id()));
+
+    let result = File::create(&temp)
+        .and_then(|mut file| {
+            file.write_all(content.as_bytes())?;
+            file.sync_all()
+        })
+        .and_then(|()| fs::rename(&temp, &target));
+    if result.is_err() {
+        let _ = fs::remove_file(&temp);
+    }
+    result.map(|()| target)
+}
+
+/// Sample the hierarchy every `interval` and rewrite the textfile in `dir`;
+/// `iterations` limits the number of samples. Only the first sample has to
+/// succeed: later failures are logged and retried on the next interval.
+pub fn run_textfile(
+    collector: &CGroupCollector,
+    options: &ExporterOptions,
+    dir: &Path,
+    interval: Duration,
+    iterations: Option<u64>,
+) -> Result<()> {
+    let mut iteration = 0;
+    loop {
+        match write_sample(collector, options, dir) {
+            Ok(target) => log::debug!("Wrote {}", target.display()),
+            Err(e) if iteration == 0 => return Err(e),
+            Err(e) => log::error!("{:#}", e),
+        }
+
+        iteration += 1;
+        if iterations.is_some_and(|iterations| iteration >= iterations) {
+            return Ok(());
+        }
+        thread::sleep(interval);
+    }
+}
+
+fn write_sample(
+    collector: &CGroupCollector,
+    options: &ExporterOptions,
+    dir: &Path,
+) -> Result<PathBuf> {
+    let metrics = collector.collect_metrics()?;
+    let content = render(&metrics, &collector.cgroup_root, options);
+    write_textfile(dir, &content)
+        .with_context(|| format!("failed to write {}", dir.join(TEXTFILE_NAME).display()))
+}
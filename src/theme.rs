@@ -0,0 +1,268 @@
+use ratatui::style::{Color, Modifier, Style};
+use serde::Deserialize;
+use std::env;
+
+/// Built-in colour schemes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum ThemeName {
+    #[default]
+    Dark,
+    Light,
+    HighContrast,
+    Monochrome,
+}
+
+/// How many colours the terminal can display
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorSupport {
+    None,
+    Ansi16,
+    Ansi256,
+    TrueColor,
+}
+
+impl ColorSupport {
+    /// Inspect `NO_COLOR`, `TERM` and `COLORTERM` of the current process
+    pub fn detect() -> Self {
+        let no_color = env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+        Self::from_env(
+            no_color,
+            env::var("TERM").ok().as_deref(),
+            env::var("COLORTERM").ok().as_deref(),
+        )
+    }
+
+    pub fn from_env(no_color: bool, term: Option<&str>, colorterm: Option<&str>) -> Self {
+        // https://no-color.org: any non-empty value disables colour
+        if no_color {
+            return ColorSupport::None;
+        }
+
+        let term = term.unwrap_or_default();
+        if term.is_empty() || term == "dumb" {
+            return ColorSupport::None;
+        }
+
+        if matches!(colorterm, Some("truecolor") | Some("24bit")) {
+            ColorSupport::TrueColor
+        } else if term.contains("256color") {
+            ColorSupport::Ansi256
+        } else {
+            ColorSupport::Ansi16
+        }
+    }
+
+    /// Downgrade `color` to something the terminal can display
+    pub fn adapt(self, color: Color) -> Color {
+        match (self, color) {
+            (ColorSupport::None, _) => Color::Reset,
+            (ColorSupport::TrueColor, _) => color,
+            (ColorSupport::Ansi256, Color::Rgb(r, g, b)) => Color::Indexed(rgb_to_ansi256(r, g, b)),
+            (ColorSupport::Ansi16, Color::Rgb(r, g, b)) => nearest_ansi16(r, g, b),
+            (ColorSupport::Ansi16, Color::Indexed(index)) => {
+                let (r, g, b) = ansi256_to_rgb(index);
+                nearest_ansi16(r, g, b)
+            }
+            _ => color,
+        }
+    }
+}
+
+/// Colour slots used by every widget, so a palette change happens in one place
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    pub name: ThemeName,
+    /// Borders, tree glyphs and informational accents
+    pub primary: Color,
+    /// Secondary section headings
+    pub secondary: Color,
+    /// Paths, CPU figures and other highlighted values
+    pub accent: Color,
+    /// Regular text
+    pub text: Color,
+    /// Explanatory text
+    pub muted: Color,
+    /// Tree connectors and inactive values
+    pub dim: Color,
+    pub good: Color,
+    pub warning: Color,
+    pub critical: Color,
+    /// Selected tree row and active tab
+    pub selected: Style,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::builtin(ThemeName::Dark)
+    }
+}
+
+impl Theme {
+    pub fn builtin(name: ThemeName) -> Self {
+        match name {
+            ThemeName::Dark => Self {
+                name,
+                primary: Color::Blue,
+                secondary: Color::Magenta,
+                accent: Color::Cyan,
+                text: Color::White,
+                muted: Color::Gray,
+                dim: Color::DarkGray,
+                good: Color::Green,
+                warning: Color::Yellow,
+                critical: Color::Red,
+                selected: Style::default()
+                    .fg(Color::Black)
+                    .bg(Color::Green)
+                    .add_modifier(Modifier::BOLD),
+            },
+            // Saturated, darker tones that stay readable on white backgrounds
+            ThemeName::Light => Self {
+                name,
+                primary: Color::Rgb(0x1f, 0x4e, 0xa8),
+                secondary: Color::Rgb(0x8a, 0x2b, 0x8f),
+                accent: Color::Rgb(0x00, 0x6d, 0x77),
+                text: Color::Rgb(0x1c, 0x1c, 0x1c),
+                muted: Color::Rgb(0x5c, 0x5c, 0x5c),
+                dim: Color::Rgb(0x8a, 0x8a, 0x8a),
+                good: Color::Rgb(0x1e, 0x7b, 0x34),
+                warning: Color::Rgb(0x9a, 0x5b, 0x00),
+                critical: Color::Rgb(0xb3, 0x1d, 0x1d),
+                selected: Style::default()
+                    .fg(Color::Rgb(0xff, 0xff, 0xff))
+                    .bg(Color::Rgb(0x1f, 0x4e, 0xa8))
+                    .add_modifier(Modifier::BOLD),
+            },
+            ThemeName::HighContrast => Self {
+                name,
+                primary: Color::White,
+                secondary: Color::LightMagenta,
+                accent: Color::LightCyan,
+                text: Color::White,
+                muted: Color::White,
+                dim: Color::Gray,
+                good: Color::LightGreen,
+                warning: Color::LightYellow,
+                critical: Color::LightRed,
+                selected: Style::default()
+                    .fg(Color::Black)
+                    .bg(Color::LightYellow)
+                    .add_modifier(Modifier::BOLD),
+            },
+            ThemeName::Monochrome => Self {
+                name,
+                primary: Color::Reset,
+                secondary: Color::Reset,
+                accent: Color::Reset,
+                text: Color::Reset,
+                muted: Color::Reset,
+                dim: Color::Reset,
+                good: Color::Reset,
+                warning: Color::Reset,
+                critical: Color::Reset,
+                selected: Style::default().add_modifier(Modifier::REVERSED | Modifier::BOLD),
+            },
+        }
+    }
+
+    /// Built-in theme adjusted to what the terminal can display
+    pub fn for_terminal(name: ThemeName, support: ColorSupport) -> Self {
+        if support == ColorSupport::None {
+            return Self::builtin(ThemeName::Monochrome);
+        }
+
+        let mut theme = Self::builtin(name);
+        for color in [
+            &mut theme.primary,
+            &mut theme.secondary,
+            &mut theme.accent,
+            &mut theme.text,
+            &mut theme.muted,
+            &mut theme.dim,
+            &mut theme.good,
+            &mut theme.warning,
+            &mut theme.critical,
+        ] {
+            *color = support.adapt(*color);
+        }
+        theme.selected.fg = theme.selected.fg.map(|color| support.adapt(color));
+        theme.selected.bg = theme.selected.bg.map(|color| support.adapt(color));
+        theme
+    }
+
+    pub fn fg(&self, color: Color) -> Style {
+        Style::default().fg(color)
+    }
+
+    pub fn bold(&self, color: Color) -> Style {
+        Style::default().fg(color).add_modifier(Modifier::BOLD)
+    }
+}
+
+fn rgb_to_ansi256(r: u8, g: u8, b: u8) -> u8 {
+    // Greys map better onto the 24-step grey ramp than onto the colour cube
+    if r == g && g == b {
+        return match r {
+            0..=7 => 16,
+            249..=255 => 231,
+            _ => 232 + ((r as u16 - 8) * 24 / 247) as u8,
+        };
+    }
+
+    // The cube levels are 0, 95, 135, 175, 215 and 255
+    let scale = |c: u8| match c {
+        0..=47 => 0,
+        48..=114 => 1,
+        _ => (c - 35) / 40,
+    };
+    16 + 36 * scale(r) + 6 * scale(g) + scale(b)
+}
+
+fn ansi256_to_rgb(index: u8) -> (u8, u8, u8) {
+    match index {
+        0..=15 => ANSI16[index as usize].1,
+        16..=231 => {
+            let level = |c: u8| if c == 0 { 0 } else { 55 + c * 40 };
+            let i = index - 16;
+            (level(i / 36), level((i / 6) % 6), level(i % 6))
+        }
+        _ => {
+            let grey = 8 + (index - 232) * 10;
+            (grey, grey, grey)
+        }
+    }
+}
+
+/// xterm's default values for the 16 named colours
+const ANSI16: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::Red, (205, 0, 0)),
+    (Color::Green, (0, 205, 0)),
+    (Color::Yellow, (205, 205, 0)),
+    (Color::Blue, (0, 0, 238)),
+    (Color::Magenta, (205, 0, 205)),
+    (Color::Cyan, (0, 205, 205)),
+    (Color::Gray, (229, 229, 229)),
+    (Color::DarkGray, (127, 127, 127)),
+    (Color::LightRed, (255, 0, 0)),
+    (Color::LightGreen, (0, 255, 0)),
+    (Color::LightYellow, (255, 255, 0)),
+    (Color::LightBlue, (92, 92, 255)),
+    (Color::LightMagenta, (255, 0, 255)),
+    (Color::LightCyan, (0, 255, 255)),
+    (Color::White, (255, 255, 255)),
+];
+
+fn nearest_ansi16(r: u8, g: u8, b: u8) -> Color {
+    let distance = |(cr, cg, cb): (u8, u8, u8)| {
+        let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2);
+        d(r, cr) + d(g, cg) + d(b, cb)
+    };
+
+    ANSI16
+        .iter()
+        .min_by_key(|(_, rgb)| distance(*rgb))
+        .map(|(color, _)| *color)
+        .unwrap_or(Color::Reset)
+}
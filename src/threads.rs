@@ -1,128 +1,267 @@
 use anyhow::Result;
-use crossbeam::channel::{Receiver, Sender, unbounded};
-use std::{path::PathBuf, thread, time::Duration};
+use chrono::Utc;
+use crossbeam::channel::{Receiver, RecvTimeoutError, Sender, unbounded};
+use std::{
+    path::{Path, PathBuf},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread,
+    time::{Duration, Instant},
+};
 
 use crate::{
     collection::{
         CGroupCollector, CGroupMetrics, CpuStats, IoStats, MemoryStats, PidStats, ResourceStats,
     },
-    events::CGroupEvent,
+    events::{CGroupEvent, CollectionCommand},
 };
 
 use crossterm::event::Event;
 use crossterm::event::KeyEventKind;
 use std::thread::JoinHandle;
 
+#[derive(Default)]
 pub struct EventThreads {
     input_handle: Option<JoinHandle<()>>,
     collection_handle: Option<JoinHandle<()>>,
-    cleanup_handle: Option<JoinHandle<()>>,
+    control_tx: Option<Sender<CollectionCommand>>,
+    stop: Arc<AtomicBool>,
+    input_pause: Arc<InputPause>,
+}
+
+/// Lets the UI borrow the terminal's input from the input thread, e.g. for a shell
+#[derive(Default)]
+struct InputPause {
+    requested: AtomicBool,
+    /// Set by the input thread once it no longer reads from the terminal
+    idle: AtomicBool,
 }
 
 impl EventThreads {
     pub fn new() -> Self {
-        Self {
-            input_handle: None,
-            collection_handle: None,
-            cleanup_handle: None,
-        }
+        Self::default()
     }
 
-    pub fn start(&mut self, cgroup_root: PathBuf) -> Result<Receiver<CGroupEvent>> {
+    pub fn start(
+        &mut self,
+        cgroup_root: PathBuf,
+        update_interval: Duration,
+    ) -> Result<Receiver<CGroupEvent>> {
         let (event_tx, event_rx) = unbounded::<CGroupEvent>();
+        let (control_tx, control_rx) = unbounded::<CollectionCommand>();
 
         let event_tx0 = event_tx.clone();
+        let stop0 = Arc::clone(&self.stop);
+        let pause0 = Arc::clone(&self.input_pause);
         // Start input thread
         self.input_handle = Some(thread::spawn(move || {
-            input_thread_worker(event_tx0);
+            input_thread_worker(event_tx0, stop0, pause0);
         }));
 
         let event_tx1 = event_tx.clone();
+        let stop1 = Arc::clone(&self.stop);
 
         self.collection_handle = Some(thread::spawn(move || {
-            collection_thread_worker(event_tx1, cgroup_root);
+            collection_thread_worker(event_tx1, control_rx, stop1, cgroup_root, update_interval);
         }));
 
+        self.control_tx = Some(control_tx);
+
         Ok(event_rx)
     }
+
+    /// Sender used by the UI to steer the collection thread
+    pub fn control_sender(&self) -> Option<Sender<CollectionCommand>> {
+        self.control_tx.clone()
+    }
+
+    /// Flag shared by the worker threads; setting it asks all of them to stop
+    pub fn stop_flag(&self) -> Arc<AtomicBool> {
+        Arc::clone(&self.stop)
+    }
+
+    pub fn is_stopping(&self) -> bool {
+        self.stop.load(Ordering::SeqCst)
+    }
+
+    /// Stop reading terminal input; returns once the input thread has let go of the terminal
+    pub fn pause_input(&self) {
+        // Only an acknowledgement given after this request counts
+        self.input_pause.idle.store(false, Ordering::SeqCst);
+        self.input_pause.requested.store(true, Ordering::SeqCst);
+        while self.input_handle.is_some()
+            && !self.input_pause.idle.load(Ordering::SeqCst)
+            && !self.is_stopping()
+        {
+            thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    pub fn resume_input(&self) {
+        self.input_pause.requested.store(false, Ordering::SeqCst);
+    }
+
+    /// Stop the worker threads and wait for them to exit
+    pub fn shutdown(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+
+        // Wake the collection thread in case it is sleeping or paused
+        if let Some(control_tx) = self.control_tx.take() {
+            let _ = control_tx.send(CollectionCommand::Shutdown);
+        }
+
+        for (name, handle) in [
+            ("input", self.input_handle.take()),
+            ("collection", self.collection_handle.take()),
+        ] {
+            if let Some(handle) = handle
+                && handle.join().is_err()
+            {
+                log::error!("The {} thread panicked", name);
+            }
+        }
+    }
+}
+
+impl Drop for EventThreads {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
 }
 
-fn input_thread_worker(sender: Sender<CGroupEvent>) {
+fn input_thread_worker(sender: Sender<CGroupEvent>, stop: Arc<AtomicBool>, pause: Arc<InputPause>) {
     log::info!("Input thread started)");
 
-    loop {
-        if let Ok(pool) = crossterm::event::poll(Duration::from_millis(20)) {
-            if pool {
-                if let Ok(event) = crossterm::event::read() {
-                    match event {
-                        Event::Key(key_event) if key_event.kind == KeyEventKind::Press => {
-                            if sender.send(CGroupEvent::KeyInput(key_event)).is_err() {
-                                break;
-                            }
-                        }
-                        _ => {}
-                    }
+    while !stop.load(Ordering::SeqCst) {
+        if pause.requested.load(Ordering::SeqCst) {
+            pause.idle.store(true, Ordering::SeqCst);
+            thread::sleep(Duration::from_millis(20));
+            continue;
+        }
+
+        if let Ok(true) = crossterm::event::poll(Duration::from_millis(20))
+            && let Ok(event) = crossterm::event::read()
+        {
+            let event = match event {
+                Event::Key(key_event) if key_event.kind == KeyEventKind::Press => {
+                    CGroupEvent::KeyInput(key_event)
                 }
+                Event::Mouse(mouse_event) => CGroupEvent::MouseInput(mouse_event),
+                Event::Resize(_, _) => CGroupEvent::Resize,
+                _ => continue,
+            };
+
+            if sender.send(event).is_err() {
+                break;
             }
         }
     }
 
+    // Let the main loop know in case the stop came from a signal
+    let _ = sender.send(CGroupEvent::Terminate);
+
     log::info!("Input thread stopped");
 }
 
-fn collection_thread_worker(sender: Sender<CGroupEvent>, cgroup_root: PathBuf) {
+fn collection_thread_worker(
+    sender: Sender<CGroupEvent>,
+    control: Receiver<CollectionCommand>,
+    stop: Arc<AtomicBool>,
+    cgroup_root: PathBuf,
+    mut interval: Duration,
+) {
     log::info!(
-        "Collection thread started with root: {}",
-        cgroup_root.display()
+        "Collection thread started with root: {} (interval: {:?})",
+        cgroup_root.display(),
+        interval
     );
 
-    loop {
-        // sleep for 200ms
-        // TODO: use the proper collection logic
-        thread::sleep(Duration::from_millis(200));
+    // Try to use mock data first for testing in sandbox environments
+    let use_mock_data =
+        std::env::var("CGTOP_USE_MOCK").unwrap_or_else(|_| "false".to_string()) == "true";
+    let collector = CGroupCollector::new(cgroup_root.clone());
 
-        // Try to use mock data first for testing in sandbox environments
-        let use_mock_data =
-            std::env::var("CGTOP_USE_MOCK").unwrap_or_else(|_| "false".to_string()) == "true";
+    let mut paused = false;
+    let mut last_sample: Option<Instant> = None;
 
-        if use_mock_data {
-            log::info!("Using mock data for testing");
-            let mock_metrics = create_mock_metrics(&cgroup_root);
-            if let Err(_e) = sender.send(CGroupEvent::Update(Box::new(mock_metrics))) {
-                break;
+    while !stop.load(Ordering::SeqCst) {
+        let command = if paused {
+            // Nothing to do until the UI tells us otherwise
+            match control.recv() {
+                Ok(command) => Some(command),
+                Err(_) => break,
             }
         } else {
-            let collector = CGroupCollector::new(cgroup_root.clone());
-
-            if let Ok(metrics) = collector.collect_metrics() {
-                if let Err(_e) = sender.send(CGroupEvent::Update(Box::new(metrics))) {
-                    break;
-                }
-            } else {
-                log::info!("Failed to collect real cgroup data, using mock data");
-                let mock_metrics = create_mock_metrics(&cgroup_root);
-                if let Err(_e) = sender.send(CGroupEvent::Update(Box::new(mock_metrics))) {
-                    break;
+            let deadline = last_sample.map_or_else(Instant::now, |t| t + interval);
+            match control.recv_deadline(deadline) {
+                Ok(command) => Some(command),
+                Err(RecvTimeoutError::Timeout) => None,
+                Err(RecvTimeoutError::Disconnected) => {
+                    // No controller attached, keep sampling at the current rate
+                    thread::sleep(deadline.saturating_duration_since(Instant::now()));
+                    None
                 }
             }
+        };
+
+        match command {
+            Some(CollectionCommand::SetInterval(new_interval)) => {
+                log::info!("Collection interval changed to {:?}", new_interval);
+                interval = new_interval;
+                continue;
+            }
+            Some(CollectionCommand::Pause) => {
+                log::info!("Collection paused");
+                paused = true;
+                continue;
+            }
+            Some(CollectionCommand::Resume) => {
+                log::info!("Collection resumed");
+                paused = false;
+                continue;
+            }
+            Some(CollectionCommand::Shutdown) => break,
+            Some(CollectionCommand::Refresh) => {
+                log::info!("Manual refresh requested");
+            }
+            None => {}
+        }
+
+        last_sample = Some(Instant::now());
+        let metrics = collect_sample(&collector, &cgroup_root, use_mock_data);
+        if sender.send(CGroupEvent::Update(Box::new(metrics))).is_err() {
+            break;
         }
     }
 
     log::info!("Collection thread stopped");
 }
 
-fn cleanup_thread_worker(_sender: Sender<CGroupEvent>) {
-    log::info!("Cleanup thread started");
+fn collect_sample(
+    collector: &CGroupCollector,
+    cgroup_root: &Path,
+    use_mock_data: bool,
+) -> CGroupMetrics {
+    if use_mock_data {
+        log::info!("Using mock data for testing");
+        return create_mock_metrics(cgroup_root);
+    }
 
-    loop {
-        // TODO, every x times, send cleanup message to only keep the limited amount of data
+    match collector.collect_metrics() {
+        Ok(metrics) => metrics,
+        Err(_) => {
+            log::info!("Failed to collect real cgroup data, using mock data");
+            create_mock_metrics(cgroup_root)
+        }
     }
 }
 
 // --------------------------------------------------------------------
 // Mock data for testing
 // --------------------------------------------------------------------
-fn create_mock_metrics(cgroup_root: &PathBuf) -> CGroupMetrics {
+fn create_mock_metrics(cgroup_root: &Path) -> CGroupMetrics {
     use hashbrown::HashMap;
     use std::time::Instant;
 
@@ -180,6 +319,7 @@ fn create_mock_metrics(cgroup_root: &PathBuf) -> CGroupMetrics {
                 current: if i == 0 { 100 } else { 1 + i as u64 }, // Root has many processes
                 max: Some(512),
             },
+            frozen: false,
         };
 
         resource_usage.insert(path.to_string(), stats);
@@ -209,5 +349,6 @@ fn create_mock_metrics(cgroup_root: &PathBuf) -> CGroupMetrics {
         processes,
         resource_usage,
         timestamp: Instant::now(),
+        collected_at: Utc::now(),
     }
 }
@@ -1,137 +1,289 @@
+use anyhow::{Context, Result};
+use std::fmt;
 use std::fs;
-use std::path::Path;
+use std::path::{Component, Path, PathBuf};
 
-/// Recursively remove a directory and all its contents
-/// Logs errors but doesn't fail if some operations can't be completed
-pub fn remove_dir_recursive_safe<P: AsRef<Path>>(path: P) -> Result<(), String> {
-    let path = path.as_ref();
+use crate::actions::collect_subtree;
 
-    log::info!("Attempting to remove directory: {}", path.display());
+/// cgroups that are never removed unless the config says otherwise
+pub const DEFAULT_DENYLIST: &[&str] = &["init.scope", "system.slice", "user.slice"];
 
-    if !path.exists() {
-        let msg = format!("Directory does not exist: {}", path.display());
-        log::warn!("{}", msg);
-        return Err(msg);
+/// Why a cgroup may not be removed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UnsafeReason {
+    /// The path is not strictly below the configured root
+    OutsideRoot,
+    /// The configured root itself
+    IsRoot,
+    /// The root is not on a cgroup2 mount
+    NotCgroup2 { fstype: Option<String> },
+    /// Matched a denylist pattern
+    Denied(String),
+    /// An allowlist is configured and the path is not covered by it
+    NotAllowed,
+    /// cgtop itself runs in this cgroup or below it
+    ContainsSelf,
+}
+
+impl fmt::Display for UnsafeReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UnsafeReason::OutsideRoot => write!(f, "outside the cgroup root"),
+            UnsafeReason::IsRoot => write!(f, "is the cgroup root"),
+            UnsafeReason::NotCgroup2 {
+                fstype: Some(fstype),
+            } => {
+                write!(f, "root is on a {} mount, not cgroup2", fstype)
+            }
+            UnsafeReason::NotCgroup2 { fstype: None } => {
+                write!(f, "root is not on a cgroup2 mount")
+            }
+            UnsafeReason::Denied(pattern) => write!(f, "protected by denylist entry {}", pattern),
+            UnsafeReason::NotAllowed => write!(f, "not covered by the allowlist"),
+            UnsafeReason::ContainsSelf => write!(f, "cgtop runs inside it"),
+        }
     }
+}
+
+/// Decides which cgroups below `root` may be removed
+#[derive(Debug, Clone)]
+pub struct SafetyPolicy {
+    pub root: PathBuf,
+    /// Patterns (relative to the root) of cgroups that are never removed, along
+    /// with everything below them
+    pub denylist: Vec<glob::Pattern>,
+    /// If non-empty, only these subtrees (relative to the root) may be removed
+    pub allowlist: Vec<glob::Pattern>,
+    /// Refuse to remove anything unless the root sits on a cgroup2 mount
+    pub require_cgroup2: bool,
+    /// cgroup of the running cgtop process
+    pub self_cgroup: Option<PathBuf>,
+}
 
-    if !path.is_dir() {
-        let msg = format!("Path is not a directory: {}", path.display());
-        log::warn!("{}", msg);
-        return Err(msg);
+impl SafetyPolicy {
+    /// Default policy for `root`: built-in denylist, no allowlist, cgroup2 required
+    pub fn new(root: PathBuf) -> Self {
+        let self_cgroup = detect_self_cgroup(&root);
+        Self {
+            denylist: compile_patterns(DEFAULT_DENYLIST.iter().copied())
+                .expect("built-in denylist patterns are valid"),
+            allowlist: Vec::new(),
+            require_cgroup2: true,
+            self_cgroup,
+            root,
+        }
     }
 
-    // Try to remove the directory recursively
-    match remove_dir_contents_recursive(path) {
-        Ok(_) => {
-            // Try to remove the directory itself
-            match fs::remove_dir(path) {
-                Ok(_) => {
-                    log::info!("Successfully removed directory: {}", path.display());
-                    Ok(())
-                }
-                Err(e) => {
-                    let msg = format!("Failed to remove directory {}: {}", path.display(), e);
-                    log::error!("{}", msg);
-                    Err(msg)
-                }
+    pub fn set_denylist<S: AsRef<str>>(&mut self, patterns: &[S]) -> Result<()> {
+        self.denylist = compile_patterns(patterns.iter().map(AsRef::as_ref))?;
+        Ok(())
+    }
+
+    pub fn set_allowlist<S: AsRef<str>>(&mut self, patterns: &[S]) -> Result<()> {
+        self.allowlist = compile_patterns(patterns.iter().map(AsRef::as_ref))?;
+        Ok(())
+    }
+
+    /// Check whether the cgroup at `path` may be removed
+    pub fn check_removal<P: AsRef<Path>>(&self, path: P) -> Result<(), UnsafeReason> {
+        let path = path.as_ref();
+        let relative = self.relative_path(path)?;
+
+        if self.require_cgroup2 {
+            let mountinfo = fs::read_to_string("/proc/self/mountinfo").unwrap_or_default();
+            self.verify_mount(&mountinfo)?;
+        }
+
+        if let Some(pattern) = self.denied_by(path, relative) {
+            return Err(UnsafeReason::Denied(pattern.as_str().to_string()));
+        }
+
+        if !self.allowlist.is_empty() {
+            // An allowlist entry covers the matching cgroup and everything below it
+            let allowed = relative.ancestors().any(|ancestor| {
+                !ancestor.as_os_str().is_empty()
+                    && self
+                        .allowlist
+                        .iter()
+                        .any(|pattern| pattern.matches_path(ancestor))
+            });
+            if !allowed {
+                return Err(UnsafeReason::NotAllowed);
             }
         }
-        Err(e) => {
-            let msg = format!(
-                "Failed to remove directory contents {}: {}",
-                path.display(),
-                e
-            );
-            log::error!("{}", msg);
-            Err(msg)
+
+        if let Some(self_cgroup) = &self.self_cgroup
+            && self_cgroup.starts_with(path)
+        {
+            return Err(UnsafeReason::ContainsSelf);
         }
+
+        Ok(())
     }
-}
 
-fn remove_dir_contents_recursive<P: AsRef<Path>>(dir: P) -> Result<(), std::io::Error> {
-    let dir = dir.as_ref();
+    /// Check whether every process in the cgroup at `path` and below it may be
+    /// frozen or killed: the denylist and cgtop's own cgroup apply as for removal,
+    /// the allowlist only limits what can be removed
+    pub fn check_process_control<P: AsRef<Path>>(&self, path: P) -> Result<(), UnsafeReason> {
+        let path = path.as_ref();
+        let relative = self.relative_path(path)?;
 
-    // Read directory entries
-    let entries = match fs::read_dir(dir) {
-        Ok(entries) => entries,
-        Err(e) => {
-            log::warn!("Failed to read directory {}: {}", dir.display(), e);
-            return Err(e);
+        if let Some(pattern) = self.denied_by(path, relative) {
+            return Err(UnsafeReason::Denied(pattern.as_str().to_string()));
         }
-    };
-
-    // Process each entry
-    for entry in entries {
-        let entry = match entry {
-            Ok(entry) => entry,
-            Err(e) => {
-                log::warn!("Failed to read directory entry in {}: {}", dir.display(), e);
-                continue; // Skip this entry but continue with others
-            }
+
+        // Freezing or killing our own cgroup would stop the UI
+        if let Some(self_cgroup) = &self.self_cgroup
+            && self_cgroup.starts_with(path)
+        {
+            return Err(UnsafeReason::ContainsSelf);
+        }
+
+        Ok(())
+    }
+
+    /// Denylist entry matching `relative`, one of its ancestors or a cgroup below
+    /// it: like the allowlist, an entry covers the matching cgroup and everything
+    /// below it, and acting on a parent would reach the protected cgroup too
+    fn denied_by(&self, path: &Path, relative: &Path) -> Option<&glob::Pattern> {
+        let matching = |cgroup: &Path| {
+            self.denylist
+                .iter()
+                .find(|pattern| pattern.matches_path(cgroup))
         };
+        relative
+            .ancestors()
+            .filter(|ancestor| !ancestor.as_os_str().is_empty())
+            .find_map(matching)
+            .or_else(|| {
+                collect_subtree(path)
+                    .unwrap_or_default()
+                    .iter()
+                    .filter_map(|cgroup| cgroup.strip_prefix(&self.root).ok())
+                    .find_map(matching)
+            })
+    }
 
-        let path = entry.path();
-
-        if path.is_dir() {
-            // Recursively remove subdirectory
-            if let Err(e) = remove_dir_contents_recursive(&path) {
-                log::warn!(
-                    "Failed to remove subdirectory contents {}: {}",
-                    path.display(),
-                    e
-                );
-                // Continue with other entries
-            }
+    /// Verify against `/proc/self/mountinfo` content that the root is on cgroup2
+    pub fn verify_mount(&self, mountinfo: &str) -> Result<(), UnsafeReason> {
+        match mount_for_path(&self.root, mountinfo) {
+            Some(mount) if mount.fstype == "cgroup2" => Ok(()),
+            Some(mount) => Err(UnsafeReason::NotCgroup2 {
+                fstype: Some(mount.fstype),
+            }),
+            None => Err(UnsafeReason::NotCgroup2 { fstype: None }),
+        }
+    }
 
-            // Try to remove the empty subdirectory
-            if let Err(e) = fs::remove_dir(&path) {
-                log::warn!("Failed to remove subdirectory {}: {}", path.display(), e);
-                // Continue with other entries
-            } else {
-                log::debug!("Removed subdirectory: {}", path.display());
-            }
-        } else {
-            // Remove file
-            if let Err(e) = fs::remove_file(&path) {
-                log::warn!("Failed to remove file {}: {}", path.display(), e);
-                // Continue with other entries
-            } else {
-                log::debug!("Removed file: {}", path.display());
-            }
+    fn relative_path<'a>(&self, path: &'a Path) -> Result<&'a Path, UnsafeReason> {
+        // Refuse anything that could climb back out of the root
+        if path
+            .components()
+            .any(|component| matches!(component, Component::ParentDir | Component::CurDir))
+        {
+            return Err(UnsafeReason::OutsideRoot);
         }
+
+        let relative = path
+            .strip_prefix(&self.root)
+            .map_err(|_| UnsafeReason::OutsideRoot)?;
+
+        if relative.as_os_str().is_empty() {
+            return Err(UnsafeReason::IsRoot);
+        }
+
+        Ok(relative)
     }
+}
+
+fn compile_patterns<'a>(patterns: impl Iterator<Item = &'a str>) -> Result<Vec<glob::Pattern>> {
+    patterns
+        .map(|pattern| {
+            glob::Pattern::new(pattern.trim_matches('/'))
+                .with_context(|| format!("invalid cgroup pattern {:?}", pattern))
+        })
+        .collect()
+}
 
-    Ok(())
+/// Whether the current user may create and write files in `path`
+pub fn has_write_access(path: &Path) -> bool {
+    nix::unistd::access(path, nix::unistd::AccessFlags::W_OK).is_ok()
 }
 
-/// Check if a path is safe to remove (basic safety checks)
-pub fn is_safe_to_remove<P: AsRef<Path>>(path: P) -> bool {
-    let path = path.as_ref();
-    let path_str = path.to_string_lossy();
-
-    // Don't allow removing system critical directories
-    let forbidden_paths = [
-        "/", "/sys", "/proc", "/dev", "/boot", "/etc", "/bin", "/sbin", "/usr", "/var", "/home",
-        "/root",
-    ];
-
-    for forbidden in &forbidden_paths {
-        if path_str == *forbidden || path_str.starts_with(&format!("{}/", forbidden)) {
-            if !path_str.starts_with("/sys/fs/cgroup") {
-                return false;
+/// A single line of `/proc/self/mountinfo`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MountEntry {
+    pub mount_point: PathBuf,
+    pub fstype: String,
+}
+
+/// Parse `/proc/self/mountinfo` content
+pub fn parse_mountinfo(content: &str) -> Vec<MountEntry> {
+    content
+        .lines()
+        .filter_map(|line| {
+            // 36 35 98:0 /mnt1 /mnt/parent rw,noatime master:1 - ext3 /dev/root rw
+            let (mount_fields, fs_fields) = line.split_once(" - ")?;
+            let mount_point = mount_fields.split_whitespace().nth(4)?;
+            let fstype = fs_fields.split_whitespace().next()?;
+            Some(MountEntry {
+                mount_point: PathBuf::from(unescape_mount_path(mount_point)),
+                fstype: fstype.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// The mount that `path` lives on: the deepest mount point containing it, latest mount winning
+pub fn mount_for_path(path: &Path, mountinfo: &str) -> Option<MountEntry> {
+    parse_mountinfo(mountinfo)
+        .into_iter()
+        .filter(|mount| path.starts_with(&mount.mount_point))
+        .fold(None, |best: Option<MountEntry>, mount| match best {
+            Some(best)
+                if best.mount_point.components().count()
+                    > mount.mount_point.components().count() =>
+            {
+                Some(best)
+            }
+            _ => Some(mount),
+        })
+}
+
+/// mountinfo escapes space, tab, newline and backslash as octal
+fn unescape_mount_path(raw: &str) -> String {
+    let mut result = String::with_capacity(raw.len());
+    let mut chars = raw.chars();
+
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            let digits: String = chars.by_ref().take(3).collect();
+            match u8::from_str_radix(&digits, 8) {
+                Ok(byte) => result.push(byte as char),
+                Err(_) => {
+                    result.push('\\');
+                    result.push_str(&digits);
+                }
             }
+        } else {
+            result.push(c);
         }
     }
 
-    // Only allow removal under /sys/fs/cgroup
-    if !path_str.starts_with("/sys/fs/cgroup/") {
-        return false;
-    }
+    result
+}
 
-    // Don't allow removing the root cgroup directory itself
-    if path_str == "/sys/fs/cgroup" {
-        return false;
-    }
+/// Absolute path of the cgroup the current process runs in, resolved against `root`'s mount
+fn detect_self_cgroup(root: &Path) -> Option<PathBuf> {
+    let content = fs::read_to_string("/proc/self/cgroup").ok()?;
+    // The cgroup v2 entry looks like "0::/user.slice/session-2.scope"
+    let relative = content.lines().find_map(|line| line.strip_prefix("0::"))?;
+
+    let mountinfo = fs::read_to_string("/proc/self/mountinfo").unwrap_or_default();
+    let base = mount_for_path(root, &mountinfo)
+        .filter(|mount| mount.fstype == "cgroup2")
+        .map(|mount| mount.mount_point)
+        .unwrap_or_else(|| root.to_path_buf());
 
-    true
+    Some(base.join(relative.trim().trim_start_matches('/')))
 }
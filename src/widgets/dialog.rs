@@ -0,0 +1,896 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Flex, Layout, Rect},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+};
+
+use crate::actions::{
+    MoveReport, SIGNAL_MENU, SignalReport, explain_move_error, explain_signal_error,
+    process_command,
+};
+use crate::app::App;
+use crate::app::dialog::{
+    ConfirmDelete, ConfirmFreeze, ConfirmKill, ConfirmSignal, Dialog, HistoryView, LimitEditor,
+    LimitKind, MemberAction, MoveChooser, NewChild, SignalMenu, SubtreeControlEditor,
+};
+use crate::theme::Theme;
+use crate::widgets::CGroupTreeWidget;
+
+/// Processes listed per cgroup before the rest are summarised
+const MAX_PROCESSES_PER_CGROUP: usize = 4;
+
+/// cgroups listed in the delete confirmation
+const MAX_DELETE_LISTED: usize = 20;
+
+/// Processes listed in the kill confirmation
+const MAX_KILL_LISTED: usize = 12;
+
+/// cgroups shown at once in the move target chooser
+const MAX_CHOOSER_ROWS: usize = 15;
+
+/// Audit entries shown at once in the history view
+const MAX_HISTORY_ROWS: usize = 15;
+
+/// Scrollable body and the footer (prompt, input and key hints) pinned below it
+type DialogLines<'a> = (Vec<Line<'a>>, Vec<Line<'a>>);
+
+pub struct DialogWidget;
+
+impl DialogWidget {
+    pub fn draw(f: &mut Frame, app: &App, area: Rect) {
+        let Some(dialog) = &app.ui_state.dialog else {
+            return;
+        };
+
+        let theme = &app.theme;
+        let (title, border, lines) = match dialog {
+            Dialog::ConfirmDelete(confirm) => (
+                confirm.title.clone(),
+                theme.critical,
+                Self::confirm_delete_lines(confirm, app, theme),
+            ),
+            Dialog::ConfirmKill(confirm) => (
+                "Kill all processes".to_string(),
+                theme.critical,
+                Self::confirm_kill_lines(confirm, app, theme),
+            ),
+            Dialog::ConfirmFreeze(confirm) => (
+                format!(
+                    "{} {} cgroups",
+                    if confirm.freeze { "Freeze" } else { "Thaw" },
+                    confirm.cgroups.len()
+                ),
+                theme.warning,
+                Self::confirm_freeze_lines(confirm, app, theme),
+            ),
+            Dialog::NewChild(new_child) => (
+                "New child cgroup".to_string(),
+                theme.primary,
+                Self::new_child_lines(new_child, app, theme),
+            ),
+            Dialog::SubtreeControl(editor) => (
+                format!("cgroup.subtree_control: {}", editor.cgroup.display()),
+                theme.primary,
+                Self::subtree_control_lines(editor, theme),
+            ),
+            Dialog::EditLimits(editor) => (
+                format!(
+                    "{} limits: {}",
+                    editor_title(editor.kind),
+                    editor.cgroup.display()
+                ),
+                theme.primary,
+                Self::limit_editor_lines(editor, app, theme),
+            ),
+            Dialog::MoveProcesses(chooser) => (
+                format!("Move {} process(es)", chooser.pids.len()),
+                theme.primary,
+                Self::move_chooser_lines(chooser, app, theme),
+            ),
+            Dialog::MoveResult(report) => (
+                "Move failed".to_string(),
+                theme.critical,
+                Self::move_result_lines(report, theme),
+            ),
+            Dialog::SignalMenu(menu) => (
+                format!("Signal {} process(es)", menu.pids.len()),
+                theme.primary,
+                Self::signal_menu_lines(menu, app, theme),
+            ),
+            Dialog::ConfirmSignal(confirm) => (
+                format!("Send {}", confirm.signal),
+                theme.critical,
+                Self::confirm_signal_lines(confirm, theme),
+            ),
+            Dialog::SignalResult(report) => (
+                format!("{} failed", report.signal),
+                theme.critical,
+                Self::signal_result_lines(report, theme),
+            ),
+            Dialog::History(view) => (
+                match app.audit.path() {
+                    Some(path) => format!("History ({})", path.display()),
+                    None => "History".to_string(),
+                },
+                theme.primary,
+                Self::history_lines(view, app, theme),
+            ),
+        };
+
+        let (body, footer) = lines;
+        // Long lines wrap, so size everything in rows of the popup's inner width
+        let width = centered_area(area, 70, 0).width.saturating_sub(2);
+        let footer_rows = rows(&footer, width);
+        let popup = centered_area(area, 70, rows(&body, width) + footer_rows + 2);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(theme.fg(border))
+            .title(format!(" {} ", title))
+            .title_style(theme.bold(border));
+        let inner = block.inner(popup);
+        f.render_widget(Clear, popup);
+        f.render_widget(block, popup);
+
+        // The footer keeps its rows so the prompt stays visible on short
+        // terminals; the body gets whatever is left
+        let [body_area, footer_area] = Layout::vertical([
+            Constraint::Min(0),
+            Constraint::Length(footer_rows.min(inner.height)),
+        ])
+        .areas(inner);
+        f.render_widget(
+            Paragraph::new(fit_lines(body, body_area, theme)).wrap(Wrap { trim: false }),
+            body_area,
+        );
+        f.render_widget(
+            Paragraph::new(footer).wrap(Wrap { trim: false }),
+            footer_area,
+        );
+    }
+
+    fn confirm_delete_lines<'a>(
+        confirm: &'a ConfirmDelete,
+        app: &App,
+        theme: &Theme,
+    ) -> DialogLines<'a> {
+        let target = match confirm.plans.as_slice() {
+            [plan] => plan.target.display().to_string(),
+            plans => format!("{} marked trees", plans.len()),
+        };
+        let mut lines = vec![
+            Line::from(vec![
+                Span::styled(
+                    format!(
+                        "{} cgroup(s) will be removed under ",
+                        confirm.cgroup_count()
+                    ),
+                    theme.fg(theme.text),
+                ),
+                Span::styled(target, theme.bold(theme.accent)),
+            ]),
+            Line::default(),
+        ];
+
+        // Children first, matching the order they are removed in
+        let cgroups = confirm.plans.iter().flat_map(|plan| &plan.cgroups);
+        for cgroup in cgroups.clone().take(MAX_DELETE_LISTED) {
+            lines.push(Line::from(vec![
+                Span::styled("  ✗ ", theme.fg(theme.critical)),
+                Span::styled(cgroup.path.display().to_string(), theme.fg(theme.text)),
+                Span::styled(
+                    format!("  ({} processes)", cgroup.processes.len()),
+                    theme.fg(theme.muted),
+                ),
+            ]));
+
+            for (pid, command) in cgroup.processes.iter().take(MAX_PROCESSES_PER_CGROUP) {
+                lines.push(Line::styled(
+                    format!("      {:>7} {}", pid, command),
+                    theme.fg(theme.dim),
+                ));
+            }
+            if cgroup.processes.len() > MAX_PROCESSES_PER_CGROUP {
+                lines.push(Line::styled(
+                    format!(
+                        "      … {} more",
+                        cgroup.processes.len() - MAX_PROCESSES_PER_CGROUP
+                    ),
+                    theme.fg(theme.dim),
+                ));
+            }
+        }
+
+        if confirm.cgroup_count() > MAX_DELETE_LISTED {
+            lines.push(Line::styled(
+                format!(
+                    "  … {} more cgroups",
+                    confirm.cgroup_count() - MAX_DELETE_LISTED
+                ),
+                theme.fg(theme.dim),
+            ));
+        }
+
+        let mut footer = vec![Line::default()];
+        let process_count = confirm.process_count();
+        if process_count > 0 {
+            let style = match confirm.members {
+                MemberAction::Kill => theme.bold(theme.critical),
+                _ => theme.fg(theme.warning),
+            };
+            footer.push(Line::from(vec![
+                Span::styled(
+                    format!("{} process(es) still running: ", process_count),
+                    theme.fg(theme.warning),
+                ),
+                Span::styled(confirm.members.label(), style),
+                Span::styled("  (Tab: change)", theme.fg(theme.muted)),
+            ]));
+        }
+        if app.config.dry_run {
+            footer.push(Line::styled(
+                "Dry run: nothing will be removed, the actions are only logged",
+                theme.fg(theme.warning),
+            ));
+        }
+
+        match confirm.required_input() {
+            Some(required) => {
+                footer.push(Line::from(vec![
+                    Span::styled("Type ", theme.fg(theme.text)),
+                    Span::styled(required, theme.bold(theme.critical)),
+                    Span::styled(" to confirm: ", theme.fg(theme.text)),
+                    Span::styled(format!("{}_", confirm.input), theme.bold(theme.accent)),
+                ]));
+                footer.push(Line::styled(
+                    "Enter: delete  Esc: cancel",
+                    theme.fg(theme.muted),
+                ));
+            }
+            None => footer.push(Line::styled(
+                "y/Enter: delete  n/Esc: cancel",
+                theme.fg(theme.muted),
+            )),
+        }
+
+        (lines, footer)
+    }
+
+    fn confirm_kill_lines<'a>(
+        confirm: &'a ConfirmKill,
+        app: &App,
+        theme: &Theme,
+    ) -> DialogLines<'a> {
+        let target = match confirm.cgroups.as_slice() {
+            [cgroup] => cgroup.display().to_string(),
+            cgroups => format!("{} marked cgroups", cgroups.len()),
+        };
+        let mut lines = vec![
+            Line::from(vec![
+                Span::styled(
+                    format!("SIGKILL {} process(es) in ", confirm.processes.len()),
+                    theme.fg(theme.text),
+                ),
+                Span::styled(target, theme.bold(theme.accent)),
+                Span::styled(" and below", theme.fg(theme.text)),
+            ]),
+            Line::default(),
+        ];
+
+        for (pid, command) in confirm.processes.iter().take(MAX_KILL_LISTED) {
+            lines.push(Line::styled(
+                format!("  {:>7} {}", pid, command),
+                theme.fg(theme.text),
+            ));
+        }
+        if confirm.processes.len() > MAX_KILL_LISTED {
+            lines.push(Line::styled(
+                format!("  … {} more", confirm.processes.len() - MAX_KILL_LISTED),
+                theme.fg(theme.dim),
+            ));
+        }
+
+        let mut footer = vec![Line::default()];
+        if app.config.dry_run {
+            footer.push(Line::styled(
+                "Dry run: nothing will be killed, the action is only logged",
+                theme.fg(theme.warning),
+            ));
+        }
+        footer.push(Line::styled(
+            "y/Enter: kill  n/Esc: cancel",
+            theme.fg(theme.muted),
+        ));
+
+        (lines, footer)
+    }
+
+    fn confirm_freeze_lines<'a>(
+        confirm: &'a ConfirmFreeze,
+        app: &App,
+        theme: &Theme,
+    ) -> DialogLines<'a> {
+        let action = if confirm.freeze { "freeze" } else { "thaw" };
+        let mut lines = vec![
+            Line::styled(
+                format!("These cgroups and everything below them will {}:", action),
+                theme.fg(theme.text),
+            ),
+            Line::default(),
+        ];
+
+        for cgroup in confirm.cgroups.iter().take(MAX_DELETE_LISTED) {
+            lines.push(Line::styled(
+                format!("  {}", cgroup.display()),
+                theme.fg(theme.text),
+            ));
+        }
+        if confirm.cgroups.len() > MAX_DELETE_LISTED {
+            lines.push(Line::styled(
+                format!("  … {} more", confirm.cgroups.len() - MAX_DELETE_LISTED),
+                theme.fg(theme.dim),
+            ));
+        }
+
+        let mut footer = vec![Line::default()];
+        if app.config.dry_run {
+            footer.push(Line::styled(
+                "Dry run: nothing changes, the action is only logged",
+                theme.fg(theme.warning),
+            ));
+        }
+        footer.push(Line::styled(
+            format!("y/Enter: {}  n/Esc: cancel", action),
+            theme.fg(theme.muted),
+        ));
+
+        (lines, footer)
+    }
+
+    fn new_child_lines<'a>(new_child: &'a NewChild, app: &App, theme: &Theme) -> DialogLines<'a> {
+        let lines = vec![
+            Line::from(vec![
+                Span::styled("Create below ", theme.fg(theme.text)),
+                Span::styled(
+                    new_child.parent.display().to_string(),
+                    theme.bold(theme.accent),
+                ),
+            ]),
+            Line::default(),
+        ];
+        let mut footer = vec![
+            Line::from(vec![
+                Span::styled("Name: ", theme.fg(theme.text)),
+                Span::styled(format!("{}_", new_child.input), theme.bold(theme.accent)),
+            ]),
+            Line::default(),
+        ];
+        if app.config.dry_run {
+            footer.push(Line::styled(
+                "Dry run: the cgroup is not created",
+                theme.fg(theme.warning),
+            ));
+        }
+        footer.push(Line::styled(
+            "Enter: create  Esc: cancel",
+            theme.fg(theme.muted),
+        ));
+        (lines, footer)
+    }
+
+    fn subtree_control_lines<'a>(
+        editor: &'a SubtreeControlEditor,
+        theme: &Theme,
+    ) -> DialogLines<'a> {
+        let mut lines = Vec::new();
+
+        if editor.controllers.is_empty() {
+            lines.push(Line::styled(
+                "No controllers available (see the parent's subtree_control)",
+                theme.fg(theme.warning),
+            ));
+        }
+        for (index, (name, enabled)) in editor.controllers.iter().enumerate() {
+            let marker = if index == editor.selected {
+                "▶ "
+            } else {
+                "  "
+            };
+            let (checkbox, style) = if *enabled {
+                ("[x] ", theme.fg(theme.good))
+            } else {
+                ("[ ] ", theme.fg(theme.muted))
+            };
+            lines.push(Line::from(vec![
+                Span::styled(marker, theme.fg(theme.accent)),
+                Span::styled(checkbox, style),
+                Span::styled(name.as_str(), theme.fg(theme.text)),
+            ]));
+        }
+
+        let mut footer = vec![Line::default()];
+        footer.push(Line::styled(
+            "Children only get controllers enabled here; a cgroup with its own processes cannot enable them",
+            theme.fg(theme.muted),
+        ));
+        footer.push(Line::styled(
+            "↑/↓: choose  Space/Enter: toggle  Esc: close",
+            theme.fg(theme.muted),
+        ));
+        (lines, footer)
+    }
+
+    fn limit_editor_lines<'a>(
+        editor: &'a LimitEditor,
+        app: &App,
+        theme: &Theme,
+    ) -> DialogLines<'a> {
+        let mut lines = Vec::new();
+
+        if !editor.devices.is_empty() {
+            let mut spans = vec![Span::styled("Device: ", theme.fg(theme.text))];
+            for (index, device) in editor.devices.iter().enumerate() {
+                let label = format!(" {} {} ", device.name, device.dev);
+                spans.push(if index == editor.device {
+                    Span::styled(label, theme.selected)
+                } else {
+                    Span::styled(label, theme.fg(theme.muted))
+                });
+            }
+            lines.push(Line::from(spans));
+            lines.push(Line::default());
+        }
+
+        for (index, field) in editor.fields.iter().enumerate() {
+            let selected = index == editor.selected;
+            let marker = if selected { "▶ " } else { "  " };
+            let mut spans = vec![
+                Span::styled(marker, theme.fg(theme.accent)),
+                Span::styled(
+                    format!("{:<18}", field.file),
+                    if selected {
+                        theme.bold(theme.text)
+                    } else {
+                        theme.fg(theme.text)
+                    },
+                ),
+                Span::styled(format!("{:>12}", field.current), theme.fg(theme.muted)),
+            ];
+            if selected {
+                spans.push(Span::styled("  → ", theme.fg(theme.dim)));
+                spans.push(Span::styled(
+                    format!("{}_", editor.input),
+                    theme.bold(theme.accent),
+                ));
+            }
+            lines.push(Line::from(spans));
+        }
+
+        let mut footer = vec![Line::default()];
+        if editor.targets.len() > 1 {
+            footer.push(Line::styled(
+                format!(
+                    "Applies to all {} marked cgroups, values shown for this one",
+                    editor.targets.len()
+                ),
+                theme.bold(theme.warning),
+            ));
+        }
+        for detail in &editor.details {
+            footer.push(Line::styled(detail.as_str(), theme.fg(theme.text)));
+        }
+        footer.push(Line::styled(
+            format!("Accepts {}", editor_hint(editor.kind)),
+            theme.fg(theme.muted),
+        ));
+        if app.config.dry_run {
+            footer.push(Line::styled(
+                "Dry run: values are only logged",
+                theme.fg(theme.warning),
+            ));
+        }
+        footer.push(Line::styled(
+            "↑/↓: choose file  Enter: apply  Esc: close",
+            theme.fg(theme.muted),
+        ));
+
+        (lines, footer)
+    }
+
+    fn move_chooser_lines<'a>(
+        chooser: &'a MoveChooser,
+        app: &App,
+        theme: &Theme,
+    ) -> DialogLines<'a> {
+        let tree = &chooser.tree;
+        let mut lines = Vec::new();
+        if tree.visible_nodes.is_empty() {
+            lines.push(Line::styled("No matching cgroups", theme.fg(theme.warning)));
+        }
+
+        // Keep the selection inside the visible window
+        let selected = tree
+            .selected
+            .as_ref()
+            .and_then(|selected| tree.visible_nodes.iter().position(|key| key == selected))
+            .unwrap_or(0);
+        let first = selected.saturating_sub(MAX_CHOOSER_ROWS - 1);
+        for (index, key) in tree
+            .visible_nodes
+            .iter()
+            .enumerate()
+            .skip(first)
+            .take(MAX_CHOOSER_ROWS)
+        {
+            let Some(node) = tree.nodes.get(key) else {
+                continue;
+            };
+            // Ancestors shown only to place a filter match are dimmed
+            let name_style = if index == selected {
+                theme.selected
+            } else if chooser.filter.is_empty() || key.contains(&chooser.filter) {
+                theme.fg(theme.text)
+            } else {
+                theme.fg(theme.dim)
+            };
+            lines.push(Line::from(vec![
+                Span::styled(
+                    CGroupTreeWidget::get_tree_prefix(node, tree),
+                    theme.fg(theme.dim),
+                ),
+                Span::styled(
+                    CGroupTreeWidget::expand_indicator(node),
+                    theme.fg(theme.primary),
+                ),
+                Span::styled(node.name.as_str(), name_style),
+            ]));
+        }
+
+        let mut footer = vec![
+            Line::default(),
+            Line::from(vec![
+                Span::styled("Filter: ", theme.fg(theme.text)),
+                Span::styled(format!("{}_", chooser.filter), theme.bold(theme.accent)),
+            ]),
+        ];
+        if let Some(target) = chooser.target() {
+            footer.push(Line::from(vec![
+                Span::styled("Target: ", theme.fg(theme.text)),
+                Span::styled(target, theme.bold(theme.accent)),
+            ]));
+        }
+        if app.config.dry_run {
+            footer.push(Line::styled(
+                "Dry run: processes are not moved",
+                theme.fg(theme.warning),
+            ));
+        }
+        footer.push(Line::styled(
+            "Type to filter  ↑/↓: choose  →/←: expand/collapse  Enter: move  Esc: cancel",
+            theme.fg(theme.muted),
+        ));
+        (lines, footer)
+    }
+
+    fn move_result_lines<'a>(report: &'a MoveReport, theme: &Theme) -> DialogLines<'a> {
+        let failed = report.failures().count();
+        let mut lines = vec![
+            Line::from(vec![
+                Span::styled(
+                    format!(
+                        "Moved {} of {} process(es) to ",
+                        report.moved_count(),
+                        report.moves.len()
+                    ),
+                    theme.fg(theme.text),
+                ),
+                Span::styled(
+                    report.target.display().to_string(),
+                    theme.bold(theme.accent),
+                ),
+            ]),
+            Line::default(),
+        ];
+
+        for failure in report.failures().take(MAX_KILL_LISTED) {
+            let Err(errno) = failure.result else {
+                continue;
+            };
+            lines.push(Line::from(vec![
+                Span::styled(format!("  {:>7} ", failure.pid), theme.fg(theme.text)),
+                Span::styled(
+                    format!("{:<7} ", format!("{:?}", errno)),
+                    theme.bold(theme.critical),
+                ),
+                Span::styled(explain_move_error(errno), theme.fg(theme.muted)),
+            ]));
+        }
+        if failed > MAX_KILL_LISTED {
+            lines.push(Line::styled(
+                format!("  … {} more", failed - MAX_KILL_LISTED),
+                theme.fg(theme.dim),
+            ));
+        }
+
+        let mut footer = vec![Line::default()];
+        footer.push(Line::styled("Any key: close", theme.fg(theme.muted)));
+        (lines, footer)
+    }
+
+    fn signal_menu_lines<'a>(menu: &'a SignalMenu, app: &App, theme: &Theme) -> DialogLines<'a> {
+        let mut lines = Vec::new();
+        for &pid in menu.pids.iter().take(MAX_KILL_LISTED) {
+            lines.push(Line::styled(
+                format!("  {:>7} {}", pid, process_command(pid)),
+                theme.fg(theme.text),
+            ));
+        }
+        if menu.pids.len() > MAX_KILL_LISTED {
+            lines.push(Line::styled(
+                format!("  … {} more", menu.pids.len() - MAX_KILL_LISTED),
+                theme.fg(theme.dim),
+            ));
+        }
+        let mut footer = vec![Line::default()];
+
+        // A typed signal replaces the menu choice
+        let typed = !menu.input.is_empty();
+        for (index, signal) in SIGNAL_MENU.iter().enumerate() {
+            let label = format!("{:<8} {:>2}", signal.as_str(), *signal as i32);
+            footer.push(if index == menu.selected && !typed {
+                Line::from(vec![
+                    Span::styled("▶ ", theme.fg(theme.accent)),
+                    Span::styled(label, theme.selected),
+                ])
+            } else {
+                Line::from(vec![
+                    Span::raw("  "),
+                    Span::styled(label, theme.fg(theme.muted)),
+                ])
+            });
+        }
+
+        footer.push(Line::default());
+        let mut other = vec![
+            Span::styled("Other: ", theme.fg(theme.text)),
+            Span::styled(format!("{}_", menu.input), theme.bold(theme.accent)),
+        ];
+        if typed {
+            other.push(match menu.signal() {
+                Ok(signal) => Span::styled(format!("  → {}", signal), theme.fg(theme.good)),
+                Err(e) => Span::styled(format!("  {}", e), theme.fg(theme.warning)),
+            });
+        }
+        footer.push(Line::from(other));
+        if app.config.dry_run {
+            footer.push(Line::styled(
+                "Dry run: no signal is sent",
+                theme.fg(theme.warning),
+            ));
+        }
+        footer.push(Line::styled(
+            "↑/↓: choose  type a name or number  Enter: send  Esc: cancel",
+            theme.fg(theme.muted),
+        ));
+        (lines, footer)
+    }
+
+    fn confirm_signal_lines<'a>(confirm: &'a ConfirmSignal, theme: &Theme) -> DialogLines<'a> {
+        let mut lines = vec![
+            Line::styled(
+                format!(
+                    "{} of {} process(es) need special care:",
+                    confirm.warnings.len(),
+                    confirm.pids.len()
+                ),
+                theme.fg(theme.text),
+            ),
+            Line::default(),
+        ];
+        for (pid, reason) in &confirm.warnings {
+            lines.push(Line::from(vec![
+                Span::styled(format!("  {:>7} ", pid), theme.fg(theme.text)),
+                Span::styled(process_command(*pid), theme.bold(theme.critical)),
+                Span::styled(format!("  {}", reason), theme.fg(theme.muted)),
+            ]));
+        }
+        let mut footer = vec![Line::default()];
+        footer.push(Line::styled(
+            format!("y/Enter: send {}  n/Esc: cancel", confirm.signal),
+            theme.fg(theme.muted),
+        ));
+        (lines, footer)
+    }
+
+    fn signal_result_lines<'a>(report: &'a SignalReport, theme: &Theme) -> DialogLines<'a> {
+        let mut lines = vec![
+            Line::styled(
+                format!(
+                    "Delivered to {} of {} process(es)",
+                    report.delivered_count(),
+                    report.deliveries.len()
+                ),
+                theme.fg(theme.text),
+            ),
+            Line::default(),
+        ];
+
+        for delivery in report.deliveries.iter().take(MAX_KILL_LISTED) {
+            lines.push(match delivery.result {
+                Ok(()) => Line::from(vec![
+                    Span::styled(format!("  {:>7} ", delivery.pid), theme.fg(theme.text)),
+                    Span::styled("ok", theme.fg(theme.good)),
+                ]),
+                Err(errno) => Line::from(vec![
+                    Span::styled(format!("  {:>7} ", delivery.pid), theme.fg(theme.text)),
+                    Span::styled(
+                        format!("{:<7} ", format!("{:?}", errno)),
+                        theme.bold(theme.critical),
+                    ),
+                    Span::styled(explain_signal_error(errno), theme.fg(theme.muted)),
+                ]),
+            });
+        }
+        if report.deliveries.len() > MAX_KILL_LISTED {
+            lines.push(Line::styled(
+                format!("  … {} more", report.deliveries.len() - MAX_KILL_LISTED),
+                theme.fg(theme.dim),
+            ));
+        }
+
+        let mut footer = vec![Line::default()];
+        footer.push(Line::styled("Any key: close", theme.fg(theme.muted)));
+        (lines, footer)
+    }
+
+    fn history_lines<'a>(view: &HistoryView, app: &'a App, theme: &Theme) -> DialogLines<'a> {
+        let entries = app.audit.entries();
+        let root = app.config.cgroup_root.to_string_lossy();
+        let undoable = app.audit.last_undoable();
+        let mut lines = Vec::new();
+
+        if entries.is_empty() {
+            lines.push(Line::styled(
+                "No changes made in this session",
+                theme.fg(theme.muted),
+            ));
+        }
+
+        // Newest first
+        let selected = view.selected.min(entries.len().saturating_sub(1));
+        let first = selected.saturating_sub(MAX_HISTORY_ROWS - 1);
+        for (row, (index, entry)) in entries
+            .iter()
+            .enumerate()
+            .rev()
+            .enumerate()
+            .skip(first)
+            .take(MAX_HISTORY_ROWS)
+        {
+            let path = entry.path.to_string_lossy();
+            let path = path
+                .strip_prefix(root.as_ref())
+                .unwrap_or(&path)
+                .to_string();
+            let change = match &entry.old {
+                Some(old) => format!("{} → {}", old.trim().replace('\n', " | "), entry.new),
+                None => entry.new.clone(),
+            };
+            let (status, status_style) = if !entry.succeeded() {
+                (entry.result.clone(), theme.fg(theme.critical))
+            } else if app.audit.is_undone(index) {
+                ("undone".to_string(), theme.fg(theme.dim))
+            } else if entry.undo_of.is_some() {
+                ("undo".to_string(), theme.fg(theme.accent))
+            } else {
+                ("ok".to_string(), theme.fg(theme.good))
+            };
+
+            let marker = if Some(index) == undoable {
+                "↶ "
+            } else {
+                "  "
+            };
+            let text_style = if row == selected {
+                theme.selected
+            } else {
+                theme.fg(theme.text)
+            };
+            lines.push(Line::from(vec![
+                Span::styled(marker, theme.fg(theme.accent)),
+                Span::styled(
+                    format!(
+                        "{} {} {} {} {}",
+                        entry.timestamp.format("%H:%M:%S"),
+                        entry.user,
+                        path,
+                        entry.file,
+                        change
+                    ),
+                    text_style,
+                ),
+                Span::styled(format!("  {}", status), status_style),
+            ]));
+        }
+
+        let mut footer = vec![Line::default()];
+        if undoable.is_none() && !entries.is_empty() {
+            footer.push(Line::styled(
+                "Nothing left to undo (deletes, kills and moves cannot be reverted)",
+                theme.fg(theme.muted),
+            ));
+        }
+        footer.push(Line::styled(
+            "↑/↓: scroll  u: undo last change (↶)  Esc: close",
+            theme.fg(theme.muted),
+        ));
+        (lines, footer)
+    }
+}
+
+fn editor_title(kind: LimitKind) -> &'static str {
+    match kind {
+        LimitKind::Memory => "Memory",
+        LimitKind::Cpu => "CPU",
+        LimitKind::Pids => "PID",
+        LimitKind::Io => "IO",
+    }
+}
+
+/// Examples of accepted input, shown under the fields
+fn editor_hint(kind: LimitKind) -> &'static str {
+    match kind {
+        LimitKind::Memory => "512M, 1.5G, 80% (of parent) or max",
+        LimitKind::Cpu => "cores (1.5) or quota/period, weight 1-10000, burst in us/ms",
+        LimitKind::Pids => "a number of tasks or max",
+        LimitKind::Io => "rbps=/wbps= (10M) riops=/wiops= (500) or max; weight 1-10000",
+    }
+}
+
+/// Rows `lines` take up when wrapped at `width`
+fn rows(lines: &[Line], width: u16) -> u16 {
+    let width = usize::from(width.max(1));
+    lines
+        .iter()
+        .map(|line| line.width().div_ceil(width).max(1) as u16)
+        .sum()
+}
+
+/// Cut `lines` down to the rows of `area`, saying how many were left out
+fn fit_lines<'a>(mut lines: Vec<Line<'a>>, area: Rect, theme: &Theme) -> Vec<Line<'a>> {
+    if rows(&lines, area.width) <= area.height {
+        return lines;
+    }
+
+    // Keep a row for the marker
+    let mut kept = 0;
+    let mut used = 0;
+    for line in &lines {
+        let height = rows(std::slice::from_ref(line), area.width);
+        if used + height >= area.height {
+            break;
+        }
+        used += height;
+        kept += 1;
+    }
+    let hidden = lines.len() - kept;
+    lines.truncate(kept);
+    if area.height > 0 {
+        lines.push(Line::styled(
+            format!("  … {} more lines (enlarge the terminal)", hidden),
+            theme.fg(theme.dim),
+        ));
+    }
+    lines
+}
+
+/// A box `percent_x` wide and `height` rows tall in the middle of `area`
+fn centered_area(area: Rect, percent_x: u16, height: u16) -> Rect {
+    let [row] = Layout::vertical([Constraint::Length(height.min(area.height))])
+        .flex(Flex::Center)
+        .areas(area);
+    let [popup] = Layout::horizontal([Constraint::Percentage(percent_x)])
+        .flex(Flex::Center)
+        .areas(row);
+    popup
+}
@@ -1,14 +1,22 @@
 use ratatui::{
     Frame,
-    layout::{Constraint, Rect},
+    layout::{Constraint, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span, Text},
     widgets::{Block, Borders, List, ListItem, Paragraph, Row, Table},
 };
-use std::{collections::BTreeMap, path::PathBuf};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
 
-use crate::app::App;
+use crate::app::{App, ProcessSortColumn, TAB_TITLES};
 use crate::canvas::{format_bytes, format_duration_usec};
+use crate::columns::{CgroupColumn, display_path};
+use crate::theme::Theme;
+
+mod dialog;
+pub use dialog::DialogWidget;
 
 #[derive(Debug, Clone)]
 pub struct CGroupTreeNode {
@@ -23,9 +31,13 @@ pub struct CGroupTreeNode {
 pub struct CGroupTreeState {
     pub nodes: BTreeMap<String, CGroupTreeNode>,
     pub selected: Option<String>,
+    /// Nodes marked for a batch action, by key like `selected`
+    pub marked: std::collections::BTreeSet<String>,
     pub expanded_nodes: std::collections::HashSet<String>,
     pub visible_nodes: Vec<String>,
     pub scroll_offset: usize,
+    /// List the root itself as the first row (the key is "")
+    pub show_root: bool,
     root_path: PathBuf,
 }
 
@@ -34,9 +46,11 @@ impl Default for CGroupTreeState {
         Self {
             nodes: BTreeMap::new(),
             selected: None,
+            marked: std::collections::BTreeSet::new(),
             expanded_nodes: std::collections::HashSet::new(),
             visible_nodes: Vec::new(),
             scroll_offset: 0,
+            show_root: false,
             root_path: PathBuf::from("/sys/fs/cgroup"),
         }
     }
@@ -44,9 +58,10 @@ impl Default for CGroupTreeState {
 
 impl CGroupTreeState {
     pub fn new(root_path: PathBuf) -> Self {
-        let mut state = Self::default();
-        state.root_path = root_path;
-        state
+        Self {
+            root_path,
+            ..Self::default()
+        }
     }
 }
 
@@ -73,23 +88,22 @@ impl CGroupTreeState {
 
         // Restore expansion state from saved state, or set defaults for first build
         for (node_key, node) in self.nodes.iter_mut() {
-            // For first build, expand root level nodes by default
-            if is_first_build && node.depth == 1 {
-                node.expanded = true;
-                self.expanded_nodes.insert(node_key.clone());
-            }
-            // For subsequent builds, restore previous expansion state
-            else if saved_expanded_nodes.contains(node_key) {
-                node.expanded = true;
-                self.expanded_nodes.insert(node_key.clone());
-            }
-            // Root is always expanded
-            else if node_key.is_empty() {
+            // For first build, expand root level nodes by default.
+            // For subsequent builds, restore previous expansion state.
+            // Root is always expanded.
+            if (is_first_build && node.depth == 1)
+                || saved_expanded_nodes.contains(node_key)
+                || node_key.is_empty()
+            {
                 node.expanded = true;
                 self.expanded_nodes.insert(node_key.clone());
             }
         }
 
+        // Forget marks on cgroups that have gone away
+        let nodes = &self.nodes;
+        self.marked.retain(|key| nodes.contains_key(key));
+
         // Build visible nodes list
         self.rebuild_visible_nodes();
 
@@ -159,11 +173,11 @@ impl CGroupTreeState {
                 );
 
                 // Add to parent's children
-                if let Some(parent) = self.nodes.get_mut(&parent_path) {
-                    if !parent.children.contains(&current_path) {
-                        parent.children.push(current_path.clone());
-                        parent.children.sort();
-                    }
+                if let Some(parent) = self.nodes.get_mut(&parent_path)
+                    && !parent.children.contains(&current_path)
+                {
+                    parent.children.push(current_path.clone());
+                    parent.children.sort();
                 }
             }
 
@@ -178,7 +192,7 @@ impl CGroupTreeState {
 
     fn add_visible_children(&mut self, path: &str) {
         if let Some(node) = self.nodes.get(path) {
-            if !path.is_empty() {
+            if !path.is_empty() || self.show_root {
                 self.visible_nodes.push(path.to_string());
             }
 
@@ -192,6 +206,54 @@ impl CGroupTreeState {
         }
     }
 
+    /// Show only the nodes whose key contains `filter`, below their (expanded)
+    /// ancestors; an empty filter goes back to the normal expanded view. The
+    /// selection moves to the first match if it was filtered out.
+    pub fn set_filter(&mut self, filter: &str) {
+        self.visible_nodes.clear();
+        if filter.is_empty() {
+            self.add_visible_children("");
+        } else {
+            self.add_matching_children("", filter);
+        }
+
+        if !self
+            .selected
+            .as_ref()
+            .is_some_and(|selected| self.visible_nodes.contains(selected))
+        {
+            self.selected = self
+                .visible_nodes
+                .iter()
+                .find(|key| filter.is_empty() || key.contains(filter))
+                .cloned();
+        }
+        self.scroll_offset = 0;
+    }
+
+    /// Add `path` and its descendants that match `filter`, keeping the
+    /// ancestors of every match; returns whether anything was added
+    fn add_matching_children(&mut self, path: &str, filter: &str) -> bool {
+        let Some(node) = self.nodes.get(path) else {
+            return false;
+        };
+        let mut children = node.children.clone();
+        children.sort();
+
+        let position = self.visible_nodes.len();
+        if !path.is_empty() || self.show_root {
+            self.visible_nodes.push(path.to_string());
+        }
+        let mut found = !path.is_empty() && path.contains(filter);
+        for child in children {
+            found |= self.add_matching_children(&child, filter);
+        }
+        if !found {
+            self.visible_nodes.truncate(position);
+        }
+        found
+    }
+
     pub fn toggle_expand(&mut self, path: &str) {
         if let Some(node) = self.nodes.get_mut(path) {
             node.expanded = !node.expanded;
@@ -262,25 +324,49 @@ impl CGroupTreeState {
     }
 
     pub fn adjust_scroll_for_area_height(&mut self, area_height: usize) {
-        if let Some(selected) = &self.selected {
-            if let Some(selected_idx) = self.visible_nodes.iter().position(|n| n == selected) {
-                let visible_height = area_height.saturating_sub(2); // Account for borders
-
-                // Ensure scroll offset keeps selected item visible
-                if selected_idx < self.scroll_offset {
-                    self.scroll_offset = selected_idx;
-                } else if selected_idx >= self.scroll_offset + visible_height {
-                    self.scroll_offset = selected_idx.saturating_sub(visible_height - 1);
-                }
+        if let Some(selected) = &self.selected
+            && let Some(selected_idx) = self.visible_nodes.iter().position(|n| n == selected)
+        {
+            let visible_height = area_height.saturating_sub(2); // Account for borders
+
+            // Ensure scroll offset keeps selected item visible
+            if selected_idx < self.scroll_offset {
+                self.scroll_offset = selected_idx;
+            } else if selected_idx >= self.scroll_offset + visible_height {
+                self.scroll_offset = selected_idx.saturating_sub(visible_height - 1);
+            }
 
-                // Ensure scroll offset doesn't go beyond the list
-                if self.scroll_offset + visible_height > self.visible_nodes.len() {
-                    self.scroll_offset = self.visible_nodes.len().saturating_sub(visible_height);
-                }
+            // Ensure scroll offset doesn't go beyond the list
+            if self.scroll_offset + visible_height > self.visible_nodes.len() {
+                self.scroll_offset = self.visible_nodes.len().saturating_sub(visible_height);
             }
         }
     }
 
+    /// Key of the node drawn on the given row of the tree list, counting from the first visible row
+    pub fn node_at_row(&self, row: usize) -> Option<&String> {
+        self.visible_nodes.get(self.scroll_offset + row)
+    }
+
+    /// Mark or unmark the selected node; the root cannot be marked
+    pub fn toggle_mark(&mut self) {
+        if let Some(selected) = self.selected.clone()
+            && !selected.is_empty()
+            && !self.marked.remove(&selected)
+        {
+            self.marked.insert(selected);
+        }
+    }
+
+    /// Full paths of the marked cgroups, in tree order
+    pub fn marked_paths(&self) -> Vec<String> {
+        self.marked
+            .iter()
+            .filter_map(|key| self.nodes.get(key))
+            .map(|node| node.path.clone())
+            .collect()
+    }
+
     pub fn root_path_string(&self) -> String {
         self.root_path.to_string_lossy().to_string()
     }
@@ -290,6 +376,7 @@ pub struct CGroupTreeWidget;
 
 impl CGroupTreeWidget {
     pub fn draw(f: &mut Frame, app: &App, tree_state: &CGroupTreeState, area: Rect) {
+        let theme = &app.theme;
         // Calculate the visible range based on scroll offset
         let visible_height = area.height.saturating_sub(2) as usize; // Account for borders
         let start_idx = tree_state.scroll_offset;
@@ -305,46 +392,42 @@ impl CGroupTreeWidget {
                     let node = tree_state.nodes.get(node_path)?;
                     let stats = metrics.resource_usage.get(&node.path)?;
 
-                    let memory_current_info = format_bytes(stats.memory.current);
-                    let memory_peak_info = format_bytes(stats.memory.peak);
-                    let cpu_info = format_duration_usec(stats.cpu.usage_usec);
+                    let root = &app.config.cgroup_root;
+                    let memory_current_info = CgroupColumn::Memory.format(&node.path, root, stats);
+                    let memory_peak_info = CgroupColumn::Peak.format(&node.path, root, stats);
+                    let cpu_info = CgroupColumn::Cpu.format(&node.path, root, stats);
 
                     // Create tree visualization with proper indentation and tree chars
                     let tree_prefix = Self::get_tree_prefix(node, tree_state);
-                    let expand_indicator = if !node.children.is_empty() {
-                        if node.expanded { "▼ " } else { "▶ " }
-                    } else {
-                        "  "
-                    };
+                    let expand_indicator = Self::expand_indicator(node);
 
-                    // Style based on selection
+                    // Style based on selection and marks
+                    let marked = tree_state.marked.contains(node_path);
                     let name_style = if tree_state.selected.as_ref() == Some(node_path) {
-                        Style::default()
-                            .fg(Color::Black)
-                            .bg(Color::Green)
-                            .add_modifier(Modifier::BOLD)
+                        theme.selected
+                    } else if marked {
+                        theme.bold(theme.warning)
                     } else {
-                        Style::default().fg(Color::Green)
+                        theme.fg(theme.good)
                     };
+                    let mark = if marked { "*" } else { "" };
+
+                    let frozen_badge = if stats.frozen { " ❄ frozen" } else { "" };
 
                     let line = Line::from(vec![
-                        Span::styled(tree_prefix, Style::default().fg(Color::DarkGray)),
-                        Span::styled(expand_indicator, Style::default().fg(Color::Blue)),
+                        Span::styled(tree_prefix, theme.fg(theme.dim)),
+                        Span::styled(expand_indicator, theme.fg(theme.primary)),
+                        Span::styled(mark, theme.bold(theme.warning)),
                         Span::styled(&node.name, name_style),
+                        Span::styled(frozen_badge, theme.bold(theme.accent)),
                         Span::raw(" - "),
                         Span::styled(
                             format!("Mem: {}", memory_current_info),
-                            Style::default().fg(Color::Yellow),
-                        ),
-                        Span::styled(
-                            format!("/{}", memory_peak_info),
-                            Style::default().fg(Color::DarkGray),
+                            theme.fg(theme.warning),
                         ),
+                        Span::styled(format!("/{}", memory_peak_info), theme.fg(theme.dim)),
                         Span::raw(" | "),
-                        Span::styled(
-                            format!("CPU: {}", cpu_info),
-                            Style::default().fg(Color::Cyan),
-                        ),
+                        Span::styled(format!("CPU: {}", cpu_info), theme.fg(theme.accent)),
                     ]);
                     Some(ListItem::new(line))
                 })
@@ -353,18 +436,39 @@ impl CGroupTreeWidget {
             vec![ListItem::new("Loading cgroup data...")]
         };
 
+        let marked = if tree_state.marked.is_empty() {
+            String::new()
+        } else {
+            format!("{} marked, c: clear, ", tree_state.marked.len())
+        };
         let list = List::new(items)
             .block(
                 Block::default()
-                    .title("cgroup Tree (↑↓: navigate, →: expand, ←: collapse, Enter/Space: toggle, d: delete, D: clean parent)")
+                    .title(format!("cgroup Tree ({}↑↓: navigate, →/Enter: expand, ←: collapse, Space/m: mark, f: freeze/thaw, K: kill, d: delete, D: clean parent, e: export)", marked))
                     .borders(Borders::ALL)
-                    .style(Style::default().fg(Color::Blue)),
+                    .style(theme.fg(theme.primary)),
             )
-            .style(Style::default().fg(Color::White));
+            .style(theme.fg(theme.text));
 
         f.render_widget(list, area);
     }
 
+    /// Expand/collapse glyph drawn before the name of `node`
+    fn expand_indicator(node: &CGroupTreeNode) -> &'static str {
+        if node.children.is_empty() {
+            "  "
+        } else if node.expanded {
+            "▼ "
+        } else {
+            "▶ "
+        }
+    }
+
+    /// Column offset (inside the list border) of the expand/collapse glyph for `node`
+    pub fn expand_indicator_offset(node: &CGroupTreeNode, tree_state: &CGroupTreeState) -> usize {
+        Self::get_tree_prefix(node, tree_state).chars().count()
+    }
+
     fn get_tree_prefix(node: &CGroupTreeNode, tree_state: &CGroupTreeState) -> String {
         if node.depth == 0 {
             return String::new();
@@ -437,57 +541,176 @@ impl CGroupTreeWidget {
     }
 }
 
+pub struct TabBarWidget;
+
+impl TabBarWidget {
+    const DIVIDER: &'static str = "│";
+
+    pub fn draw(f: &mut Frame, app: &App, area: Rect) {
+        let theme = &app.theme;
+        let mut spans = Vec::new();
+        for (i, title) in TAB_TITLES.iter().enumerate() {
+            if i > 0 {
+                spans.push(Span::styled(Self::DIVIDER, theme.fg(theme.dim)));
+            }
+
+            let style = if i == app.ui_state.current_tab {
+                theme.selected
+            } else {
+                theme.fg(theme.accent)
+            };
+            spans.push(Span::styled(format!(" {} ", title), style));
+        }
+
+        f.render_widget(Paragraph::new(Line::from(spans)), area);
+    }
+
+    /// Screen area of each tab title, matching the layout used by `draw`
+    pub fn title_areas(area: Rect) -> Vec<Rect> {
+        let mut areas = Vec::with_capacity(TAB_TITLES.len());
+        let mut x = area.x;
+
+        for (i, title) in TAB_TITLES.iter().enumerate() {
+            if i > 0 {
+                x = x.saturating_add(Self::DIVIDER.chars().count() as u16);
+            }
+
+            let width = title.chars().count() as u16 + 2;
+            areas.push(Rect::new(x, area.y, width, 1).intersection(area));
+            x = x.saturating_add(width);
+        }
+
+        areas
+    }
+}
+
 pub struct ProcessListWidget;
 
 impl ProcessListWidget {
+    pub const COLUMNS: [(ProcessSortColumn, &'static str); 3] = [
+        (ProcessSortColumn::Pid, "PID"),
+        (ProcessSortColumn::Command, "Command"),
+        (ProcessSortColumn::Cgroup, "cgroup"),
+    ];
+
+    const WIDTHS: [Constraint; 3] = [
+        Constraint::Length(8),
+        Constraint::Length(20),
+        Constraint::Min(20),
+    ];
+
     pub fn draw(f: &mut Frame, app: &App, area: Rect) {
-        let rows: Vec<Row> = if let Some(ref metrics) = app.cgroup_data.metrics {
-            // Collect and sort processes by PID first
-            let mut process_data: Vec<_> = metrics.processes.iter().collect();
-            process_data.sort_by_key(|(pid, _)| **pid);
+        let theme = &app.theme;
+        // Header row and borders take three lines
+        let visible_height = area.height.saturating_sub(3) as usize;
 
-            // Create rows from sorted data, limiting to first 100 for performance
-            process_data
+        let rows: Vec<Row> = if app.cgroup_data.metrics.is_some() {
+            Self::sorted_processes(app)
                 .into_iter()
-                .take(100)
-                .map(|(pid, cgroup_path)| {
-                    Row::new(vec![
-                        pid.to_string(),
-                        format!("pid-{}", pid), // Simple process identifier
-                        Self::format_cgroup_display(cgroup_path, &app.config.cgroup_root),
-                    ])
+                .enumerate()
+                .skip(app.ui_state.scroll_offset)
+                .take(visible_height)
+                .map(|(index, (pid, cgroup_path))| {
+                    let marker = if app.ui_state.marked_pids.contains(&pid) {
+                        "*"
+                    } else {
+                        " "
+                    };
+                    let [pid_cell, command, cgroup] =
+                        Self::cells(pid, cgroup_path, &app.config.cgroup_root);
+                    let row = Row::new(vec![format!("{}{}", marker, pid_cell), command, cgroup]);
+                    if index == app.ui_state.process_cursor {
+                        row.style(theme.selected)
+                    } else {
+                        row
+                    }
                 })
                 .collect()
         } else {
             vec![Row::new(vec!["Loading...", "", ""])]
         };
 
-        let widths = [
-            Constraint::Length(8),
-            Constraint::Length(20),
-            Constraint::Min(20),
-        ];
+        let sort = app.ui_state.process_sort;
+        let header = Row::new(Self::COLUMNS.iter().map(|(column, label)| {
+            if *column == sort.column {
+                format!("{}{}", label, if sort.descending { " ▼" } else { " ▲" })
+            } else {
+                label.to_string()
+            }
+        }))
+        .style(Style::default().add_modifier(Modifier::BOLD));
 
-        let table = Table::new(rows, widths)
-            .header(
-                Row::new(vec!["PID", "Command", "cgroup"])
-                    .style(Style::default().add_modifier(Modifier::BOLD)),
-            )
+        let table = Table::new(rows, Self::WIDTHS)
+            .header(header)
             .block(
                 Block::default()
-                    .title("Process List")
+                    .title(
+                        "Process List (click a header to sort, Space/m: mark, c: clear marks, s: signal, v: move, V: move all)",
+                    )
                     .borders(Borders::ALL)
-                    .style(Style::default().fg(Color::Blue)),
+                    .style(theme.fg(theme.primary)),
             )
-            .style(Style::default().fg(Color::White));
+            .style(theme.fg(theme.text));
 
         f.render_widget(table, area);
     }
 
-    fn format_cgroup_display(path: &str, root_path: &PathBuf) -> String {
-        path.strip_prefix(root_path.to_string_lossy().as_ref())
-            .unwrap_or(path)
-            .to_string()
+    /// Screen area of each column header, matching the layout used by `draw`
+    pub fn header_areas(area: Rect) -> Vec<(ProcessSortColumn, Rect)> {
+        let inner = Block::default().borders(Borders::ALL).inner(area);
+        let header = Rect { height: 1, ..inner };
+
+        // Table lays out its columns with a single space between them
+        let columns = Layout::horizontal(Self::WIDTHS).spacing(1).split(header);
+
+        Self::COLUMNS
+            .iter()
+            .zip(columns.iter())
+            .map(|((column, _), rect)| (*column, *rect))
+            .collect()
+    }
+
+    /// Processes ordered by the current sort column
+    pub fn sorted_processes(app: &App) -> Vec<(u32, &String)> {
+        let Some(ref metrics) = app.cgroup_data.metrics else {
+            return Vec::new();
+        };
+
+        let mut process_data: Vec<_> = metrics
+            .processes
+            .iter()
+            .map(|(pid, path)| (*pid, path))
+            .collect();
+
+        let sort = app.ui_state.process_sort;
+        match sort.column {
+            ProcessSortColumn::Pid => process_data.sort_by_key(|(pid, _)| *pid),
+            ProcessSortColumn::Command => {
+                process_data.sort_by_cached_key(|(pid, _)| Self::command_label(*pid))
+            }
+            ProcessSortColumn::Cgroup => {
+                process_data.sort_by(|(a_pid, a), (b_pid, b)| a.cmp(b).then(a_pid.cmp(b_pid)))
+            }
+        }
+
+        if sort.descending {
+            process_data.reverse();
+        }
+
+        process_data
+    }
+
+    /// Cell contents for `COLUMNS`, also used by the batch output
+    pub fn cells(pid: u32, cgroup_path: &str, root_path: &Path) -> [String; 3] {
+        [
+            pid.to_string(),
+            Self::command_label(pid),
+            display_path(cgroup_path, root_path),
+        ]
+    }
+
+    fn command_label(pid: u32) -> String {
+        format!("pid-{}", pid) // Simple process identifier
     }
 }
 
@@ -495,50 +718,42 @@ pub struct ResourceGraphWidget;
 
 impl ResourceGraphWidget {
     pub fn draw(f: &mut Frame, app: &App, area: Rect) {
+        let theme = &app.theme;
         let content = if let Some(ref metrics) = app.cgroup_data.metrics {
             if let Some(selected_path) = &app.ui_state.selected_cgroup {
                 if let Some(stats) = metrics.resource_usage.get(selected_path) {
-                    Self::create_styled_resource_view(selected_path, stats)
+                    Self::create_styled_resource_view(theme, selected_path, stats)
                 } else {
                     Text::from(vec![Line::from(vec![Span::styled(
                         "Selected cgroup not found",
-                        Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+                        theme.bold(theme.critical),
                     )])])
                 }
             } else {
                 Text::from(vec![
                     Line::from(vec![Span::styled(
                         "cgroup Monitor",
-                        Style::default()
-                            .fg(Color::Cyan)
-                            .add_modifier(Modifier::BOLD),
+                        theme.bold(theme.accent),
                     )]),
                     Line::from(""),
                     Line::from(vec![
-                        Span::styled(
-                            "Total cgroups detected: ",
-                            Style::default().fg(Color::White),
-                        ),
+                        Span::styled("Total cgroups detected: ", theme.fg(theme.text)),
                         Span::styled(
                             format!("{}", metrics.resource_usage.len()),
-                            Style::default()
-                                .fg(Color::Yellow)
-                                .add_modifier(Modifier::BOLD),
+                            theme.bold(theme.warning),
                         ),
                     ]),
                     Line::from(""),
                     Line::from(vec![Span::styled(
                         "Select a cgroup from the tree above to view detailed resource usage",
-                        Style::default().fg(Color::Gray),
+                        theme.fg(theme.muted),
                     )]),
                 ])
             }
         } else {
             Text::from(vec![Line::from(vec![Span::styled(
                 "Loading resource data...",
-                Style::default()
-                    .fg(Color::Yellow)
-                    .add_modifier(Modifier::BOLD),
+                theme.bold(theme.warning),
             )])])
         };
 
@@ -547,14 +762,15 @@ impl ResourceGraphWidget {
                 Block::default()
                     .title("Resource Usage")
                     .borders(Borders::ALL)
-                    .style(Style::default().fg(Color::Blue)),
+                    .style(theme.fg(theme.primary)),
             )
-            .style(Style::default().fg(Color::White));
+            .style(theme.fg(theme.text));
 
         f.render_widget(paragraph, area);
     }
 
     fn create_styled_resource_view(
+        theme: &Theme,
         selected_path: &str,
         stats: &crate::collection::ResourceStats,
     ) -> Text<'static> {
@@ -562,73 +778,50 @@ impl ResourceGraphWidget {
 
         // Header with cgroup path
         lines.push(Line::from(vec![
-            Span::styled(
-                "Selected: ",
-                Style::default()
-                    .fg(Color::Green)
-                    .add_modifier(Modifier::BOLD),
-            ),
-            Span::styled(selected_path.to_string(), Style::default().fg(Color::Cyan)),
+            Span::styled("Selected: ", theme.bold(theme.good)),
+            Span::styled(selected_path.to_string(), theme.fg(theme.accent)),
         ]));
         lines.push(Line::from(""));
 
         // Memory Overview Section
         lines.push(Line::from(vec![Span::styled(
             "MEMORY OVERVIEW",
-            Style::default()
-                .fg(Color::Magenta)
-                .add_modifier(Modifier::BOLD),
+            theme.bold(theme.secondary),
         )]));
 
         lines.push(Line::from(vec![
-            Span::styled("  Current: ", Style::default().fg(Color::White)),
-            Span::styled(
-                format_bytes(stats.memory.current),
-                Style::default()
-                    .fg(Color::Cyan)
-                    .add_modifier(Modifier::BOLD),
-            ),
-            Span::styled(" | Peak: ", Style::default().fg(Color::White)),
-            Span::styled(
-                format_bytes(stats.memory.peak),
-                Style::default()
-                    .fg(Color::Yellow)
-                    .add_modifier(Modifier::BOLD),
-            ),
+            Span::styled("  Current: ", theme.fg(theme.text)),
+            Span::styled(format_bytes(stats.memory.current), theme.bold(theme.accent)),
+            Span::styled(" | Peak: ", theme.fg(theme.text)),
+            Span::styled(format_bytes(stats.memory.peak), theme.bold(theme.warning)),
         ]));
 
         lines.push(Line::from(vec![
-            Span::styled("  High: ", Style::default().fg(Color::White)),
+            Span::styled("  High: ", theme.fg(theme.text)),
             Span::styled(
                 stats
                     .memory
                     .high
-                    .map_or("unlimited".to_string(), |h| format_bytes(h)),
+                    .map_or("unlimited".to_string(), format_bytes),
                 if stats.memory.high.is_some() {
-                    Style::default()
-                        .fg(Color::Yellow)
-                        .add_modifier(Modifier::BOLD)
+                    theme.bold(theme.warning)
                 } else {
-                    Style::default()
-                        .fg(Color::Green)
-                        .add_modifier(Modifier::BOLD)
+                    theme.bold(theme.good)
                 },
             ),
         ]));
 
         lines.push(Line::from(vec![
-            Span::styled("  Limit: ", Style::default().fg(Color::White)),
+            Span::styled("  Limit: ", theme.fg(theme.text)),
             Span::styled(
                 stats
                     .memory
                     .max
-                    .map_or("unlimited".to_string(), |m| format_bytes(m)),
+                    .map_or("unlimited".to_string(), format_bytes),
                 if stats.memory.max.is_some() {
-                    Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
+                    theme.bold(theme.critical)
                 } else {
-                    Style::default()
-                        .fg(Color::Green)
-                        .add_modifier(Modifier::BOLD)
+                    theme.bold(theme.good)
                 },
             ),
         ]));
@@ -636,211 +829,197 @@ impl ResourceGraphWidget {
 
         // Memory Breakdown Section
         lines.push(Line::from(vec![
-            Span::styled(
-                "MEMORY BREAKDOWN",
-                Style::default()
-                    .fg(Color::Blue)
-                    .add_modifier(Modifier::BOLD),
-            ),
-            Span::styled(" (memory.stat)", Style::default().fg(Color::Gray)),
+            Span::styled("MEMORY BREAKDOWN", theme.bold(theme.primary)),
+            Span::styled(" (memory.stat)", theme.fg(theme.muted)),
         ]));
 
         Self::add_memory_item(
+            theme,
             &mut lines,
             "",
             "Anonymous (heap/stack)",
             stats.memory.anon,
-            Color::Red,
+            theme.critical,
         );
         Self::add_memory_item(
+            theme,
             &mut lines,
             "",
             "File Cache",
             stats.memory.file,
-            Color::Green,
+            theme.good,
         );
         Self::add_memory_item(
+            theme,
             &mut lines,
             "",
             "Kernel Stack",
             stats.memory.kernel_stack,
-            Color::Yellow,
+            theme.warning,
         );
         Self::add_memory_item(
+            theme,
             &mut lines,
             "",
             "Slab (kernel structures)",
             stats.memory.slab,
-            Color::Cyan,
+            theme.accent,
         );
         Self::add_memory_item(
+            theme,
             &mut lines,
             "",
             "Socket Buffers",
             stats.memory.sock,
-            Color::Magenta,
+            theme.secondary,
         );
         lines.push(Line::from(""));
 
         // Memory Activity Section
         lines.push(Line::from(vec![Span::styled(
             "MEMORY ACTIVITY",
-            Style::default()
-                .fg(Color::Yellow)
-                .add_modifier(Modifier::BOLD),
+            theme.bold(theme.warning),
         )]));
 
         Self::add_memory_item(
+            theme,
             &mut lines,
             "",
             "Active Anonymous",
             stats.memory.active_anon,
-            Color::Red,
+            theme.critical,
         );
         Self::add_memory_item(
+            theme,
             &mut lines,
             "",
             "Inactive Anonymous",
             stats.memory.inactive_anon,
-            Color::DarkGray,
+            theme.dim,
         );
         Self::add_memory_item(
+            theme,
             &mut lines,
             "",
             "Active File Cache",
             stats.memory.active_file,
-            Color::Green,
+            theme.good,
         );
         Self::add_memory_item(
+            theme,
             &mut lines,
             "",
             "Inactive File Cache",
             stats.memory.inactive_file,
-            Color::DarkGray,
+            theme.dim,
         );
         lines.push(Line::from(""));
 
         // Page Faults Section
         lines.push(Line::from(vec![Span::styled(
             "PAGE FAULTS",
-            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            theme.bold(theme.critical),
         )]));
 
         lines.push(Line::from(vec![
-            Span::styled("  Total: ", Style::default().fg(Color::White)),
+            Span::styled("  Total: ", theme.fg(theme.text)),
             Span::styled(
                 format!("{}", stats.memory.pgfault),
-                Style::default()
-                    .fg(Color::Yellow)
-                    .add_modifier(Modifier::BOLD),
+                theme.bold(theme.warning),
             ),
-            Span::styled(" | Major: ", Style::default().fg(Color::White)),
+            Span::styled(" | Major: ", theme.fg(theme.text)),
             Span::styled(
                 format!("{}", stats.memory.pgmajfault),
-                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+                theme.bold(theme.critical),
             ),
         ]));
         lines.push(Line::from(""));
 
         // Memory Pressure Section
         if let Some(ref pressure) = stats.memory.pressure {
-            let pressure_color = Self::get_pressure_color(pressure.some_avg10);
+            let pressure_color = Self::get_pressure_color(theme, pressure.some_avg10);
             lines.push(Line::from(vec![
-                Span::styled(
-                    "MEMORY PRESSURE",
-                    Style::default()
-                        .fg(pressure_color)
-                        .add_modifier(Modifier::BOLD),
-                ),
-                Span::styled(" (PSI)", Style::default().fg(Color::Gray)),
+                Span::styled("MEMORY PRESSURE", theme.bold(pressure_color)),
+                Span::styled(" (PSI)", theme.fg(theme.muted)),
             ]));
 
             lines.push(Line::from(vec![Span::styled(
                 "  Some Tasks Delayed:",
-                Style::default().fg(Color::White),
+                theme.fg(theme.text),
             )]));
             lines.push(Line::from(vec![
-                Span::styled("    10s: ", Style::default().fg(Color::Gray)),
+                Span::styled("    10s: ", theme.fg(theme.muted)),
                 Span::styled(
                     format!("{:.1}%", pressure.some_avg10),
                     Style::default()
-                        .fg(Self::get_pressure_color(pressure.some_avg10))
+                        .fg(Self::get_pressure_color(theme, pressure.some_avg10))
                         .add_modifier(Modifier::BOLD),
                 ),
-                Span::styled(" | 1m: ", Style::default().fg(Color::Gray)),
+                Span::styled(" | 1m: ", theme.fg(theme.muted)),
                 Span::styled(
                     format!("{:.1}%", pressure.some_avg60),
                     Style::default()
-                        .fg(Self::get_pressure_color(pressure.some_avg60))
+                        .fg(Self::get_pressure_color(theme, pressure.some_avg60))
                         .add_modifier(Modifier::BOLD),
                 ),
-                Span::styled(" | 5m: ", Style::default().fg(Color::Gray)),
+                Span::styled(" | 5m: ", theme.fg(theme.muted)),
                 Span::styled(
                     format!("{:.1}%", pressure.some_avg300),
                     Style::default()
-                        .fg(Self::get_pressure_color(pressure.some_avg300))
+                        .fg(Self::get_pressure_color(theme, pressure.some_avg300))
                         .add_modifier(Modifier::BOLD),
                 ),
             ]));
             lines.push(Line::from(vec![
-                Span::styled("    Total: ", Style::default().fg(Color::Gray)),
+                Span::styled("    Total: ", theme.fg(theme.muted)),
                 Span::styled(
                     format!("{}ms", pressure.some_total / 1000),
-                    Style::default()
-                        .fg(Color::White)
-                        .add_modifier(Modifier::BOLD),
+                    theme.bold(theme.text),
                 ),
             ]));
 
             lines.push(Line::from(vec![Span::styled(
                 "  All Tasks Delayed:",
-                Style::default().fg(Color::White),
+                theme.fg(theme.text),
             )]));
             lines.push(Line::from(vec![
-                Span::styled("    10s: ", Style::default().fg(Color::Gray)),
+                Span::styled("    10s: ", theme.fg(theme.muted)),
                 Span::styled(
                     format!("{:.1}%", pressure.full_avg10),
                     Style::default()
-                        .fg(Self::get_pressure_color(pressure.full_avg10))
+                        .fg(Self::get_pressure_color(theme, pressure.full_avg10))
                         .add_modifier(Modifier::BOLD),
                 ),
-                Span::styled(" | 1m: ", Style::default().fg(Color::Gray)),
+                Span::styled(" | 1m: ", theme.fg(theme.muted)),
                 Span::styled(
                     format!("{:.1}%", pressure.full_avg60),
                     Style::default()
-                        .fg(Self::get_pressure_color(pressure.full_avg60))
+                        .fg(Self::get_pressure_color(theme, pressure.full_avg60))
                         .add_modifier(Modifier::BOLD),
                 ),
-                Span::styled(" | 5m: ", Style::default().fg(Color::Gray)),
+                Span::styled(" | 5m: ", theme.fg(theme.muted)),
                 Span::styled(
                     format!("{:.1}%", pressure.full_avg300),
                     Style::default()
-                        .fg(Self::get_pressure_color(pressure.full_avg300))
+                        .fg(Self::get_pressure_color(theme, pressure.full_avg300))
                         .add_modifier(Modifier::BOLD),
                 ),
             ]));
             lines.push(Line::from(vec![
-                Span::styled("    Total: ", Style::default().fg(Color::Gray)),
+                Span::styled("    Total: ", theme.fg(theme.muted)),
                 Span::styled(
                     format!("{}ms", pressure.full_total / 1000),
-                    Style::default()
-                        .fg(Color::White)
-                        .add_modifier(Modifier::BOLD),
+                    theme.bold(theme.text),
                 ),
             ]));
         } else {
             lines.push(Line::from(vec![
-                Span::styled(
-                    "MEMORY PRESSURE",
-                    Style::default()
-                        .fg(Color::Gray)
-                        .add_modifier(Modifier::BOLD),
-                ),
-                Span::styled(" (PSI)", Style::default().fg(Color::Gray)),
+                Span::styled("MEMORY PRESSURE", theme.bold(theme.muted)),
+                Span::styled(" (PSI)", theme.fg(theme.muted)),
             ]));
             lines.push(Line::from(vec![Span::styled(
                 "  Not available (memory.pressure file not found)",
-                Style::default().fg(Color::Gray),
+                theme.fg(theme.muted),
             )]));
         }
         lines.push(Line::from(""));
@@ -849,29 +1028,23 @@ impl ResourceGraphWidget {
         if stats.cgroup_procs.is_empty() {
             lines.push(Line::from(vec![Span::styled(
                 "CGROUP PROCESSES",
-                Style::default()
-                    .fg(Color::Gray)
-                    .add_modifier(Modifier::BOLD),
+                theme.bold(theme.muted),
             )]));
             lines.push(Line::from(vec![Span::styled(
                 "  No processes in this cgroup",
-                Style::default().fg(Color::Gray),
+                theme.fg(theme.muted),
             )]));
         } else {
             lines.push(Line::from(vec![Span::styled(
                 "CGROUP PROCESSES",
-                Style::default()
-                    .fg(Color::Green)
-                    .add_modifier(Modifier::BOLD),
+                theme.bold(theme.good),
             )]));
 
             lines.push(Line::from(vec![
-                Span::styled("  Count: ", Style::default().fg(Color::White)),
+                Span::styled("  Count: ", theme.fg(theme.text)),
                 Span::styled(
                     format!("{}", stats.cgroup_procs.len()),
-                    Style::default()
-                        .fg(Color::Yellow)
-                        .add_modifier(Modifier::BOLD),
+                    theme.bold(theme.warning),
                 ),
             ]));
 
@@ -898,8 +1071,8 @@ impl ResourceGraphWidget {
             };
 
             lines.push(Line::from(vec![
-                Span::styled("  PIDs: ", Style::default().fg(Color::White)),
-                Span::styled(process_list, Style::default().fg(Color::Cyan)),
+                Span::styled("  PIDs: ", theme.fg(theme.text)),
+                Span::styled(process_list, theme.fg(theme.accent)),
             ]));
         }
         lines.push(Line::from(""));
@@ -907,43 +1080,29 @@ impl ResourceGraphWidget {
         // Other Resources Section
         lines.push(Line::from(vec![Span::styled(
             "OTHER RESOURCES",
-            Style::default()
-                .fg(Color::Cyan)
-                .add_modifier(Modifier::BOLD),
+            theme.bold(theme.accent),
         )]));
 
         lines.push(Line::from(vec![
-            Span::styled("  CPU Time: ", Style::default().fg(Color::White)),
+            Span::styled("  CPU Time: ", theme.fg(theme.text)),
             Span::styled(
                 format_duration_usec(stats.cpu.usage_usec),
-                Style::default()
-                    .fg(Color::Yellow)
-                    .add_modifier(Modifier::BOLD),
+                theme.bold(theme.warning),
             ),
         ]));
 
         lines.push(Line::from(vec![
-            Span::styled("  IO Read: ", Style::default().fg(Color::White)),
-            Span::styled(
-                format_bytes(stats.io.rbytes),
-                Style::default()
-                    .fg(Color::Green)
-                    .add_modifier(Modifier::BOLD),
-            ),
-            Span::styled(" / Write: ", Style::default().fg(Color::White)),
-            Span::styled(
-                format_bytes(stats.io.wbytes),
-                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
-            ),
+            Span::styled("  IO Read: ", theme.fg(theme.text)),
+            Span::styled(format_bytes(stats.io.rbytes), theme.bold(theme.good)),
+            Span::styled(" / Write: ", theme.fg(theme.text)),
+            Span::styled(format_bytes(stats.io.wbytes), theme.bold(theme.critical)),
         ]));
 
         lines.push(Line::from(vec![
-            Span::styled("  PIDs: ", Style::default().fg(Color::White)),
+            Span::styled("  PIDs: ", theme.fg(theme.text)),
             Span::styled(
                 format!("{}", stats.pids.current),
-                Style::default()
-                    .fg(Color::Magenta)
-                    .add_modifier(Modifier::BOLD),
+                theme.bold(theme.secondary),
             ),
         ]));
 
@@ -951,6 +1110,7 @@ impl ResourceGraphWidget {
     }
 
     fn add_memory_item(
+        theme: &Theme,
         lines: &mut Vec<Line<'static>>,
         _emoji: &str,
         label: &str,
@@ -958,21 +1118,18 @@ impl ResourceGraphWidget {
         color: Color,
     ) {
         lines.push(Line::from(vec![
-            Span::styled(format!("  {}: ", label), Style::default().fg(Color::White)),
-            Span::styled(
-                format_bytes(value),
-                Style::default().fg(color).add_modifier(Modifier::BOLD),
-            ),
+            Span::styled(format!("  {}: ", label), theme.fg(theme.text)),
+            Span::styled(format_bytes(value), theme.bold(color)),
         ]));
     }
 
-    fn get_pressure_color(pressure: f64) -> Color {
+    fn get_pressure_color(theme: &Theme, pressure: f64) -> Color {
         if pressure < 10.0 {
-            Color::Green
+            theme.good
         } else if pressure < 50.0 {
-            Color::Yellow
+            theme.warning
         } else {
-            Color::Red
+            theme.critical
         }
     }
 }
@@ -0,0 +1,1020 @@
+mod common;
+
+use cgtop::actions::{
+    Eviction, KillMethod, MemberPolicy, RemovalOutcome, RemoveOptions, explain_move_error,
+    is_frozen, is_kernel_thread_stat, is_populated, kill_all, move_processes, parse_signal,
+    plan_removal, read_procs, remove_cgroup_tree, send_signal, validate_cgroup_name, write_freeze,
+};
+use cgtop::app::App;
+use cgtop::app::dialog::{Dialog, MemberAction};
+use cgtop::collection::{CGroupMetrics, ResourceStats};
+use cgtop::columns::CsvColumn;
+use cgtop::widgets::DialogWidget;
+use chrono::Utc;
+use common::{app_for, press, type_text};
+use crossterm::event::KeyCode;
+use hashbrown::HashMap;
+use nix::errno::Errno;
+use nix::sys::signal::Signal;
+use pretty_assertions::assert_eq;
+use ratatui::{Terminal, backend::TestBackend};
+use std::fs;
+use std::os::unix::process::ExitStatusExt;
+use std::path::Path;
+use std::process::Command;
+use std::time::Instant;
+use tempfile::TempDir;
+
+#[test]
+fn test_remove_cgroup_tree_removes_leaves_first() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path().join("test.slice");
+    fs::create_dir_all(root.join("a.scope/inner")).unwrap();
+    fs::create_dir_all(root.join("b.scope")).unwrap();
+
+    let report = remove_cgroup_tree(&root, &RemoveOptions::default());
+
+    assert!(report.is_success(), "{}", report);
+    assert_eq!(report.removed_count(), 4);
+    assert_eq!(report.entries.last().unwrap().path, root);
+    assert_eq!(
+        report.entries.last().unwrap().outcome,
+        RemovalOutcome::Removed { attempts: 1 }
+    );
+    assert!(!root.exists());
+}
+
+#[test]
+fn test_remove_cgroup_tree_refuses_populated_cgroups() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path().join("test.slice");
+    let busy = root.join("busy.scope");
+    fs::create_dir_all(&busy).unwrap();
+    fs::create_dir_all(root.join("idle.scope")).unwrap();
+    fs::write(busy.join("cgroup.events"), "populated 1\nfrozen 0\n").unwrap();
+
+    assert!(is_populated(&busy));
+
+    let report = remove_cgroup_tree(&root, &RemoveOptions::default());
+
+    assert!(!report.is_success());
+    assert!(!root.join("idle.scope").exists());
+    assert!(busy.exists());
+
+    let outcome_of = |path: &std::path::Path| {
+        report
+            .entries
+            .iter()
+            .find(|entry| entry.path == path)
+            .map(|entry| entry.outcome.clone())
+    };
+    assert_eq!(outcome_of(&busy), Some(RemovalOutcome::Populated));
+    assert_eq!(outcome_of(&root), Some(RemovalOutcome::Skipped));
+    assert_eq!(report.failures().count(), 1);
+}
+
+#[test]
+fn test_remove_cgroup_tree_kills_or_migrates_members_first() {
+    let temp_dir = TempDir::new().unwrap();
+    let parent = temp_dir.path().join("test.slice");
+    let tree = parent.join("job.scope");
+    fs::create_dir_all(tree.join("worker")).unwrap();
+    fs::write(tree.join("cgroup.procs"), "4242\n").unwrap();
+    fs::write(tree.join("worker/cgroup.procs"), "4343\n").unwrap();
+    fs::write(tree.join("cgroup.kill"), "").unwrap();
+
+    let kill = RemoveOptions {
+        member_policy: MemberPolicy::Kill,
+        ..RemoveOptions::default()
+    };
+    let report = remove_cgroup_tree(&tree, &kill);
+    assert_eq!(
+        report.eviction,
+        Some(Eviction::Killed(Ok(KillMethod::CgroupKill)))
+    );
+    assert_eq!(fs::read_to_string(tree.join("cgroup.kill")).unwrap(), "1");
+
+    let migrate = RemoveOptions {
+        member_policy: MemberPolicy::MigrateTo(parent.clone()),
+        ..RemoveOptions::default()
+    };
+    let report = remove_cgroup_tree(&tree, &migrate);
+    let Some(Eviction::Migrated(moves)) = &report.eviction else {
+        panic!("expected a migration, got {:?}", report.eviction);
+    };
+    assert_eq!(moves.target, parent);
+    assert_eq!(moves.moved_count(), 2);
+    // The kernel takes one PID per write, the fake file keeps the last one
+    assert_eq!(
+        fs::read_to_string(parent.join("cgroup.procs")).unwrap(),
+        "4242"
+    );
+
+    let report = remove_cgroup_tree(&tree, &RemoveOptions::default());
+    assert_eq!(report.eviction, None);
+}
+
+#[test]
+fn test_remove_missing_cgroup_reports_failure() {
+    let temp_dir = TempDir::new().unwrap();
+    let report = remove_cgroup_tree(&temp_dir.path().join("gone"), &RemoveOptions::default());
+
+    assert_eq!(report.entries.len(), 1);
+    assert!(matches!(
+        report.entries[0].outcome,
+        RemovalOutcome::Failed(_)
+    ));
+}
+
+#[test]
+fn test_plan_removal_lists_cgroups_and_processes() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path().join("test.slice");
+    fs::create_dir_all(root.join("a.scope")).unwrap();
+    fs::write(root.join("a.scope/cgroup.procs"), "1\n").unwrap();
+
+    let plan = plan_removal(&root).unwrap();
+
+    assert!(plan.is_recursive());
+    assert_eq!(plan.cgroups.len(), 2);
+    assert_eq!(plan.cgroups[0].path, root.join("a.scope"));
+    assert_eq!(plan.cgroups[0].processes[0].0, 1);
+    assert_eq!(plan.cgroups[1].path, root);
+    assert_eq!(plan.process_count(), 1);
+    // Planning changes nothing
+    assert!(root.join("a.scope").exists());
+}
+
+#[test]
+fn test_single_cgroup_delete_needs_confirmation() {
+    let temp_dir = TempDir::new().unwrap();
+    let leaf = temp_dir.path().join("leaf.scope");
+    fs::create_dir(&leaf).unwrap();
+    let mut app = app_for(temp_dir.path());
+
+    app.request_delete("Delete cgroup".to_string(), &leaf);
+    assert!(matches!(
+        app.ui_state.dialog,
+        Some(Dialog::ConfirmDelete(_))
+    ));
+
+    press(&mut app, KeyCode::Char('n'));
+    assert!(app.ui_state.dialog.is_none());
+    assert!(leaf.exists());
+
+    app.request_delete("Delete cgroup".to_string(), &leaf);
+    press(&mut app, KeyCode::Char('y'));
+    assert!(app.ui_state.dialog.is_none());
+    assert!(!leaf.exists());
+}
+
+#[test]
+fn test_recursive_delete_requires_typed_name() {
+    let temp_dir = TempDir::new().unwrap();
+    let target = temp_dir.path().join("test.slice");
+    fs::create_dir_all(target.join("child.scope")).unwrap();
+    let mut app = app_for(temp_dir.path());
+
+    app.request_delete("Delete cgroup".to_string(), &target);
+    let Some(Dialog::ConfirmDelete(confirm)) = &app.ui_state.dialog else {
+        panic!("expected a delete confirmation");
+    };
+    assert_eq!(confirm.required_input().as_deref(), Some("test.slice"));
+
+    // 'y' is just text here, and Enter does nothing until the name matches
+    type_text(&mut app, "y");
+    press(&mut app, KeyCode::Enter);
+    assert!(app.ui_state.dialog.is_some());
+
+    press(&mut app, KeyCode::Backspace);
+    type_text(&mut app, "test.slice");
+    press(&mut app, KeyCode::Enter);
+    assert!(app.ui_state.dialog.is_none());
+    assert!(!target.exists());
+}
+
+#[test]
+fn test_delete_prompt_stays_visible_on_small_terminals() {
+    let temp_dir = TempDir::new().unwrap();
+    let target = temp_dir.path().join("test.slice");
+    for index in 0..10 {
+        let child = target.join(format!("child-{}.scope", index));
+        fs::create_dir_all(&child).unwrap();
+        fs::write(child.join("cgroup.procs"), "1\n2\n3\n4\n5\n6\n").unwrap();
+    }
+    let mut app = app_for(temp_dir.path());
+    app.request_delete("Delete cgroup".to_string(), &target);
+
+    let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+    terminal
+        .draw(|f| DialogWidget::draw(f, &app, f.area()))
+        .unwrap();
+    let screen: String = terminal
+        .backend()
+        .buffer()
+        .content()
+        .iter()
+        .map(|cell| cell.symbol())
+        .collect();
+
+    assert!(
+        screen.contains("Type test.slice to confirm: _"),
+        "{}",
+        screen
+    );
+    assert!(screen.contains("Enter: delete  Esc: cancel"));
+    assert!(screen.contains("more lines"));
+}
+
+#[test]
+fn test_dry_run_and_unsafe_paths_leave_cgroups_alone() {
+    let temp_dir = TempDir::new().unwrap();
+    let leaf = temp_dir.path().join("leaf.scope");
+    fs::create_dir(&leaf).unwrap();
+    let mut app = app_for(temp_dir.path());
+
+    app.config.dry_run = true;
+    app.request_delete("Delete cgroup".to_string(), &leaf);
+    press(&mut app, KeyCode::Enter);
+    assert!(app.ui_state.dialog.is_none());
+    assert!(leaf.exists());
+
+    // The root itself is refused before any dialog opens
+    app.request_delete("Delete cgroup".to_string(), temp_dir.path());
+    assert!(app.ui_state.dialog.is_none());
+    assert!(app.notifications.has_notifications());
+}
+
+#[test]
+fn test_write_freeze_leaves_the_state_to_cgroup_events() {
+    let temp_dir = TempDir::new().unwrap();
+    let cgroup = temp_dir.path();
+    fs::write(cgroup.join("cgroup.events"), "populated 1\nfrozen 0\n").unwrap();
+
+    // The write returns at once; the state follows from cgroup.events
+    write_freeze(cgroup, true).unwrap();
+    assert_eq!(
+        fs::read_to_string(cgroup.join("cgroup.freeze")).unwrap(),
+        "1"
+    );
+    assert!(!is_frozen(cgroup));
+
+    fs::write(cgroup.join("cgroup.events"), "populated 1\nfrozen 1\n").unwrap();
+    assert!(is_frozen(cgroup));
+    write_freeze(cgroup, false).unwrap();
+    assert_eq!(
+        fs::read_to_string(cgroup.join("cgroup.freeze")).unwrap(),
+        "0"
+    );
+}
+
+#[test]
+fn test_read_procs_skips_processes_outside_the_pid_namespace() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(temp_dir.path().join("cgroup.procs"), "0\n42\n0\n7\n").unwrap();
+
+    assert_eq!(read_procs(temp_dir.path()), [42, 7]);
+}
+
+#[test]
+fn test_kill_all_prefers_cgroup_kill() {
+    let temp_dir = TempDir::new().unwrap();
+    let cgroup = temp_dir.path();
+    fs::write(cgroup.join("cgroup.kill"), "").unwrap();
+
+    assert_eq!(kill_all(cgroup).unwrap(), KillMethod::CgroupKill);
+    assert_eq!(fs::read_to_string(cgroup.join("cgroup.kill")).unwrap(), "1");
+}
+
+#[test]
+fn test_kill_all_falls_back_to_signalling_pids() {
+    let temp_dir = TempDir::new().unwrap();
+    let cgroup = temp_dir.path().join("job.scope");
+    fs::create_dir_all(cgroup.join("worker")).unwrap();
+
+    let mut child = Command::new("sleep").arg("30").spawn().unwrap();
+    fs::write(
+        cgroup.join("worker/cgroup.procs"),
+        format!("{}\n", child.id()),
+    )
+    .unwrap();
+
+    let method = kill_all(&cgroup).unwrap();
+
+    assert!(matches!(method, KillMethod::Signalled { processes } if processes >= 1));
+    assert_eq!(child.wait().unwrap().signal(), Some(9));
+}
+
+#[test]
+fn test_kill_all_never_signals_a_process_group() {
+    let temp_dir = TempDir::new().unwrap();
+    let cgroup = temp_dir.path();
+    // 0 stands for a process in another PID namespace; kill(0) would hit our
+    // own process group, and 2147483648 would wrap to a negative group ID
+    fs::write(cgroup.join("cgroup.procs"), "0\n").unwrap();
+    assert_eq!(
+        kill_all(cgroup).unwrap(),
+        KillMethod::Signalled { processes: 0 }
+    );
+
+    fs::write(cgroup.join("cgroup.procs"), "0\n2147483648\n").unwrap();
+    let error = kill_all(cgroup).unwrap_err().to_string();
+    assert_eq!(
+        error,
+        "signalled 0 process(es), could not kill 2147483648 (EINVAL)"
+    );
+}
+
+#[test]
+fn test_freeze_toggle_and_kill_confirmation() {
+    let temp_dir = TempDir::new().unwrap();
+    let cgroup = temp_dir.path().join("batch.scope");
+    fs::create_dir(&cgroup).unwrap();
+    fs::write(cgroup.join("cgroup.freeze"), "0\n").unwrap();
+    fs::write(cgroup.join("cgroup.events"), "populated 1\nfrozen 1\n").unwrap();
+    fs::write(cgroup.join("cgroup.procs"), "").unwrap();
+    let mut app = app_for(temp_dir.path());
+    app.ui_state.selected_cgroup = Some(cgroup.display().to_string());
+
+    // Reported frozen, so the toggle thaws
+    app.toggle_freeze();
+    assert_eq!(
+        fs::read_to_string(cgroup.join("cgroup.freeze")).unwrap(),
+        "0"
+    );
+
+    // Nothing to kill in an empty cgroup
+    app.request_kill();
+    assert!(app.ui_state.dialog.is_none());
+
+    fs::write(cgroup.join("cgroup.procs"), "1\n").unwrap();
+    fs::write(cgroup.join("cgroup.kill"), "").unwrap();
+    app.request_kill();
+    assert!(matches!(app.ui_state.dialog, Some(Dialog::ConfirmKill(_))));
+    press(&mut app, KeyCode::Char('y'));
+    assert_eq!(fs::read_to_string(cgroup.join("cgroup.kill")).unwrap(), "1");
+}
+
+#[test]
+fn test_freeze_completes_on_the_tick() {
+    let temp_dir = TempDir::new().unwrap();
+    let cgroup = temp_dir.path().join("batch.scope");
+    fs::create_dir(&cgroup).unwrap();
+    fs::write(cgroup.join("cgroup.freeze"), "0\n").unwrap();
+    fs::write(cgroup.join("cgroup.events"), "populated 1\nfrozen 0\n").unwrap();
+    let mut app = app_for(temp_dir.path());
+    app.ui_state.selected_cgroup = Some(cgroup.display().to_string());
+
+    // Written right away, confirmed once cgroup.events follows
+    app.toggle_freeze();
+    assert_eq!(
+        fs::read_to_string(cgroup.join("cgroup.freeze")).unwrap(),
+        "1"
+    );
+    assert_eq!(app.ui_state.pending_freezes.len(), 1);
+    assert!(!app.notifications.has_notifications());
+
+    fs::write(cgroup.join("cgroup.events"), "populated 1\nfrozen 1\n").unwrap();
+    app.poll_pending_freezes();
+    assert!(app.ui_state.pending_freezes.is_empty());
+    assert_eq!(
+        app.notifications.get_latest().unwrap().message,
+        format!("Frozen: {}", cgroup.display())
+    );
+
+    // A thaw the kernel never confirms is reported after the timeout
+    app.toggle_freeze();
+    app.ui_state.pending_freezes[0].deadline = Instant::now();
+    app.poll_pending_freezes();
+    assert!(app.ui_state.pending_freezes.is_empty());
+    assert!(
+        app.notifications
+            .get_latest()
+            .unwrap()
+            .message
+            .contains("has not finished yet")
+    );
+}
+
+#[test]
+fn test_freeze_and_kill_refuse_denylisted_subtrees() {
+    let temp_dir = TempDir::new().unwrap();
+    let cgroup = temp_dir.path().join("system.slice/sshd.service");
+    fs::create_dir_all(&cgroup).unwrap();
+    fs::write(cgroup.join("cgroup.freeze"), "0\n").unwrap();
+    fs::write(cgroup.join("cgroup.procs"), "1\n").unwrap();
+    fs::write(cgroup.join("cgroup.kill"), "").unwrap();
+    let mut app = app_for(temp_dir.path());
+    app.ui_state.selected_cgroup = Some(cgroup.display().to_string());
+
+    app.toggle_freeze();
+    app.request_kill();
+    app.open_cgroup_signal_menu();
+
+    assert!(app.ui_state.dialog.is_none());
+    assert_eq!(
+        fs::read_to_string(cgroup.join("cgroup.freeze")).unwrap(),
+        "0\n"
+    );
+    assert!(app.audit.entries().is_empty());
+}
+
+#[test]
+fn test_delete_freeze_and_kill_refuse_parents_of_denylisted_cgroups() {
+    let temp_dir = TempDir::new().unwrap();
+    let parent = temp_dir.path().join("machine.slice");
+    let protected = parent.join("db.scope");
+    fs::create_dir_all(&protected).unwrap();
+    for cgroup in [&parent, &protected] {
+        fs::write(cgroup.join("cgroup.freeze"), "0\n").unwrap();
+        fs::write(cgroup.join("cgroup.procs"), "1\n").unwrap();
+        fs::write(cgroup.join("cgroup.kill"), "").unwrap();
+    }
+    let mut app = app_for(temp_dir.path());
+    app.config
+        .safety
+        .set_denylist(&["machine.slice/db.scope"])
+        .unwrap();
+    app.ui_state.selected_cgroup = Some(parent.display().to_string());
+
+    app.request_delete("Delete cgroup".to_string(), &parent);
+    assert!(app.ui_state.dialog.is_none());
+    app.toggle_freeze();
+    app.request_kill();
+    app.open_cgroup_signal_menu();
+
+    assert!(app.ui_state.dialog.is_none());
+    assert!(protected.exists());
+    assert_eq!(
+        fs::read_to_string(parent.join("cgroup.freeze")).unwrap(),
+        "0\n"
+    );
+    assert_eq!(fs::read_to_string(parent.join("cgroup.kill")).unwrap(), "");
+    assert!(app.audit.entries().is_empty());
+}
+
+#[test]
+fn test_validate_cgroup_name() {
+    assert!(validate_cgroup_name("scratch").is_ok());
+    assert!(validate_cgroup_name("build-42.scope").is_ok());
+
+    assert!(validate_cgroup_name("").is_err());
+    assert!(validate_cgroup_name("..").is_err());
+    assert!(validate_cgroup_name("a/b").is_err());
+    assert!(validate_cgroup_name("two words").is_err());
+    assert!(validate_cgroup_name("memory.test").is_err());
+    assert!(validate_cgroup_name("cgroup.procs").is_err());
+    assert!(validate_cgroup_name(&"x".repeat(256)).is_err());
+}
+
+#[test]
+fn test_new_child_and_subtree_control_dialogs() {
+    let temp_dir = TempDir::new().unwrap();
+    let parent = temp_dir.path().join("lab.slice");
+    fs::create_dir(&parent).unwrap();
+    fs::write(parent.join("cgroup.controllers"), "cpu io memory pids\n").unwrap();
+    fs::write(parent.join("cgroup.subtree_control"), "memory\n").unwrap();
+    let mut app = app_for(temp_dir.path());
+    app.ui_state.selected_cgroup = Some(parent.display().to_string());
+
+    // An invalid name keeps the prompt open
+    app.request_new_child();
+    type_text(&mut app, "cpu.x");
+    press(&mut app, KeyCode::Enter);
+    assert!(matches!(app.ui_state.dialog, Some(Dialog::NewChild(_))));
+
+    for _ in 0..5 {
+        press(&mut app, KeyCode::Backspace);
+    }
+    type_text(&mut app, "scratch");
+    press(&mut app, KeyCode::Enter);
+    assert!(app.ui_state.dialog.is_none());
+    assert!(parent.join("scratch").is_dir());
+
+    app.open_subtree_control_editor();
+    let Some(Dialog::SubtreeControl(editor)) = &app.ui_state.dialog else {
+        panic!("expected the subtree_control editor");
+    };
+    assert_eq!(
+        editor.controllers,
+        vec![
+            ("cpu".to_string(), false),
+            ("io".to_string(), false),
+            ("memory".to_string(), true),
+            ("pids".to_string(), false),
+        ]
+    );
+
+    press(&mut app, KeyCode::Char(' '));
+    assert_eq!(
+        fs::read_to_string(parent.join("cgroup.subtree_control")).unwrap(),
+        "+cpu"
+    );
+
+    // Regular files do not apply "+cpu", so start over from the original state
+    press(&mut app, KeyCode::Esc);
+    fs::write(parent.join("cgroup.subtree_control"), "memory\n").unwrap();
+    app.open_subtree_control_editor();
+    press(&mut app, KeyCode::Down);
+    press(&mut app, KeyCode::Down);
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(
+        fs::read_to_string(parent.join("cgroup.subtree_control")).unwrap(),
+        "-memory"
+    );
+    assert!(app.ui_state.dialog.is_some());
+}
+
+#[test]
+fn test_move_processes_reports_each_pid() {
+    let temp_dir = TempDir::new().unwrap();
+    let target = temp_dir.path().join("target.scope");
+    fs::create_dir(&target).unwrap();
+    fs::write(target.join("cgroup.procs"), "").unwrap();
+
+    let report = move_processes(&[100, 200], &target);
+    assert_eq!(report.moved_count(), 2);
+    assert_eq!(report.failures().count(), 0);
+    // One write per PID, so the file holds the last one
+    assert_eq!(
+        fs::read_to_string(target.join("cgroup.procs")).unwrap(),
+        "200"
+    );
+
+    // PID 0 would move cgtop itself and is never written
+    let report = move_processes(&[0], &target);
+    assert_eq!(report.moved_count(), 0);
+    assert_eq!(report.moves[0].result, Err(Errno::EINVAL));
+    assert_eq!(
+        fs::read_to_string(target.join("cgroup.procs")).unwrap(),
+        "200"
+    );
+
+    let report = move_processes(&[100, 200], &temp_dir.path().join("gone.scope"));
+    assert_eq!(report.moved_count(), 0);
+    let failures: Vec<_> = report.failures().map(|m| (m.pid, m.result)).collect();
+    assert_eq!(
+        failures,
+        vec![(100, Err(Errno::ENOENT)), (200, Err(Errno::ENOENT))]
+    );
+
+    assert!(explain_move_error(Errno::EINVAL).contains("threaded"));
+    assert!(explain_move_error(Errno::EACCES).contains("delegation"));
+}
+
+#[test]
+fn test_move_selected_processes_through_chooser() {
+    let temp_dir = TempDir::new().unwrap();
+    let source = temp_dir.path().join("source.scope");
+    let target = temp_dir.path().join("target.scope");
+    fs::create_dir(&source).unwrap();
+    fs::create_dir(&target).unwrap();
+    fs::write(source.join("cgroup.procs"), "100\n200\n").unwrap();
+    fs::write(target.join("cgroup.procs"), "").unwrap();
+
+    let mut app = app_for(temp_dir.path());
+    let source_key = source.display().to_string();
+    let target_key = target.display().to_string();
+    app.apply_metrics(Box::new(CGroupMetrics {
+        hierarchies: Vec::new(),
+        processes: HashMap::from([(100, source_key.clone()), (200, source_key.clone())]),
+        resource_usage: HashMap::from([
+            (source_key.clone(), ResourceStats::default()),
+            (target_key.clone(), ResourceStats::default()),
+        ]),
+        timestamp: Instant::now(),
+        collected_at: Utc::now(),
+    }));
+
+    // Without marks the highlighted process is the one acted on
+    app.move_process_cursor(1);
+    assert_eq!(app.target_pids(), vec![200]);
+    app.toggle_process_mark();
+    app.move_process_cursor(-1);
+    app.toggle_process_mark();
+    assert_eq!(app.target_pids(), vec![100, 200]);
+
+    app.request_move_processes();
+    type_text(&mut app, "target");
+    let Some(Dialog::MoveProcesses(chooser)) = &app.ui_state.dialog else {
+        panic!("expected the move chooser");
+    };
+    // The filter keeps the matches and the ancestors leading to them
+    let root_key = temp_dir.path().display().to_string();
+    assert_eq!(chooser.rows(), vec![root_key.as_str(), target_key.as_str()]);
+    assert_eq!(chooser.target(), Some(target_key.as_str()));
+    press(&mut app, KeyCode::Enter);
+
+    assert!(app.ui_state.dialog.is_none());
+    assert!(app.ui_state.marked_pids.is_empty());
+    assert_eq!(
+        fs::read_to_string(target.join("cgroup.procs")).unwrap(),
+        "200"
+    );
+
+    // "Move all" takes the PIDs from cgroup.procs; a vanished target reports each failure
+    app.ui_state.selected_cgroup = Some(source_key);
+    app.request_move_all();
+    let Some(Dialog::MoveProcesses(chooser)) = &mut app.ui_state.dialog else {
+        panic!("expected the move chooser");
+    };
+    assert_eq!(chooser.pids, vec![100, 200]);
+    fs::remove_dir_all(&target).unwrap();
+    type_text(&mut app, "target");
+    press(&mut app, KeyCode::Enter);
+    let Some(Dialog::MoveResult(report)) = &app.ui_state.dialog else {
+        panic!("expected the move result");
+    };
+    assert_eq!(report.failures().count(), 2);
+    press(&mut app, KeyCode::Char('x'));
+    assert!(app.ui_state.dialog.is_none());
+}
+
+#[test]
+fn test_move_chooser_navigates_the_cgroup_tree() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    let mut app = app_with_cgroups(
+        root,
+        &[
+            "app.slice",
+            "app.slice/web.scope",
+            "app.slice/db.scope",
+            "lab.slice",
+        ],
+    );
+    fs::write(root.join("lab.slice/cgroup.procs"), "4242\n").unwrap();
+    app.ui_state.selected_cgroup = Some(root.join("lab.slice").display().to_string());
+    app.request_move_all();
+    let Some(Dialog::MoveProcesses(chooser)) = &mut app.ui_state.dialog else {
+        panic!("expected the move chooser");
+    };
+    let key = |cgroup: &str| root.join(cgroup).display().to_string();
+
+    // Same expansion as the main tree, with the root as a target of its own
+    let root_key = root.display().to_string();
+    assert_eq!(
+        chooser.rows(),
+        vec![
+            root_key.clone(),
+            key("app.slice"),
+            key("app.slice/db.scope"),
+            key("app.slice/web.scope"),
+            key("lab.slice"),
+        ]
+    );
+
+    // Collapse app.slice, expand it again and step into a child
+    chooser.tree.selected = Some("app.slice".to_string());
+    press(&mut app, KeyCode::Left);
+    let Some(Dialog::MoveProcesses(chooser)) = &app.ui_state.dialog else {
+        panic!("expected the move chooser");
+    };
+    assert_eq!(chooser.rows().len(), 3);
+    press(&mut app, KeyCode::Right);
+    press(&mut app, KeyCode::Down);
+    let Some(Dialog::MoveProcesses(chooser)) = &app.ui_state.dialog else {
+        panic!("expected the move chooser");
+    };
+    assert_eq!(chooser.rows().len(), 5);
+    assert_eq!(chooser.target(), Some(key("app.slice/db.scope").as_str()));
+
+    // Filtering shows the match under its ancestors; clearing it restores the tree
+    type_text(&mut app, "web");
+    let Some(Dialog::MoveProcesses(chooser)) = &app.ui_state.dialog else {
+        panic!("expected the move chooser");
+    };
+    assert_eq!(
+        chooser.rows(),
+        vec![root_key, key("app.slice"), key("app.slice/web.scope")]
+    );
+    assert_eq!(chooser.target(), Some(key("app.slice/web.scope").as_str()));
+    for _ in 0..3 {
+        press(&mut app, KeyCode::Backspace);
+    }
+    let Some(Dialog::MoveProcesses(chooser)) = &app.ui_state.dialog else {
+        panic!("expected the move chooser");
+    };
+    assert_eq!(chooser.rows().len(), 5);
+    assert_eq!(chooser.target(), Some(key("app.slice/web.scope").as_str()));
+
+    // The main tree is left as it was
+    assert!(
+        app.ui_state
+            .tree_state
+            .visible_nodes
+            .iter()
+            .all(|key| !key.is_empty())
+    );
+}
+
+/// App whose process list shows `pids`, all in one cgroup below `root`
+fn app_with_processes(root: &Path, pids: &[u32]) -> App {
+    let mut app = app_for(root);
+    let cgroup = root.join("work.scope").display().to_string();
+    app.apply_metrics(Box::new(CGroupMetrics {
+        hierarchies: Vec::new(),
+        processes: pids.iter().map(|&pid| (pid, cgroup.clone())).collect(),
+        resource_usage: HashMap::from([(cgroup, ResourceStats::default())]),
+        timestamp: Instant::now(),
+        collected_at: Utc::now(),
+    }));
+    app
+}
+
+#[test]
+fn test_parse_signal_and_kernel_threads() {
+    assert_eq!(parse_signal("TERM"), Ok(Signal::SIGTERM));
+    assert_eq!(parse_signal("sigusr1"), Ok(Signal::SIGUSR1));
+    assert_eq!(parse_signal(" 9 "), Ok(Signal::SIGKILL));
+    assert!(parse_signal("").is_err());
+    assert!(parse_signal("NOPE").is_err());
+    assert!(parse_signal("0").is_err());
+
+    // kthreadd has PF_KTHREAD (0x200000) in its flags, a shell does not
+    assert!(is_kernel_thread_stat(
+        "2 (kthreadd) S 0 0 0 0 -1 2129984 0 0 0 0 0 0 0 0 20 0 1 0 1"
+    ));
+    assert!(!is_kernel_thread_stat(
+        "4242 (my (odd) sh) S 1 4242 4242 0 -1 4194560 0 0 0 0 0 0 0 0 20 0 1 0 1"
+    ));
+}
+
+#[test]
+fn test_signal_menu_sends_typed_signal() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut child = Command::new("sleep").arg("30").spawn().unwrap();
+    let mut app = app_with_processes(temp_dir.path(), &[child.id()]);
+
+    app.open_signal_menu();
+    // An unknown name keeps the menu open
+    type_text(&mut app, "BOGUS");
+    press(&mut app, KeyCode::Enter);
+    assert!(matches!(app.ui_state.dialog, Some(Dialog::SignalMenu(_))));
+
+    for _ in 0..5 {
+        press(&mut app, KeyCode::Backspace);
+    }
+    type_text(&mut app, "KILL");
+    press(&mut app, KeyCode::Enter);
+    assert!(app.ui_state.dialog.is_none());
+    assert_eq!(child.wait().unwrap().signal(), Some(9));
+
+    // The child is gone now, so the next signal reports ESRCH
+    let report = send_signal(&[child.id()], Signal::SIGTERM);
+    assert_eq!(report.delivered_count(), 0);
+    assert_eq!(report.failures().next().unwrap().result, Err(Errno::ESRCH));
+
+    // PID 0 would signal our own process group
+    let report = send_signal(&[0], Signal::SIGTERM);
+    assert_eq!(report.failures().next().unwrap().result, Err(Errno::EINVAL));
+}
+
+#[test]
+fn test_signalling_init_needs_confirmation() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut app = app_with_processes(temp_dir.path(), &[1]);
+    app.config.dry_run = true;
+
+    app.open_signal_menu();
+    press(&mut app, KeyCode::Enter);
+    let Some(Dialog::ConfirmSignal(confirm)) = &app.ui_state.dialog else {
+        panic!("expected a second confirmation");
+    };
+    assert_eq!(confirm.signal, Signal::SIGTERM);
+    assert_eq!(confirm.warnings.len(), 1);
+
+    press(&mut app, KeyCode::Char('n'));
+    assert!(app.ui_state.dialog.is_none());
+}
+
+/// App whose tree holds `cgroups`, created as directories below `root`
+fn app_with_cgroups(root: &Path, cgroups: &[&str]) -> App {
+    for cgroup in cgroups {
+        fs::create_dir_all(root.join(cgroup)).unwrap();
+    }
+    let mut app = app_for(root);
+    app.apply_metrics(Box::new(CGroupMetrics {
+        hierarchies: Vec::new(),
+        processes: HashMap::new(),
+        resource_usage: cgroups
+            .iter()
+            .map(|cgroup| {
+                (
+                    root.join(cgroup).display().to_string(),
+                    ResourceStats::default(),
+                )
+            })
+            .collect(),
+        timestamp: Instant::now(),
+        collected_at: Utc::now(),
+    }));
+    app
+}
+
+fn mark(app: &mut App, cgroup: &Path) {
+    let path = cgroup.display().to_string();
+    let key = app
+        .ui_state
+        .tree_state
+        .nodes
+        .iter()
+        .find(|(_, node)| node.path == path)
+        .map(|(key, _)| key.clone())
+        .unwrap();
+    app.ui_state.tree_state.selected = Some(key);
+    app.sync_selected_cgroup();
+    app.toggle_cgroup_mark();
+}
+
+#[test]
+fn test_freezing_a_mixed_set_records_each_old_state() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    let mut app = app_with_cgroups(root, &["frozen.scope", "thawed.scope"]);
+    let frozen = root.join("frozen.scope");
+    let thawed = root.join("thawed.scope");
+    fs::write(frozen.join("cgroup.freeze"), "1\n").unwrap();
+    fs::write(frozen.join("cgroup.events"), "populated 1\nfrozen 1\n").unwrap();
+    fs::write(thawed.join("cgroup.freeze"), "0\n").unwrap();
+    fs::write(thawed.join("cgroup.events"), "populated 1\nfrozen 0\n").unwrap();
+    mark(&mut app, &frozen);
+    mark(&mut app, &thawed);
+
+    app.toggle_freeze();
+    press(&mut app, KeyCode::Char('y'));
+    let old_values: Vec<_> = app
+        .audit
+        .entries()
+        .iter()
+        .map(|entry| (entry.path.clone(), entry.old.clone()))
+        .collect();
+    assert_eq!(
+        old_values,
+        [
+            (frozen.clone(), Some("1\n".to_string())),
+            (thawed.clone(), Some("0\n".to_string())),
+        ]
+    );
+
+    // Undoing both leaves the already frozen cgroup frozen
+    app.open_history();
+    press(&mut app, KeyCode::Char('u'));
+    press(&mut app, KeyCode::Char('u'));
+    assert_eq!(
+        fs::read_to_string(frozen.join("cgroup.freeze")).unwrap(),
+        "1"
+    );
+    assert_eq!(
+        fs::read_to_string(thawed.join("cgroup.freeze")).unwrap(),
+        "0"
+    );
+}
+
+#[test]
+fn test_marked_cgroups_are_deleted_after_one_confirmation() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    let mut app = app_with_cgroups(root, &["a.scope", "a.scope/inner", "b.scope", "keep.scope"]);
+
+    mark(&mut app, &root.join("a.scope"));
+    mark(&mut app, &root.join("a.scope/inner"));
+    mark(&mut app, &root.join("b.scope"));
+    assert_eq!(app.target_cgroups().len(), 3);
+
+    app.request_delete_marked();
+    let Some(Dialog::ConfirmDelete(confirm)) = &app.ui_state.dialog else {
+        panic!("expected a delete confirmation");
+    };
+    // inner goes with its marked parent, and the batch needs the tree count typed
+    assert_eq!(confirm.plans.len(), 2);
+    assert_eq!(confirm.cgroup_count(), 3);
+    assert_eq!(confirm.required_input().as_deref(), Some("2"));
+
+    type_text(&mut app, "2");
+    press(&mut app, KeyCode::Enter);
+    assert!(app.ui_state.dialog.is_none());
+    assert!(!root.join("a.scope").exists());
+    assert!(!root.join("b.scope").exists());
+    assert!(root.join("keep.scope").exists());
+    assert!(app.ui_state.tree_state.marked.is_empty());
+}
+
+#[test]
+fn test_freeze_kill_and_limits_apply_to_marked_cgroups() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    let mut app = app_with_cgroups(root, &["a.scope", "b.scope", "c.scope"]);
+    let marked = [root.join("a.scope"), root.join("b.scope")];
+    for cgroup in &marked {
+        fs::write(cgroup.join("cgroup.freeze"), "0\n").unwrap();
+        fs::write(cgroup.join("cgroup.events"), "populated 1\nfrozen 0\n").unwrap();
+        fs::write(cgroup.join("cgroup.procs"), "1\n").unwrap();
+        fs::write(cgroup.join("cgroup.kill"), "").unwrap();
+        fs::write(cgroup.join("pids.max"), "max\n").unwrap();
+        mark(&mut app, cgroup);
+    }
+    fs::write(root.join("c.scope/pids.max"), "max\n").unwrap();
+
+    app.toggle_freeze();
+    assert!(matches!(
+        app.ui_state.dialog,
+        Some(Dialog::ConfirmFreeze(_))
+    ));
+    press(&mut app, KeyCode::Char('y'));
+
+    app.request_kill();
+    let Some(Dialog::ConfirmKill(confirm)) = &app.ui_state.dialog else {
+        panic!("expected a kill confirmation");
+    };
+    assert_eq!(confirm.cgroups.len(), 2);
+    press(&mut app, KeyCode::Char('y'));
+
+    app.open_pids_editor();
+    type_text(&mut app, "64");
+    press(&mut app, KeyCode::Enter);
+    assert!(app.ui_state.dialog.is_none());
+
+    for cgroup in &marked {
+        assert_eq!(
+            fs::read_to_string(cgroup.join("cgroup.freeze")).unwrap(),
+            "1"
+        );
+        assert_eq!(fs::read_to_string(cgroup.join("cgroup.kill")).unwrap(), "1");
+        assert_eq!(fs::read_to_string(cgroup.join("pids.max")).unwrap(), "64");
+    }
+    assert_eq!(
+        fs::read_to_string(root.join("c.scope/pids.max")).unwrap(),
+        "max\n"
+    );
+    assert_eq!(app.audit.entries().len(), 6);
+}
+
+#[test]
+fn test_export_writes_marked_cgroups_to_csv() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path().join("cg");
+    let mut app = app_with_cgroups(&root, &["a.scope", "b.scope", "c.scope"]);
+    app.config.export_dir = temp_dir.path().to_path_buf();
+    app.config.csv_columns = vec![CsvColumn::Path, CsvColumn::PidsCurrent];
+
+    let file = app.export_csv().unwrap();
+    let content = fs::read_to_string(&file).unwrap();
+    assert!(content.starts_with("path,pids.current\n"));
+    assert!(content.contains("/a.scope,0\n"), "{}", content);
+
+    mark(&mut app, &root.join("c.scope"));
+    mark(&mut app, &root.join("a.scope"));
+    let file = app.export_csv().unwrap();
+    assert_eq!(
+        fs::read_to_string(&file).unwrap(),
+        "path,pids.current\n/a.scope,0\n/c.scope,0\n"
+    );
+}
+
+#[test]
+fn test_delete_confirmation_chooses_what_happens_to_members() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path().join("cg");
+    let mut app = app_with_cgroups(&root, &["a.scope", "b.scope"]);
+    for cgroup in ["a.scope", "b.scope"] {
+        fs::write(root.join(cgroup).join("cgroup.procs"), "4242\n").unwrap();
+        fs::write(root.join(cgroup).join("cgroup.kill"), "").unwrap();
+    }
+
+    // Tab once: move the members to the parent
+    app.request_delete("Delete".to_string(), &root.join("a.scope"));
+    let Some(Dialog::ConfirmDelete(confirm)) = &app.ui_state.dialog else {
+        panic!("expected a delete confirmation");
+    };
+    assert_eq!(confirm.members, MemberAction::Leave);
+    press(&mut app, KeyCode::Tab);
+    press(&mut app, KeyCode::Char('y'));
+    assert_eq!(
+        fs::read_to_string(root.join("cgroup.procs")).unwrap(),
+        "4242"
+    );
+    assert_eq!(
+        fs::read_to_string(root.join("a.scope/cgroup.kill")).unwrap(),
+        ""
+    );
+
+    // Tab twice: kill them
+    app.request_delete("Delete".to_string(), &root.join("b.scope"));
+    press(&mut app, KeyCode::Tab);
+    press(&mut app, KeyCode::Tab);
+    press(&mut app, KeyCode::Char('y'));
+    assert_eq!(
+        fs::read_to_string(root.join("b.scope/cgroup.kill")).unwrap(),
+        "1"
+    );
+
+    let files: Vec<_> = app
+        .audit
+        .entries()
+        .iter()
+        .map(|entry| entry.file.as_str())
+        .collect();
+    assert!(files.contains(&"cgroup.procs"), "{:?}", files);
+    assert!(files.contains(&"cgroup.kill"), "{:?}", files);
+}
@@ -0,0 +1,197 @@
+use cgtop::actions::apply::{Change, Spec, apply_change, plan};
+use cgtop::audit::AuditLog;
+use pretty_assertions::assert_eq;
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+const SPEC: &str = r#"
+["batch.slice/*.scope"]
+"memory.max" = "1G"
+"cpu.max" = "1.5"
+"pids.max" = 64
+
+["batch.slice"]
+"cgroup.subtree_control" = ["cpu", "memory"]
+
+["batch.slice/db.scope"]
+"memory.max" = "2G"
+
+["missing.slice/*"]
+"pids.max" = "max"
+"#;
+
+/// batch.slice with two scopes, the way a fresh hierarchy looks
+fn hierarchy(root: &Path) {
+    let batch = root.join("batch.slice");
+    fs::create_dir(&batch).unwrap();
+    fs::write(batch.join("cgroup.subtree_control"), "memory\n").unwrap();
+    for scope in ["db.scope", "web.scope"] {
+        let cgroup = batch.join(scope);
+        fs::create_dir(&cgroup).unwrap();
+        fs::write(cgroup.join("memory.max"), "max\n").unwrap();
+        fs::write(cgroup.join("cpu.max"), "max 100000\n").unwrap();
+        fs::write(cgroup.join("pids.max"), "64\n").unwrap();
+    }
+}
+
+#[test]
+fn test_parse_spec() {
+    let spec = Spec::parse_toml(SPEC).unwrap();
+    assert_eq!(spec.entries.len(), 4);
+    // File order is kept, so later entries can override earlier ones
+    assert_eq!(spec.entries[0].pattern, "batch.slice/*.scope");
+    assert_eq!(
+        spec.entries[0].settings,
+        vec![
+            ("memory.max".to_string(), "1G".to_string()),
+            ("cpu.max".to_string(), "1.5".to_string()),
+            ("pids.max".to_string(), "64".to_string()),
+        ]
+    );
+    assert_eq!(spec.entries[1].controllers, vec!["cpu", "memory"]);
+
+    assert!(Spec::parse_toml("[\"a\"]\n\"memory.current\" = \"1G\"\n").is_err());
+    assert!(Spec::parse_toml("[\"a\"]\n\"memory.max\" = true\n").is_err());
+    assert!(Spec::parse_toml("\"memory.max\" = \"1G\"\n").is_err());
+    assert!(Spec::load(Path::new("limits.yaml")).is_err());
+}
+
+#[test]
+fn test_yaml_spec_matches_toml() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("limits.yaml");
+    fs::write(
+        &path,
+        r#"
+batch.slice/*.scope:
+  memory.max: 1G
+  cpu.max: 1.5
+  pids.max: 64
+batch.slice:
+  cgroup.subtree_control: [cpu, memory]
+batch.slice/db.scope:
+  memory.max: 2G
+missing.slice/*:
+  pids.max: max
+"#,
+    )
+    .unwrap();
+
+    assert_eq!(Spec::load(&path).unwrap(), Spec::parse_toml(SPEC).unwrap());
+    assert!(
+        Spec::parse_yaml(
+            "a:
+  memory.max: [1, 2]
+"
+        )
+        .is_err()
+    );
+    assert!(
+        Spec::parse_yaml(
+            "- not a mapping
+"
+        )
+        .is_err()
+    );
+}
+
+#[test]
+fn test_percentages_refer_to_the_planned_parent_limit() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    hierarchy(root);
+    let batch = root.join("batch.slice");
+    fs::write(batch.join("memory.max"), "1073741824\n").unwrap();
+
+    let spec = Spec::parse_toml(
+        "[\"batch.slice\"]\n\"memory.max\" = \"4G\"\n\n[\"batch.slice/db.scope\"]\n\"memory.max\" = \"50%\"\n",
+    )
+    .unwrap();
+    let plan = plan(&spec, root).unwrap();
+
+    let values: Vec<(&Path, &str)> = plan
+        .changes
+        .iter()
+        .map(|change| (change.cgroup.as_path(), change.value.as_str()))
+        .collect();
+    assert_eq!(
+        values,
+        vec![
+            (batch.as_path(), "4294967296"),
+            (batch.join("db.scope").as_path(), "2147483648"),
+        ]
+    );
+}
+
+#[test]
+fn test_plan_lists_only_drift() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    hierarchy(root);
+    let batch = root.join("batch.slice");
+
+    let plan = plan(&Spec::parse_toml(SPEC).unwrap(), root).unwrap();
+
+    let change = |cgroup: &Path, file: &str, current: &str, value: &str| Change {
+        cgroup: cgroup.to_path_buf(),
+        file: file.to_string(),
+        current: Some(current.to_string()),
+        value: value.to_string(),
+    };
+    assert_eq!(
+        plan.changes,
+        vec![
+            change(&batch, "cgroup.subtree_control", "memory", "+cpu"),
+            change(&batch.join("db.scope"), "memory.max", "max", "2147483648"),
+            change(
+                &batch.join("db.scope"),
+                "cpu.max",
+                "max 100000",
+                "150000 100000"
+            ),
+            change(&batch.join("web.scope"), "memory.max", "max", "1073741824"),
+            change(
+                &batch.join("web.scope"),
+                "cpu.max",
+                "max 100000",
+                "150000 100000"
+            ),
+        ]
+    );
+    // memory in subtree_control and both pids.max
+    assert_eq!(plan.unchanged, 3);
+    assert_eq!(plan.unmatched, vec!["missing.slice/*"]);
+    assert!(plan.has_drift());
+}
+
+#[test]
+fn test_apply_converges_and_is_audited() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path().join("cgroup");
+    fs::create_dir(&root).unwrap();
+    hierarchy(&root);
+    let spec = Spec::parse_toml(
+        "[\"batch.slice/*\"]\n\"memory.max\" = \"512M\"\n\"pids.max\" = \"max\"\n",
+    )
+    .unwrap();
+    let mut audit = AuditLog::in_memory();
+
+    let first = plan(&spec, &root).unwrap();
+    assert_eq!(first.changes.len(), 4);
+    for change in &first.changes {
+        apply_change(change, &mut audit).unwrap();
+    }
+    assert_eq!(audit.entries().len(), 4);
+    assert_eq!(audit.entries()[0].old.as_deref(), Some("max\n"));
+
+    let second = plan(&spec, &root).unwrap();
+    assert!(!second.has_drift(), "{:?}", second.changes);
+    assert_eq!(second.unchanged, 4);
+
+    // The kernel rounds memory limits down to whole pages; that is not drift
+    fs::write(root.join("batch.slice/db.scope/memory.max"), "536866816\n").unwrap();
+    let spec =
+        Spec::parse_toml("[\"batch.slice/db.scope\"]\n\"memory.max\" = 536869000\n").unwrap();
+    assert!(!plan(&spec, &root).unwrap().has_drift());
+}
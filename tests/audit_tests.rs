@@ -0,0 +1,149 @@
+mod common;
+
+use cgtop::app::dialog::Dialog;
+use cgtop::audit::{AuditEntry, AuditLog, RMDIR};
+use common::{app_for, press};
+use crossterm::event::KeyCode;
+use pretty_assertions::assert_eq;
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn entry(file: &str, old: Option<&str>, new: &str) -> AuditEntry {
+    AuditEntry::new(
+        Path::new("/sys/fs/cgroup/lab.slice"),
+        file,
+        old.map(str::to_string),
+        new,
+        Ok(()),
+    )
+}
+
+#[test]
+fn test_undo_values() {
+    assert_eq!(
+        entry("memory.max", Some("max\n"), "536870912").undo_value(),
+        Some("max".to_string())
+    );
+    assert_eq!(
+        entry("cpu.max", Some("max 100000\n"), "150000 100000").undo_value(),
+        Some("max 100000".to_string())
+    );
+    assert_eq!(
+        entry("cgroup.subtree_control", Some("memory\n"), "+cpu").undo_value(),
+        Some("-cpu".to_string())
+    );
+    assert_eq!(
+        entry("cgroup.subtree_control", None, "-memory").undo_value(),
+        Some("+memory".to_string())
+    );
+
+    // io files restore just the device that was written
+    let io_max = "8:0 rbps=max wbps=1048576 riops=max wiops=max\n259:0 rbps=max wbps=max riops=500 wiops=max\n";
+    assert_eq!(
+        entry("io.max", Some(io_max), "259:0 riops=1000").undo_value(),
+        Some("259:0 rbps=max wbps=max riops=500 wiops=max".to_string())
+    );
+    assert_eq!(
+        entry("io.max", Some(""), "8:16 wbps=10485760").undo_value(),
+        Some("8:16 rbps=max wbps=max riops=max wiops=max".to_string())
+    );
+    assert_eq!(
+        entry("io.weight", Some("default 100\n"), "8:0 300").undo_value(),
+        Some("8:0 default".to_string())
+    );
+
+    // Irreversible or failed writes have no undo
+    assert_eq!(entry("cgroup.kill", None, "1").undo_value(), None);
+    assert_eq!(entry(RMDIR, None, "").undo_value(), None);
+    let failed = AuditEntry::new(
+        Path::new("/sys/fs/cgroup/lab.slice"),
+        "memory.max",
+        Some("max".to_string()),
+        "1",
+        Err("Device or resource busy".to_string()),
+    );
+    assert_eq!(failed.undo_value(), None);
+}
+
+#[test]
+fn test_audit_log_appends_json_lines() {
+    let temp_dir = TempDir::new().unwrap();
+    let log_path = temp_dir.path().join("state/cgtop.log");
+    let audit_path = AuditLog::path_for_log(&log_path);
+    assert_eq!(audit_path, temp_dir.path().join("state/cgtop-audit.log"));
+
+    let mut audit = AuditLog::open(audit_path.clone()).unwrap();
+    audit.record(entry("memory.max", Some("max"), "1073741824"));
+    audit.record(entry("pids.max", Some("max"), "64"));
+
+    let content = fs::read_to_string(&audit_path).unwrap();
+    let parsed: Vec<AuditEntry> = content
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(parsed, audit.entries());
+    assert_eq!(parsed[1].file, "pids.max");
+    assert_eq!(parsed[1].result, "ok");
+
+    // Reopening appends instead of truncating
+    let mut reopened = AuditLog::open(audit_path.clone()).unwrap();
+    reopened.record(entry("cpu.weight", Some("100"), "200"));
+    assert_eq!(fs::read_to_string(&audit_path).unwrap().lines().count(), 3);
+}
+
+#[test]
+fn test_history_undo_walks_back_through_changes() {
+    let temp_dir = TempDir::new().unwrap();
+    let cgroup = temp_dir.path().join("lab.slice");
+    fs::create_dir(&cgroup).unwrap();
+    fs::write(cgroup.join("cgroup.controllers"), "cpu memory\n").unwrap();
+    fs::write(cgroup.join("cgroup.subtree_control"), "memory\n").unwrap();
+    fs::write(cgroup.join("cgroup.freeze"), "0\n").unwrap();
+    fs::write(cgroup.join("cgroup.events"), "populated 0\nfrozen 1\n").unwrap();
+
+    let mut app = app_for(temp_dir.path());
+    app.ui_state.selected_cgroup = Some(cgroup.display().to_string());
+
+    // Enable cpu for children, then freeze
+    app.open_subtree_control_editor();
+    press(&mut app, KeyCode::Enter);
+    press(&mut app, KeyCode::Esc);
+    fs::write(cgroup.join("cgroup.events"), "populated 0\nfrozen 0\n").unwrap();
+    app.toggle_freeze();
+    assert_eq!(app.audit.entries().len(), 2);
+    assert_eq!(app.audit.last_undoable(), Some(1));
+
+    app.open_history();
+    assert!(matches!(app.ui_state.dialog, Some(Dialog::History(_))));
+
+    // Newest first: thaw, then disable cpu again
+    press(&mut app, KeyCode::Char('u'));
+    assert_eq!(
+        fs::read_to_string(cgroup.join("cgroup.freeze")).unwrap(),
+        "0"
+    );
+    press(&mut app, KeyCode::Char('u'));
+    assert_eq!(
+        fs::read_to_string(cgroup.join("cgroup.subtree_control")).unwrap(),
+        "-cpu"
+    );
+    assert!(app.audit.is_undone(0) && app.audit.is_undone(1));
+    assert_eq!(app.audit.last_undoable(), None);
+    assert_eq!(app.audit.entries()[3].undo_of, Some(0));
+
+    // Still open for another look; scrolling stops at the oldest entry, so
+    // one press back up always moves
+    for _ in 0..10 {
+        press(&mut app, KeyCode::Down);
+    }
+    let Some(Dialog::History(view)) = &app.ui_state.dialog else {
+        panic!("expected the history view");
+    };
+    assert_eq!(view.selected, 3);
+    press(&mut app, KeyCode::Up);
+    let Some(Dialog::History(view)) = &app.ui_state.dialog else {
+        panic!("expected the history view");
+    };
+    assert_eq!(view.selected, 2);
+}
@@ -0,0 +1,253 @@
+use cgtop::batch::{BatchOptions, OutputFormat, cgroup_table, process_table, run};
+use cgtop::collection::{CGroupCollector, CGroupMetrics, ResourceStats, SCHEMA_VERSION, Snapshot};
+use cgtop::columns::{CgroupColumn, CsvColumn, csv_header, csv_rows};
+use cgtop::config::FileConfig;
+use chrono::Utc;
+use hashbrown::HashMap;
+use pretty_assertions::assert_eq;
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, Instant};
+use tempfile::TempDir;
+
+fn stats(memory: u64, usage_usec: u64, pids: u64) -> ResourceStats {
+    let mut stats = ResourceStats::default();
+    stats.memory.current = memory;
+    stats.memory.peak = memory * 2;
+    stats.cpu.usage_usec = usage_usec;
+    stats.pids.current = pids;
+    stats
+}
+
+fn sample() -> CGroupMetrics {
+    let mut frozen = stats(2048, 500, 1);
+    frozen.frozen = true;
+    frozen.pids.max = Some(10);
+
+    CGroupMetrics {
+        hierarchies: Vec::new(),
+        processes: HashMap::from([(42, "/cg/web.slice".to_string()), (7, "/cg".to_string())]),
+        resource_usage: HashMap::from([
+            ("/cg".to_string(), stats(0, 0, 0)),
+            ("/cg/web.slice".to_string(), stats(1024, 2_500_000, 3)),
+            ("/cg/batch.slice".to_string(), frozen),
+        ]),
+        timestamp: Instant::now(),
+        collected_at: Utc::now(),
+    }
+}
+
+#[test]
+fn test_cgroup_table_sorts_by_column() {
+    let table = cgroup_table(&sample(), Path::new("/cg"), CgroupColumn::Cpu);
+    assert_eq!(
+        table,
+        "\
+CGROUP           MEM    PEAK    CPU  PIDS  STATE
+/web.slice    1.0 KB  2.0 KB   2.5s     3
+/batch.slice  2.0 KB  4.0 KB  0.5ms  1/10  frozen
+/                0 B     0 B  0.0ms     0
+"
+    );
+
+    let by_path = cgroup_table(&sample(), Path::new("/cg"), CgroupColumn::Path);
+    let order: Vec<_> = by_path
+        .lines()
+        .skip(1)
+        .map(|line| line.split_whitespace().next().unwrap())
+        .collect();
+    assert_eq!(order, ["/", "/batch.slice", "/web.slice"]);
+}
+
+#[test]
+fn test_process_table_uses_process_list_columns() {
+    assert_eq!(
+        process_table(&sample(), Path::new("/cg")),
+        "\
+PID  Command  cgroup
+  7  pid-7    /
+ 42  pid-42   /web.slice
+"
+    );
+}
+
+#[test]
+fn test_batch_run_prints_each_iteration() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::create_dir(temp_dir.path().join("job.scope")).unwrap();
+    fs::write(temp_dir.path().join("job.scope/memory.current"), "4096\n").unwrap();
+    let collector = CGroupCollector::new(temp_dir.path().to_path_buf());
+    let options = BatchOptions {
+        format: OutputFormat::Text,
+        iterations: Some(2),
+        interval: Duration::from_millis(10),
+        sort: CgroupColumn::Memory,
+        processes: false,
+        columns: CsvColumn::DEFAULT.to_vec(),
+    };
+
+    let mut out = Vec::new();
+    run(&collector, &options, &mut out).unwrap();
+    let out = String::from_utf8(out).unwrap();
+
+    assert_eq!(out.matches("cgtop - ").count(), 2);
+    assert_eq!(out.matches("/job.scope").count(), 2);
+    assert!(out.contains("/job.scope  4.0 KB"), "{}", out);
+    assert!(!out.contains("Command"));
+}
+
+#[test]
+fn test_snapshot_round_trips_with_schema_version() {
+    let snapshot = Snapshot::new(Path::new("/cg"), sample());
+    let json = serde_json::to_string(&snapshot).unwrap();
+
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(value["schema_version"], SCHEMA_VERSION);
+    assert_eq!(value["root"], "/cg");
+    assert_eq!(
+        value["metrics"]["resource_usage"]["/cg/batch.slice"]["pids"]["max"],
+        10
+    );
+    assert_eq!(value["metrics"]["processes"]["42"], "/cg/web.slice");
+    // Wall-clock time as RFC 3339; the monotonic Instant is not written
+    assert!(value["metrics"]["collected_at"].is_string());
+    assert!(value["metrics"].get("timestamp").is_none());
+    // Keys are written in order, so equal states give equal documents
+    assert!(json.find("\"/cg\"").unwrap() < json.find("\"/cg/batch.slice\"").unwrap());
+    assert!(json.find("\"7\"").unwrap() < json.find("\"42\"").unwrap());
+
+    let parsed: Snapshot = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed.schema_version, SCHEMA_VERSION);
+    assert_eq!(parsed.metrics.collected_at, snapshot.metrics.collected_at);
+    assert_eq!(parsed.metrics.processes, snapshot.metrics.processes);
+    assert!(parsed.metrics.resource_usage["/cg/batch.slice"].frozen);
+}
+
+#[test]
+fn test_ndjson_writes_one_document_per_line() {
+    let temp_dir = TempDir::new().unwrap();
+    let collector = CGroupCollector::new(temp_dir.path().to_path_buf());
+    let options = BatchOptions {
+        format: OutputFormat::Ndjson,
+        iterations: Some(3),
+        interval: Duration::from_millis(10),
+        sort: CgroupColumn::Path,
+        processes: false,
+        columns: CsvColumn::DEFAULT.to_vec(),
+    };
+
+    let mut out = Vec::new();
+    run(&collector, &options, &mut out).unwrap();
+    let out = String::from_utf8(out).unwrap();
+
+    let lines: Vec<_> = out.lines().collect();
+    assert_eq!(lines.len(), 3);
+    for line in lines {
+        let snapshot: Snapshot = serde_json::from_str(line).unwrap();
+        assert_eq!(snapshot.root, temp_dir.path());
+        assert!(
+            snapshot
+                .metrics
+                .resource_usage
+                .contains_key(temp_dir.path().to_str().unwrap())
+        );
+    }
+}
+
+#[test]
+fn test_csv_rows_compute_rates_from_the_previous_sample() {
+    let previous = sample();
+    let mut current = sample();
+    current.timestamp = previous.timestamp + Duration::from_secs(2);
+    let web = current.resource_usage.get_mut("/cg/web.slice").unwrap();
+    web.cpu.usage_usec += 1_000_000;
+    web.io.rbytes += 8192;
+    web.memory.max = Some(1 << 20);
+
+    let columns = [
+        CsvColumn::Path,
+        CsvColumn::CpuRate,
+        CsvColumn::IoReadRate,
+        CsvColumn::MemoryMax,
+        CsvColumn::PidsMax,
+    ];
+    let paths = ["/cg/web.slice", "/cg/batch.slice"];
+    assert_eq!(
+        csv_header(&columns),
+        "path,cpu.rate,io.read_bps,memory.max,pids.max\n"
+    );
+    assert_eq!(
+        csv_rows(
+            &columns,
+            &paths,
+            Path::new("/cg"),
+            &current,
+            Some(&previous)
+        ),
+        "/web.slice,0.500,4096,1048576,max\n/batch.slice,0.000,0,max,10\n"
+    );
+    // Rates need two samples
+    assert_eq!(
+        csv_rows(&columns, &paths[..1], Path::new("/cg"), &current, None),
+        "/web.slice,,,1048576,max\n"
+    );
+}
+
+#[test]
+fn test_csv_quotes_paths_with_separators() {
+    let mut metrics = sample();
+    metrics
+        .resource_usage
+        .insert("/cg/a,\"b\"".to_string(), stats(1, 0, 0));
+    assert_eq!(
+        csv_rows(
+            &[CsvColumn::Path, CsvColumn::MemoryCurrent],
+            &["/cg/a,\"b\""],
+            Path::new("/cg"),
+            &metrics,
+            None
+        ),
+        "\"/a,\"\"b\"\"\",1\n"
+    );
+}
+
+#[test]
+fn test_csv_run_writes_the_header_once() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::create_dir(temp_dir.path().join("job.scope")).unwrap();
+    fs::write(temp_dir.path().join("job.scope/memory.current"), "4096\n").unwrap();
+    let collector = CGroupCollector::new(temp_dir.path().to_path_buf());
+    let options = BatchOptions {
+        format: OutputFormat::Csv,
+        iterations: Some(2),
+        interval: Duration::from_millis(1),
+        sort: CgroupColumn::Path,
+        processes: false,
+        columns: vec![CsvColumn::Path, CsvColumn::MemoryCurrent],
+    };
+
+    let mut out = Vec::new();
+    run(&collector, &options, &mut out).unwrap();
+    let out = String::from_utf8(out).unwrap();
+
+    assert_eq!(out.matches("path,memory.current").count(), 1);
+    assert_eq!(out.matches("/job.scope,4096").count(), 2);
+}
+
+#[test]
+fn test_csv_columns_from_config_file() {
+    let config = FileConfig::parse(
+        "csv_columns = [\"path\", \"cpu.rate\", \"memory.pressure.full_avg10\"]\nexport_dir = \"/tmp\"\n",
+    )
+    .unwrap();
+    assert_eq!(
+        config.csv_columns,
+        Some(vec![
+            CsvColumn::Path,
+            CsvColumn::CpuRate,
+            CsvColumn::MemoryPressureFull
+        ])
+    );
+    assert_eq!(config.export_dir, Some("/tmp".into()));
+    assert!(FileConfig::parse("csv_columns = [\"cpu\"]").is_err());
+}
@@ -1,8 +1,6 @@
 mod common;
 
-use cgtop::collection::{
-    CGroupCollector, CGroupMetrics, CpuStats, IoStats, MemoryStats, PidStats, ResourceStats,
-};
+use cgtop::collection::{CGroupCollector, ResourceStats};
 use pretty_assertions::assert_eq;
 use std::fs;
 use std::path::PathBuf;
@@ -1,5 +1,31 @@
+// Not every test binary uses every helper
+#![allow(dead_code)]
+
+use cgtop::app::App;
 use cgtop::collection::{CpuStats, IoStats, MemoryStats, PidStats, ResourceStats};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use hashbrown::HashMap;
+use std::path::Path;
+
+/// Send `code` to the open dialog
+pub fn press(app: &mut App, code: KeyCode) {
+    app.handle_dialog_key(KeyEvent::new(code, KeyModifiers::NONE));
+}
+
+/// Type `text` into the open dialog one key at a time
+pub fn type_text(app: &mut App, text: &str) {
+    for c in text.chars() {
+        press(app, KeyCode::Char(c));
+    }
+}
+
+/// App rooted at a temporary directory that the safety policy accepts
+pub fn app_for(root: &Path) -> App {
+    let mut app = App::new_with_path(root.to_path_buf());
+    app.config.safety.require_cgroup2 = false;
+    app.config.safety.self_cgroup = None;
+    app
+}
 
 /// Create mock resource stats for testing
 pub fn create_mock_resource_stats() -> ResourceStats {
@@ -25,6 +51,8 @@ pub fn create_mock_resource_stats() -> ResourceStats {
             current: 1,
             max: Some(100),
         },
+        cgroup_procs: Vec::new(),
+        frozen: false,
     }
 }
 
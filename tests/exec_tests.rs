@@ -0,0 +1,72 @@
+use cgtop::actions::exec::{command_in_cgroup, resolve_cgroup};
+use cgtop::app::App;
+use pretty_assertions::assert_eq;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+#[test]
+fn test_resolve_cgroup() {
+    let root = Path::new("/sys/fs/cgroup");
+    assert_eq!(
+        resolve_cgroup(root, Path::new("/sys/fs/cgroup/lab.slice")),
+        PathBuf::from("/sys/fs/cgroup/lab.slice")
+    );
+    assert_eq!(
+        resolve_cgroup(root, Path::new("lab.slice/job.scope")),
+        PathBuf::from("/sys/fs/cgroup/lab.slice/job.scope")
+    );
+    assert_eq!(
+        resolve_cgroup(root, Path::new("/lab.slice")),
+        PathBuf::from("/sys/fs/cgroup/lab.slice")
+    );
+}
+
+#[test]
+fn test_command_joins_cgroup_before_exec() {
+    let temp_dir = TempDir::new().unwrap();
+    let cgroup = temp_dir.path().join("job.scope");
+    fs::create_dir(&cgroup).unwrap();
+    fs::write(cgroup.join("cgroup.procs"), "").unwrap();
+
+    let output = command_in_cgroup(&cgroup, "sh", &["-c", "echo ran"])
+        .unwrap()
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "ran\n");
+    // The child wrote "0", which the kernel reads as "the writing process"
+    assert_eq!(
+        fs::read_to_string(cgroup.join("cgroup.procs")).unwrap(),
+        "0"
+    );
+}
+
+#[test]
+fn test_command_fails_without_cgroup() {
+    let temp_dir = TempDir::new().unwrap();
+    let missing = temp_dir.path().join("gone.scope");
+
+    let error = command_in_cgroup(&missing, "true", &[] as &[&str]).unwrap_err();
+    assert!(format!("{:#}", error).contains("gone.scope/cgroup.procs"));
+
+    // Never falls back to running the program outside the cgroup
+    let dir_as_procs = temp_dir.path().join("odd.scope");
+    fs::create_dir_all(dir_as_procs.join("cgroup.procs")).unwrap();
+    assert!(command_in_cgroup(&dir_as_procs, "true", &[] as &[&str]).is_err());
+}
+
+#[test]
+fn test_shell_request_targets_selected_cgroup() {
+    let mut app = App::new_with_path(PathBuf::from("/sys/fs/cgroup"));
+    app.request_shell();
+    assert_eq!(app.ui_state.pending_shell, None);
+
+    app.ui_state.selected_cgroup = Some("/sys/fs/cgroup/lab.slice".to_string());
+    app.request_shell();
+    assert_eq!(
+        app.ui_state.pending_shell,
+        Some(PathBuf::from("/sys/fs/cgroup/lab.slice"))
+    );
+}
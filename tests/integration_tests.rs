@@ -4,11 +4,10 @@ use cgtop::{
     app::{App, UiState},
     collection::CGroupMetrics,
     events::CGroupEvent,
-    threads::EventThreads,
 };
-use crossbeam::channel::{self, Receiver};
+use chrono::Utc;
+use crossbeam::channel;
 use pretty_assertions::assert_eq;
-use std::time::Duration;
 
 fn create_mock_metrics() -> Box<CGroupMetrics> {
     use cgtop::collection::{CpuStats, IoStats, MemoryStats, PidStats, ResourceStats};
@@ -19,7 +18,7 @@ fn create_mock_metrics() -> Box<CGroupMetrics> {
     let processes = HashMap::new();
 
     // Create a simple test hierarchy
-    let paths = vec![
+    let paths = [
         "/sys/fs/cgroup",
         "/sys/fs/cgroup/test.slice",
         "/sys/fs/cgroup/test.slice/test.service",
@@ -45,6 +44,8 @@ fn create_mock_metrics() -> Box<CGroupMetrics> {
                 current: i as u64 + 1,
                 max: Some(100),
             },
+            cgroup_procs: Vec::new(),
+            frozen: false,
         };
 
         resource_usage.insert(path.to_string(), stats);
@@ -55,6 +56,7 @@ fn create_mock_metrics() -> Box<CGroupMetrics> {
         processes,
         resource_usage,
         timestamp: Instant::now(),
+        collected_at: Utc::now(),
     })
 }
 
@@ -215,7 +217,7 @@ fn test_cgroup_path_to_selection_sync() {
 
         // The selected cgroup should exist in the metrics
         let selected_path = app.ui_state.selected_cgroup.as_ref().unwrap();
-        if let Some(ref metrics) = mock_metrics.resource_usage.get(selected_path) {
+        if let Some(metrics) = mock_metrics.resource_usage.get(selected_path) {
             assert!(metrics.memory.current > 0);
         }
     }
@@ -246,7 +248,7 @@ fn test_app_with_multiple_updates() {
     let mut app = App::new();
 
     // First update
-    let mut mock_metrics1 = create_mock_metrics();
+    let mock_metrics1 = create_mock_metrics();
     app.ui_state
         .tree_state
         .build_from_paths(&mock_metrics1.resource_usage);
@@ -303,3 +305,131 @@ fn test_app_with_multiple_updates() {
             .contains_key("/sys/fs/cgroup/new.slice")
     );
 }
+
+#[test]
+fn test_sampling_controls_reach_collection_thread() {
+    use cgtop::events::CollectionCommand;
+    use std::time::Duration;
+
+    let mut app = App::new();
+    let (control_tx, control_rx) = channel::unbounded::<CollectionCommand>();
+    app.set_control_sender(control_tx);
+
+    assert_eq!(app.config.update_interval_ms, 1000);
+
+    app.adjust_update_interval(true);
+    assert_eq!(app.config.update_interval_ms, 500);
+    assert_eq!(
+        control_rx.try_recv().unwrap(),
+        CollectionCommand::SetInterval(Duration::from_millis(500))
+    );
+
+    app.adjust_update_interval(false);
+    app.adjust_update_interval(false);
+    assert_eq!(app.config.update_interval_ms, 2000);
+
+    app.toggle_pause();
+    app.request_refresh();
+    app.toggle_pause();
+    let commands: Vec<_> = control_rx.try_iter().collect();
+    assert_eq!(
+        commands[2..],
+        [
+            CollectionCommand::Pause,
+            CollectionCommand::Refresh,
+            CollectionCommand::Resume
+        ]
+    );
+    assert!(!app.ui_state.sampling_paused);
+}
+
+#[test]
+fn test_mouse_hit_areas() {
+    use cgtop::app::{ProcessSort, ProcessSortColumn};
+    use cgtop::widgets::{ProcessListWidget, TabBarWidget};
+    use ratatui::layout::Rect;
+
+    // " Resources " + "│" + " Processes "
+    let tabs = TabBarWidget::title_areas(Rect::new(10, 4, 40, 1));
+    assert_eq!(tabs, vec![Rect::new(10, 4, 11, 1), Rect::new(22, 4, 11, 1)]);
+
+    let headers = ProcessListWidget::header_areas(Rect::new(0, 0, 60, 10));
+    assert_eq!(headers.len(), 3);
+    assert_eq!(headers[0], (ProcessSortColumn::Pid, Rect::new(1, 1, 8, 1)));
+    assert_eq!(headers[1].1.x, 10);
+
+    let mut sort = ProcessSort::default();
+    sort.toggle(ProcessSortColumn::Pid);
+    assert!(sort.descending);
+    sort.toggle(ProcessSortColumn::Cgroup);
+    assert_eq!(sort.column, ProcessSortColumn::Cgroup);
+    assert!(!sort.descending);
+}
+
+#[test]
+fn test_drain_coalesced_keeps_latest_update() {
+    use cgtop::events::drain_coalesced;
+
+    let (sender, receiver) = channel::unbounded::<CGroupEvent>();
+
+    let mut newest = create_mock_metrics();
+    newest.resource_usage.insert(
+        "/sys/fs/cgroup/newest.slice".to_string(),
+        common::create_mock_resource_stats(),
+    );
+
+    sender.send(CGroupEvent::Resize).unwrap();
+    sender
+        .send(CGroupEvent::Update(create_mock_metrics()))
+        .unwrap();
+    sender.send(CGroupEvent::Update(newest)).unwrap();
+
+    let events = drain_coalesced(CGroupEvent::Update(create_mock_metrics()), &receiver);
+
+    assert_eq!(events.len(), 2);
+    assert!(matches!(events[0], CGroupEvent::Resize));
+    match &events[1] {
+        CGroupEvent::Update(metrics) => {
+            assert!(
+                metrics
+                    .resource_usage
+                    .contains_key("/sys/fs/cgroup/newest.slice")
+            );
+        }
+        other => panic!("Unexpected event {:?}", other),
+    }
+    assert!(receiver.is_empty());
+}
+
+#[test]
+fn test_event_threads_shutdown_joins_workers() {
+    use cgtop::threads::EventThreads;
+    use std::time::{Duration, Instant};
+
+    let mut event_threads = EventThreads::new();
+    let event_rx = event_threads
+        .start(
+            std::path::PathBuf::from("/nonexistent/cgroup"),
+            Duration::from_secs(60),
+        )
+        .unwrap();
+
+    // The first sample is taken right away (falling back to mock data)
+    let first = event_rx.recv_timeout(Duration::from_secs(10)).unwrap();
+    assert!(matches!(first, CGroupEvent::Update(_)));
+
+    // Pause so the collection thread blocks on its control channel
+    event_threads
+        .control_sender()
+        .unwrap()
+        .send(cgtop::events::CollectionCommand::Pause)
+        .unwrap();
+
+    let started = Instant::now();
+    event_threads.shutdown();
+    assert!(event_threads.is_stopping());
+    assert!(started.elapsed() < Duration::from_secs(5));
+
+    // The input thread announces that it is going away
+    assert!(event_rx.try_iter().any(|event| event.is_terminate()));
+}
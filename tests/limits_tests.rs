@@ -0,0 +1,404 @@
+mod common;
+
+use cgtop::actions::limits::{
+    BlockDevice, CpuKnob, LimitValue, effective_limit, list_block_devices, parse_cpu_max,
+    parse_cpu_weight, parse_duration_usec, parse_io_max, parse_io_weight, parse_memory_value,
+    parse_pids_max, validate_against_parent,
+};
+use cgtop::app::App;
+use cgtop::app::dialog::{Dialog, LimitEditor};
+use common::{press, type_text};
+use crossterm::event::KeyCode;
+use pretty_assertions::assert_eq;
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+const MIB: u64 = 1 << 20;
+
+/// A fake cgroup with the memory controller files present
+fn memory_cgroup(path: &Path, max: &str, current: u64) {
+    fs::create_dir_all(path).unwrap();
+    for file in ["memory.high", "memory.swap.max"] {
+        fs::write(path.join(file), "max\n").unwrap();
+    }
+    for file in ["memory.low", "memory.min"] {
+        fs::write(path.join(file), "0\n").unwrap();
+    }
+    fs::write(path.join("memory.max"), max).unwrap();
+    fs::write(path.join("memory.current"), current.to_string()).unwrap();
+}
+
+#[test]
+fn test_parse_memory_value_units() {
+    assert_eq!(parse_memory_value("max", None).unwrap(), LimitValue::Max);
+    assert_eq!(parse_memory_value(" MAX ", None).unwrap(), LimitValue::Max);
+    assert_eq!(
+        parse_memory_value("4096", None).unwrap(),
+        LimitValue::Value(4096)
+    );
+    assert_eq!(
+        parse_memory_value("512M", None).unwrap(),
+        LimitValue::Value(512 * MIB)
+    );
+    assert_eq!(
+        parse_memory_value("1.5g", None).unwrap(),
+        LimitValue::Value(1536 * MIB)
+    );
+    assert_eq!(
+        parse_memory_value("2GiB", None).unwrap(),
+        LimitValue::Value(2048 * MIB)
+    );
+    assert_eq!(
+        parse_memory_value("64 KB", None).unwrap(),
+        LimitValue::Value(64 * 1024)
+    );
+    assert_eq!(
+        parse_memory_value("80%", Some(1000 * MIB)).unwrap(),
+        LimitValue::Value(800 * MIB)
+    );
+
+    assert!(parse_memory_value("", None).is_err());
+    assert!(parse_memory_value("lots", None).is_err());
+    assert!(parse_memory_value("-1G", None).is_err());
+    assert!(parse_memory_value("80%", None).is_err());
+    assert!(parse_memory_value("120%", Some(MIB)).is_err());
+}
+
+#[test]
+fn test_parse_memory_value_rejects_malformed_sizes() {
+    assert_eq!(
+        parse_memory_value("12.5%", Some(1000)).unwrap(),
+        LimitValue::Value(125)
+    );
+    assert_eq!(
+        parse_memory_value("100B", None).unwrap(),
+        LimitValue::Value(100)
+    );
+    assert_eq!(
+        parse_memory_value("16T", None).unwrap(),
+        LimitValue::Value(16 << 40)
+    );
+
+    for input in [
+        "512BBB", "512MBB", "1GG", "2IB", "1.5.2G", ".", "G", "1e3", "0x10", "1 G B", "nan", "inf",
+    ] {
+        assert!(parse_memory_value(input, None).is_err(), "{}", input);
+    }
+    // Too large for a u64 instead of saturating
+    assert!(parse_memory_value("99999999999T", None).is_err());
+    assert!(parse_memory_value("18446744073709551616", None).is_err());
+    assert!(parse_memory_value("100.1%", Some(MIB)).is_err());
+}
+
+#[test]
+fn test_effective_limit_is_tightest_ancestor() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    let parent = root.join("parent.slice");
+    let child = parent.join("child.scope");
+    memory_cgroup(&parent, "1073741824\n", 0);
+    memory_cgroup(&child, "max\n", 0);
+
+    assert_eq!(effective_limit(&child, root, "memory.max"), Some(1 << 30));
+    assert_eq!(effective_limit(root, root, "memory.max"), None);
+
+    assert!(validate_against_parent(LimitValue::Value(512 * MIB), Some(1 << 30)).is_ok());
+    assert!(validate_against_parent(LimitValue::Max, Some(1 << 30)).is_ok());
+    assert!(validate_against_parent(LimitValue::Value(2 << 30), Some(1 << 30)).is_err());
+}
+
+#[test]
+fn test_memory_editor_writes_selected_file() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    let parent = root.join("parent.slice");
+    let child = parent.join("child.scope");
+    memory_cgroup(&parent, "1073741824\n", 0);
+    memory_cgroup(&child, "max\n", 100 * MIB);
+
+    let mut app = App::new_with_path(root.to_path_buf());
+    app.ui_state.selected_cgroup = Some(child.display().to_string());
+    app.open_memory_editor();
+    let Some(Dialog::EditLimits(editor)) = &app.ui_state.dialog else {
+        panic!("expected the memory editor");
+    };
+    assert_eq!(editor.selected_file(), "memory.max");
+    assert!(editor.details.iter().any(|line| line.contains("1.0 GB")));
+
+    // Above the parent's limit: rejected, dialog stays open
+    type_text(&mut app, "2G");
+    press(&mut app, KeyCode::Enter);
+    assert!(app.ui_state.dialog.is_some());
+    assert_eq!(
+        fs::read_to_string(child.join("memory.max")).unwrap(),
+        "max\n"
+    );
+
+    // memory.high as a share of the parent's limit
+    press(&mut app, KeyCode::Down);
+    type_text(&mut app, "50%");
+    press(&mut app, KeyCode::Enter);
+    assert!(app.ui_state.dialog.is_none());
+    assert_eq!(
+        fs::read_to_string(child.join("memory.high")).unwrap(),
+        (512 * MIB).to_string()
+    );
+}
+
+#[test]
+fn test_memory_editor_needs_memory_controller() {
+    let temp_dir = TempDir::new().unwrap();
+    let cgroup = temp_dir.path().join("plain.scope");
+    fs::create_dir(&cgroup).unwrap();
+
+    let mut app = App::new_with_path(temp_dir.path().to_path_buf());
+    app.ui_state.selected_cgroup = Some(cgroup.display().to_string());
+    app.open_memory_editor();
+
+    assert!(app.ui_state.dialog.is_none());
+    assert!(app.notifications.has_notifications());
+}
+
+#[test]
+fn test_parse_cpu_max_forms() {
+    assert_eq!(
+        parse_cpu_max("2", 100_000).unwrap(),
+        (Some(200_000), 100_000)
+    );
+    assert_eq!(
+        parse_cpu_max("1.5 cores", 100_000).unwrap(),
+        (Some(150_000), 100_000)
+    );
+    assert_eq!(
+        parse_cpu_max("0.5", 50_000).unwrap(),
+        (Some(25_000), 50_000)
+    );
+    assert_eq!(
+        parse_cpu_max("200000 100000", 50_000).unwrap(),
+        (Some(200_000), 100_000)
+    );
+    assert_eq!(
+        parse_cpu_max("50000/20000", 100_000).unwrap(),
+        (Some(50_000), 20_000)
+    );
+    assert_eq!(parse_cpu_max("max", 100_000).unwrap(), (None, 100_000));
+    assert_eq!(parse_cpu_max("max 20000", 100_000).unwrap(), (None, 20_000));
+
+    assert!(parse_cpu_max("", 100_000).is_err());
+    assert!(parse_cpu_max("0", 100_000).is_err());
+    assert!(parse_cpu_max("0.001", 100_000).is_err());
+    assert!(parse_cpu_max("100000 10", 100_000).is_err());
+    assert!(parse_cpu_max("fast", 100_000).is_err());
+
+    assert_eq!(CpuKnob::Max.parse("2", 100_000).unwrap(), "200000 100000");
+    assert_eq!(CpuKnob::Max.parse("max", 100_000).unwrap(), "max 100000");
+}
+
+#[test]
+fn test_parse_cpu_weight_and_burst() {
+    assert_eq!(parse_cpu_weight("100").unwrap(), 100);
+    assert!(parse_cpu_weight("0").is_err());
+    assert!(parse_cpu_weight("10001").is_err());
+
+    assert_eq!(parse_duration_usec("500").unwrap(), 500);
+    assert_eq!(parse_duration_usec("20ms").unwrap(), 20_000);
+    assert_eq!(parse_duration_usec("0.5s").unwrap(), 500_000);
+    assert_eq!(parse_duration_usec("30us").unwrap(), 30);
+    assert!(parse_duration_usec("soon").is_err());
+}
+
+#[test]
+fn test_cpu_editor_writes_quota_and_warns_without_controller() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    let cgroup = root.join("worker.service");
+    fs::create_dir(&cgroup).unwrap();
+    fs::write(cgroup.join("cpu.max"), "max 100000\n").unwrap();
+    fs::write(cgroup.join("cpu.weight"), "100\n").unwrap();
+    fs::write(cgroup.join("cpu.max.burst"), "0\n").unwrap();
+
+    let mut app = App::new_with_path(root.to_path_buf());
+    app.ui_state.selected_cgroup = Some(cgroup.display().to_string());
+    app.open_cpu_editor();
+    assert!(matches!(app.ui_state.dialog, Some(Dialog::EditLimits(_))));
+    // The root's cgroup.subtree_control does not list cpu
+    assert!(
+        app.notifications
+            .get_latest()
+            .is_some_and(|n| n.message.contains("cgroup.subtree_control"))
+    );
+
+    type_text(&mut app, "2");
+    press(&mut app, KeyCode::Enter);
+    assert!(app.ui_state.dialog.is_none());
+    assert_eq!(
+        fs::read_to_string(cgroup.join("cpu.max")).unwrap(),
+        "200000 100000"
+    );
+
+    fs::write(root.join("cgroup.subtree_control"), "cpu memory\n").unwrap();
+    app.open_cpu_editor();
+    press(&mut app, KeyCode::Down);
+    type_text(&mut app, "0");
+    press(&mut app, KeyCode::Enter);
+    assert!(app.ui_state.dialog.is_some());
+    assert_eq!(
+        fs::read_to_string(cgroup.join("cpu.weight")).unwrap(),
+        "100\n"
+    );
+}
+
+#[test]
+fn test_cpu_editor_shows_burst_only_when_the_kernel_has_it() {
+    let temp_dir = TempDir::new().unwrap();
+    let cgroup = temp_dir.path();
+    fs::write(cgroup.join("cpu.max"), "max 100000\n").unwrap();
+    fs::write(cgroup.join("cpu.weight"), "100\n").unwrap();
+    let current = |editor: &LimitEditor| -> Vec<String> {
+        editor
+            .fields
+            .iter()
+            .map(|field| field.current.clone())
+            .collect()
+    };
+
+    // No cpu.max.burst before Linux 5.14
+    let editor = LimitEditor::cpu(cgroup).unwrap();
+    assert_eq!(current(&editor)[1..], ["100", "n/a"]);
+
+    fs::write(cgroup.join("cpu.max.burst"), "5000\n").unwrap();
+    let editor = LimitEditor::cpu(cgroup).unwrap();
+    assert_eq!(current(&editor)[1..], ["100", "5000us"]);
+}
+
+#[test]
+fn test_parse_pids_and_io_values() {
+    assert_eq!(parse_pids_max("max").unwrap(), LimitValue::Max);
+    assert_eq!(parse_pids_max("64").unwrap(), LimitValue::Value(64));
+    assert!(parse_pids_max("0").is_err());
+    assert!(parse_pids_max("many").is_err());
+
+    assert_eq!(
+        parse_io_max("wbps=10M riops=500", "8:0").unwrap(),
+        "8:0 wbps=10485760 riops=500"
+    );
+    assert_eq!(
+        parse_io_max("max", "8:0").unwrap(),
+        "8:0 rbps=max wbps=max riops=max wiops=max"
+    );
+    assert!(parse_io_max("", "8:0").is_err());
+    assert!(parse_io_max("wbps", "8:0").is_err());
+    assert!(parse_io_max("speed=1", "8:0").is_err());
+    let error = parse_io_max("wbps=80%", "8:0").unwrap_err().to_string();
+    assert_eq!(
+        error,
+        "wbps takes a rate such as 10M or max, not a percentage"
+    );
+
+    assert_eq!(parse_io_weight("200", "8:0").unwrap(), "8:0 200");
+    assert_eq!(parse_io_weight("default 50", "8:0").unwrap(), "default 50");
+    assert!(parse_io_weight("0", "8:0").is_err());
+}
+
+#[test]
+fn test_list_block_devices() {
+    let temp_dir = TempDir::new().unwrap();
+    for (name, dev) in [("vdb", "253:16"), ("sda", "8:0")] {
+        fs::create_dir(temp_dir.path().join(name)).unwrap();
+        fs::write(temp_dir.path().join(name).join("dev"), format!("{}\n", dev)).unwrap();
+    }
+    // Entries without a dev file are not devices
+    fs::create_dir(temp_dir.path().join("stray")).unwrap();
+
+    let devices = list_block_devices(temp_dir.path());
+    assert_eq!(
+        devices,
+        vec![
+            BlockDevice {
+                name: "sda".to_string(),
+                dev: "8:0".to_string()
+            },
+            BlockDevice {
+                name: "vdb".to_string(),
+                dev: "253:16".to_string()
+            },
+        ]
+    );
+    assert!(list_block_devices(&temp_dir.path().join("missing")).is_empty());
+}
+
+#[test]
+fn test_pids_editor_respects_parent_limit() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    let parent = root.join("parent.slice");
+    let child = parent.join("forky.service");
+    fs::create_dir_all(&child).unwrap();
+    fs::write(parent.join("pids.max"), "100\n").unwrap();
+    fs::write(child.join("pids.max"), "max\n").unwrap();
+
+    let mut app = App::new_with_path(root.to_path_buf());
+    app.ui_state.selected_cgroup = Some(child.display().to_string());
+    app.open_pids_editor();
+    assert!(app.ui_state.dialog.is_some());
+
+    type_text(&mut app, "500");
+    press(&mut app, KeyCode::Enter);
+    assert!(app.ui_state.dialog.is_some());
+
+    for _ in 0..3 {
+        press(&mut app, KeyCode::Backspace);
+    }
+    type_text(&mut app, "50");
+    press(&mut app, KeyCode::Enter);
+    assert!(app.ui_state.dialog.is_none());
+    assert_eq!(fs::read_to_string(child.join("pids.max")).unwrap(), "50");
+}
+
+#[test]
+fn test_io_editor_picks_device() {
+    let temp_dir = TempDir::new().unwrap();
+    let cgroup = temp_dir.path().join("hog.service");
+    fs::create_dir(&cgroup).unwrap();
+    fs::write(
+        cgroup.join("io.max"),
+        "253:16 rbps=max wbps=1048576 riops=max wiops=max\n",
+    )
+    .unwrap();
+    fs::write(cgroup.join("io.weight"), "default 100\n").unwrap();
+
+    let devices = vec![
+        BlockDevice {
+            name: "sda".to_string(),
+            dev: "8:0".to_string(),
+        },
+        BlockDevice {
+            name: "vdb".to_string(),
+            dev: "253:16".to_string(),
+        },
+    ];
+    let mut app = App::new_with_path(temp_dir.path().to_path_buf());
+    let editor = LimitEditor::io(&cgroup, devices).unwrap();
+    assert_eq!(editor.fields[0].current, "no limit");
+    assert_eq!(editor.fields[1].current, "default 100");
+    app.ui_state.dialog = Some(Dialog::EditLimits(editor));
+
+    press(&mut app, KeyCode::Right);
+    let Some(Dialog::EditLimits(editor)) = &app.ui_state.dialog else {
+        panic!("expected the io editor");
+    };
+    assert_eq!(editor.selected_device().unwrap().dev, "253:16");
+    assert_eq!(
+        editor.fields[0].current,
+        "rbps=max wbps=1048576 riops=max wiops=max"
+    );
+
+    type_text(&mut app, "wbps=10M");
+    press(&mut app, KeyCode::Enter);
+    assert!(app.ui_state.dialog.is_none());
+    assert_eq!(
+        fs::read_to_string(cgroup.join("io.max")).unwrap(),
+        "253:16 wbps=10485760"
+    );
+}
@@ -0,0 +1,262 @@
+use cgtop::collection::{CGroupCollector, CGroupMetrics, ResourceStats};
+use cgtop::prometheus::{
+    ExporterOptions, MetricsCache, TEXTFILE_NAME, container_id, render, run_textfile, serve,
+    systemd_unit, write_textfile,
+};
+use chrono::Utc;
+use hashbrown::HashMap;
+use pretty_assertions::assert_eq;
+use std::fs;
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, Instant};
+use tempfile::TempDir;
+
+const CONTAINER: &str = "0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef";
+
+fn sample() -> CGroupMetrics {
+    let mut nginx = ResourceStats::default();
+    nginx.memory.current = 4096;
+    nginx.memory.max = Some(1 << 20);
+    nginx.cpu.usage_usec = 2_500_000;
+
+    CGroupMetrics {
+        hierarchies: Vec::new(),
+        processes: HashMap::new(),
+        resource_usage: HashMap::from([
+            ("/cg".to_string(), ResourceStats::default()),
+            ("/cg/system.slice".to_string(), ResourceStats::default()),
+            ("/cg/system.slice/nginx.service".to_string(), nginx),
+            (
+                format!("/cg/system.slice/docker-{}.scope", CONTAINER),
+                ResourceStats::default(),
+            ),
+        ]),
+        timestamp: Instant::now(),
+        collected_at: Utc::now(),
+    }
+}
+
+#[test]
+fn test_render_labels_series_by_cgroup_unit_and_container() {
+    let out = render(&sample(), Path::new("/cg"), &ExporterOptions::default());
+
+    assert!(out.contains("# TYPE cgtop_cpu_usage_seconds_total counter\n"));
+    assert!(out.contains(
+        "cgtop_memory_current_bytes{cgroup=\"/system.slice/nginx.service\",unit=\"nginx.service\"} 4096\n"
+    ));
+    assert!(out.contains(
+        "cgtop_cpu_usage_seconds_total{cgroup=\"/system.slice/nginx.service\",unit=\"nginx.service\"} 2.5\n"
+    ));
+    assert!(out.contains(&format!(
+        "cgtop_pids_current{{cgroup=\"/system.slice/docker-{0}.scope\",unit=\"docker-{0}.scope\",container_id=\"{0}\"}} 0\n",
+        CONTAINER
+    )));
+    assert!(out.contains("cgtop_memory_current_bytes{cgroup=\"/\"} 0\n"));
+    // Unset limits are left out rather than exported as a number
+    assert_eq!(out.matches("cgtop_memory_max_bytes{").count(), 1);
+}
+
+#[test]
+fn test_render_respects_max_depth() {
+    let options = ExporterOptions { max_depth: Some(1) };
+    let out = render(&sample(), Path::new("/cg"), &options);
+
+    let cgroups: Vec<_> = out
+        .lines()
+        .filter(|line| line.starts_with("cgtop_frozen{"))
+        .collect();
+    assert_eq!(
+        cgroups,
+        [
+            "cgtop_frozen{cgroup=\"/\"} 0",
+            "cgtop_frozen{cgroup=\"/system.slice\",unit=\"system.slice\"} 0",
+        ]
+    );
+}
+
+#[test]
+fn test_unit_and_container_id_from_path() {
+    assert_eq!(
+        systemd_unit("/system.slice/nginx.service/worker"),
+        Some("nginx.service")
+    );
+    assert_eq!(systemd_unit("/user.slice"), Some("user.slice"));
+    assert_eq!(systemd_unit("/lab/job"), None);
+
+    for path in [
+        format!("/system.slice/docker-{}.scope", CONTAINER),
+        format!(
+            "/kubepods.slice/pod1.slice/cri-containerd-{}.scope",
+            CONTAINER
+        ),
+        format!("/docker/{}", CONTAINER),
+    ] {
+        assert_eq!(container_id(&path), Some(CONTAINER), "{}", path);
+    }
+    assert_eq!(container_id("/system.slice/docker.service"), None);
+}
+
+fn get(address: &str, target: &str) -> String {
+    let mut stream = TcpStream::connect(address).unwrap();
+    write!(
+        stream,
+        "GET {} HTTP/1.1\r\nHost: {}\r\n\r\n",
+        target, address
+    )
+    .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    response
+}
+
+#[test]
+fn test_serve_answers_scrapes_from_the_cache() {
+    let temp_dir = TempDir::new().unwrap();
+    let cgroup = temp_dir.path().join("job.scope");
+    fs::create_dir(&cgroup).unwrap();
+    fs::write(cgroup.join("memory.current"), "4096\n").unwrap();
+
+    let cache = MetricsCache::new(
+        CGroupCollector::new(temp_dir.path().to_path_buf()),
+        ExporterOptions::default(),
+        Duration::from_secs(3600),
+    );
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap().to_string();
+    thread::spawn(move || serve(listener, cache));
+
+    let response = get(&address, "/metrics");
+    assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{}", response);
+    assert!(response.contains("Content-Type: text/plain; version=0.0.4"));
+    assert!(
+        response.contains(
+            "cgtop_memory_current_bytes{cgroup=\"/job.scope\",unit=\"job.scope\"} 4096\n"
+        )
+    );
+
+    // Within the cache lifetime a scrape does not read cgroupfs again
+    fs::write(cgroup.join("memory.current"), "8192\n").unwrap();
+    let response = get(&address, "/metrics?x=1");
+    assert!(
+        response.contains("unit=\"job.scope\"} 4096\n"),
+        "{}",
+        response
+    );
+
+    assert!(get(&address, "/other").starts_with("HTTP/1.1 404 Not Found\r\n"));
+}
+
+#[test]
+fn test_serve_is_not_stalled_by_a_silent_client() {
+    let temp_dir = TempDir::new().unwrap();
+    let cache = MetricsCache::new(
+        CGroupCollector::new(temp_dir.path().to_path_buf()),
+        ExporterOptions::default(),
+        Duration::from_secs(3600),
+    );
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap().to_string();
+    thread::spawn(move || serve(listener, cache));
+
+    let mut silent = TcpStream::connect(&address).unwrap();
+    silent.write_all(b"GET /metr").unwrap();
+
+    let started = Instant::now();
+    assert!(get(&address, "/").starts_with("HTTP/1.1 200 OK\r\n"));
+    assert!(started.elapsed() < Duration::from_secs(2));
+
+    let mut long = TcpStream::connect(&address).unwrap();
+    let request = format!("GET /{} HTTP/1.1\r\n\r\n", "a".repeat(8192));
+    long.write_all(&request.as_bytes()[..8192]).unwrap();
+    let mut response = String::new();
+    long.read_to_string(&mut response).unwrap();
+    assert!(
+        response.starts_with("HTTP/1.1 400 Bad Request\r\n"),
+        "{}",
+        response
+    );
+}
+
+#[test]
+fn test_write_textfile_replaces_the_file_without_leftovers() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(temp_dir.path().join(TEXTFILE_NAME), "old\n").unwrap();
+
+    let target = write_textfile(temp_dir.path(), "cgtop_frozen{cgroup=\"/\"} 0\n").unwrap();
+
+    assert_eq!(target, temp_dir.path().join("cgtop.prom"));
+    assert_eq!(
+        fs::read_to_string(&target).unwrap(),
+        "cgtop_frozen{cgroup=\"/\"} 0\n"
+    );
+    let files: Vec<_> = fs::read_dir(temp_dir.path())
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name())
+        .collect();
+    assert_eq!(files, ["cgtop.prom"]);
+
+    assert!(write_textfile(&temp_dir.path().join("missing"), "").is_err());
+}
+
+#[test]
+fn test_run_textfile_uses_the_exporter_metrics() {
+    let root = TempDir::new().unwrap();
+    fs::create_dir(root.path().join("job.scope")).unwrap();
+    fs::write(root.path().join("job.scope/memory.current"), "4096\n").unwrap();
+    let out_dir = TempDir::new().unwrap();
+    let collector = CGroupCollector::new(root.path().to_path_buf());
+    let options = ExporterOptions::default();
+
+    run_textfile(
+        &collector,
+        &options,
+        out_dir.path(),
+        Duration::from_millis(1),
+        Some(2),
+    )
+    .unwrap();
+
+    let content = fs::read_to_string(out_dir.path().join(TEXTFILE_NAME)).unwrap();
+    let expected = render(&collector.collect_metrics().unwrap(), root.path(), &options);
+    assert_eq!(content, expected);
+    assert!(
+        content.contains(
+            "cgtop_memory_current_bytes{cgroup=\"/job.scope\",unit=\"job.scope\"} 4096\n"
+        )
+    );
+}
+
+#[test]
+fn test_run_textfile_keeps_going_after_a_failed_write() {
+    let root = TempDir::new().unwrap();
+    let out_dir = TempDir::new().unwrap();
+    let dir = out_dir.path().join("textfile");
+    let collector = CGroupCollector::new(root.path().to_path_buf());
+    let options = ExporterOptions::default();
+
+    // The first write has to succeed
+    assert!(run_textfile(&collector, &options, &dir, Duration::ZERO, Some(3)).is_err());
+
+    // Once running, a directory that disappears is retried rather than fatal
+    fs::create_dir(&dir).unwrap();
+    let watched = dir.clone();
+    let remover = thread::spawn(move || {
+        while !watched.join(TEXTFILE_NAME).exists() {
+            thread::sleep(Duration::from_millis(1));
+        }
+        fs::remove_dir_all(&watched).unwrap();
+    });
+    run_textfile(
+        &collector,
+        &options,
+        &dir,
+        Duration::from_millis(100),
+        Some(3),
+    )
+    .unwrap();
+    remover.join().unwrap();
+    assert!(!dir.exists());
+}
@@ -15,7 +15,7 @@ fn arb_cgroup_paths() -> impl Strategy<Value = HashMap<String, cgtop::collection
     prop::collection::vec(
         (
             arb_cgroup_path(),
-            prop::just(common::create_mock_resource_stats()),
+            Just(common::create_mock_resource_stats()),
         ),
         1..20,
     )
@@ -31,7 +31,7 @@ proptest! {
         tree_state.build_from_paths(&paths);
 
         // Basic invariants should hold
-        assert!(tree_state.nodes.len() > 0); // Should at least have root
+        assert!(!tree_state.nodes.is_empty()); // Should at least have root
         assert!(tree_state.nodes.contains_key("")); // Root should exist
 
         // All visible nodes should exist in the tree
@@ -69,7 +69,7 @@ proptest! {
     #[test]
     fn test_expansion_invariants(
         paths in arb_cgroup_paths(),
-        expand_ops in prop::collection::vec(prop::string::string_regex("[a-z][a-z0-9_-/]*").unwrap(), 0..20)
+        expand_ops in prop::collection::vec(prop::string::string_regex("[a-z][a-z0-9_/-]*").unwrap(), 0..20)
     ) {
         let mut tree_state = CGroupTreeState::default();
         tree_state.build_from_paths(&paths);
@@ -95,7 +95,7 @@ proptest! {
     fn test_state_persistence_invariants(
         initial_paths in arb_cgroup_paths(),
         updated_paths in arb_cgroup_paths(),
-        expand_ops in prop::collection::vec(prop::string::string_regex("[a-z][a-z0-9_-/]*").unwrap(), 0..5)
+        expand_ops in prop::collection::vec(prop::string::string_regex("[a-z][a-z0-9_/-]*").unwrap(), 0..5)
     ) {
         let mut tree_state = CGroupTreeState::default();
 
@@ -107,14 +107,11 @@ proptest! {
             tree_state.toggle_expand(&path);
         }
 
-        let expanded_before = tree_state.expanded_nodes.clone();
-        let selected_before = tree_state.selected.clone();
-
         // Update with new paths
         tree_state.build_from_paths(&updated_paths);
 
         // Invariants after update
-        assert!(tree_state.nodes.len() > 0); // Should have nodes
+        assert!(!tree_state.nodes.is_empty()); // Should have nodes
         assert!(tree_state.nodes.contains_key("")); // Root should exist
 
         // All visible nodes should exist
@@ -176,7 +173,7 @@ proptest! {
     #[test]
     fn test_visible_nodes_consistency(
         paths in arb_cgroup_paths(),
-        random_expansions in prop::collection::vec(prop::string::string_regex("[a-z][a-z0-9_-/]*").unwrap(), 0..10)
+        random_expansions in prop::collection::vec(prop::string::string_regex("[a-z][a-z0-9_/-]*").unwrap(), 0..10)
     ) {
         let mut tree_state = CGroupTreeState::default();
         tree_state.build_from_paths(&paths);
@@ -207,11 +204,10 @@ proptest! {
                 current_path.push_str(part);
 
                 // All intermediate paths should exist and be expanded (except the last one)
-                if i < path_parts.len() - 1 {
-                    if let Some(parent_node) = tree_state.nodes.get(&current_path) {
+                if i < path_parts.len() - 1
+                    && let Some(parent_node) = tree_state.nodes.get(&current_path) {
                         assert!(parent_node.expanded, "Parent {} of visible node {} should be expanded", current_path, visible_path);
                     }
-                }
             }
         }
     }
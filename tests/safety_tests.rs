@@ -0,0 +1,285 @@
+mod common;
+
+use cgtop::config::{FileConfig, resolve_read_only};
+use cgtop::utils::{SafetyPolicy, UnsafeReason, mount_for_path, parse_mountinfo};
+use common::{app_for, press, type_text};
+use crossterm::event::KeyCode;
+use pretty_assertions::assert_eq;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+const MOUNTINFO: &str = "\
+22 1 8:1 / / rw,relatime shared:1 - ext4 /dev/sda1 rw
+25 22 0:21 / /sys rw,nosuid shared:7 - sysfs sysfs rw
+26 25 0:22 / /sys/fs/cgroup rw,nosuid shared:8 - cgroup2 cgroup2 rw,nsdelegate
+40 22 0:30 / /mnt/my\\040cgroups rw,relatime - cgroup2 none rw
+41 22 0:31 / /legacy rw,relatime - tmpfs tmpfs rw
+";
+
+fn policy(root: &str) -> SafetyPolicy {
+    let mut policy = SafetyPolicy::new(PathBuf::from(root));
+    // Tests cannot rely on the sandbox's mounts or cgroup
+    policy.require_cgroup2 = false;
+    policy.self_cgroup = None;
+    policy
+}
+
+#[test]
+fn test_mountinfo_parsing_finds_deepest_mount() {
+    let mounts = parse_mountinfo(MOUNTINFO);
+    assert_eq!(mounts.len(), 5);
+    assert_eq!(mounts[3].mount_point, PathBuf::from("/mnt/my cgroups"));
+
+    let mount = mount_for_path(Path::new("/sys/fs/cgroup/user.slice"), MOUNTINFO).unwrap();
+    assert_eq!(mount.mount_point, PathBuf::from("/sys/fs/cgroup"));
+    assert_eq!(mount.fstype, "cgroup2");
+
+    let mount = mount_for_path(Path::new("/sys/fs/cgroupfoo"), MOUNTINFO).unwrap();
+    assert_eq!(mount.fstype, "sysfs");
+}
+
+#[test]
+fn test_root_must_be_on_cgroup2() {
+    assert_eq!(policy("/sys/fs/cgroup").verify_mount(MOUNTINFO), Ok(()));
+    assert_eq!(policy("/mnt/my cgroups/a").verify_mount(MOUNTINFO), Ok(()));
+    assert_eq!(
+        policy("/legacy").verify_mount(MOUNTINFO),
+        Err(UnsafeReason::NotCgroup2 {
+            fstype: Some("tmpfs".to_string())
+        })
+    );
+    assert_eq!(
+        policy("/legacy").verify_mount(""),
+        Err(UnsafeReason::NotCgroup2 { fstype: None })
+    );
+}
+
+#[test]
+fn test_removal_is_limited_to_the_configured_root() {
+    let policy = policy("/srv/cgroups");
+
+    assert_eq!(policy.check_removal("/srv/cgroups/test.slice"), Ok(()));
+    assert_eq!(
+        policy.check_removal("/srv/cgroups"),
+        Err(UnsafeReason::IsRoot)
+    );
+    assert_eq!(
+        policy.check_removal("/sys/fs/cgroup/test.slice"),
+        Err(UnsafeReason::OutsideRoot)
+    );
+    assert_eq!(
+        policy.check_removal("/srv/cgroups-other/test.slice"),
+        Err(UnsafeReason::OutsideRoot)
+    );
+    assert_eq!(
+        policy.check_removal("/srv/cgroups/a/../../etc"),
+        Err(UnsafeReason::OutsideRoot)
+    );
+}
+
+#[test]
+fn test_denylist_and_allowlist() {
+    let mut policy = policy("/sys/fs/cgroup");
+
+    assert_eq!(
+        policy.check_removal("/sys/fs/cgroup/system.slice"),
+        Err(UnsafeReason::Denied("system.slice".to_string()))
+    );
+    // Everything below a listed cgroup is protected as well
+    for path in [
+        "/sys/fs/cgroup/system.slice/sshd.service",
+        "/sys/fs/cgroup/system.slice/dbus.service/child",
+    ] {
+        assert_eq!(
+            policy.check_removal(path),
+            Err(UnsafeReason::Denied("system.slice".to_string())),
+            "{}",
+            path
+        );
+    }
+    assert_eq!(
+        policy.check_removal("/sys/fs/cgroup/user.slice/user-1000.slice/session-2.scope"),
+        Err(UnsafeReason::Denied("user.slice".to_string()))
+    );
+    assert_eq!(
+        policy.check_removal("/sys/fs/cgroup/init.scope/x"),
+        Err(UnsafeReason::Denied("init.scope".to_string()))
+    );
+    assert_eq!(
+        policy.check_removal("/sys/fs/cgroup/lab.slice/job.scope"),
+        Ok(())
+    );
+
+    policy.set_denylist(&["system.slice/*.service"]).unwrap();
+    policy
+        .set_allowlist(&["/machine.slice/", "system.slice"])
+        .unwrap();
+    assert_eq!(
+        policy.check_removal("/sys/fs/cgroup/system.slice/foo.service"),
+        Err(UnsafeReason::Denied("system.slice/*.service".to_string()))
+    );
+    assert_eq!(
+        policy.check_removal("/sys/fs/cgroup/system.slice/foo.service/worker"),
+        Err(UnsafeReason::Denied("system.slice/*.service".to_string()))
+    );
+    assert_eq!(
+        policy.check_removal("/sys/fs/cgroup/machine.slice/vm-1.scope/vcpu0"),
+        Ok(())
+    );
+    assert_eq!(
+        policy.check_removal("/sys/fs/cgroup/user.slice"),
+        Err(UnsafeReason::NotAllowed)
+    );
+
+    assert!(policy.set_allowlist(&["[unclosed"]).is_err());
+}
+
+#[test]
+fn test_freeze_and_kill_honour_the_denylist_subtree() {
+    let mut policy = policy("/sys/fs/cgroup");
+    policy.self_cgroup = Some(PathBuf::from("/sys/fs/cgroup/lab.slice/cgtop.scope"));
+    policy.set_allowlist(&["machine.slice"]).unwrap();
+
+    assert_eq!(
+        policy.check_process_control("/sys/fs/cgroup/system.slice/sshd.service"),
+        Err(UnsafeReason::Denied("system.slice".to_string()))
+    );
+    assert_eq!(
+        policy.check_process_control("/sys/fs/cgroup/init.scope"),
+        Err(UnsafeReason::Denied("init.scope".to_string()))
+    );
+    assert_eq!(
+        policy.check_process_control("/sys/fs/cgroup"),
+        Err(UnsafeReason::IsRoot)
+    );
+    assert_eq!(
+        policy.check_process_control("/sys/fs/cgroup/lab.slice"),
+        Err(UnsafeReason::ContainsSelf)
+    );
+    // The allowlist only limits removal
+    assert_eq!(
+        policy.check_process_control("/sys/fs/cgroup/lab.slice/job.scope"),
+        Ok(())
+    );
+}
+
+#[test]
+fn test_parents_of_denylisted_cgroups_are_protected() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    fs::create_dir_all(root.join("machine.slice/db.scope/inner")).unwrap();
+    fs::create_dir_all(root.join("machine.slice/web.scope")).unwrap();
+    let mut policy = policy(root.to_str().unwrap());
+    policy.set_denylist(&["machine.slice/db.scope"]).unwrap();
+
+    let denied = Err(UnsafeReason::Denied("machine.slice/db.scope".to_string()));
+    for cgroup in ["machine.slice", "machine.slice/db.scope/inner"] {
+        assert_eq!(policy.check_removal(root.join(cgroup)), denied);
+        assert_eq!(policy.check_process_control(root.join(cgroup)), denied);
+    }
+    assert_eq!(
+        policy.check_removal(root.join("machine.slice/web.scope")),
+        Ok(())
+    );
+    assert_eq!(
+        policy.check_process_control(root.join("machine.slice/web.scope")),
+        Ok(())
+    );
+}
+
+#[test]
+fn test_own_cgroup_and_its_ancestors_are_protected() {
+    let mut policy = policy("/sys/fs/cgroup");
+    policy.self_cgroup = Some(PathBuf::from("/sys/fs/cgroup/user.slice/session-2.scope"));
+    policy.set_denylist::<&str>(&[]).unwrap();
+
+    assert_eq!(
+        policy.check_removal("/sys/fs/cgroup/user.slice"),
+        Err(UnsafeReason::ContainsSelf)
+    );
+    assert_eq!(
+        policy.check_removal("/sys/fs/cgroup/user.slice/session-2.scope"),
+        Err(UnsafeReason::ContainsSelf)
+    );
+    assert_eq!(
+        policy.check_removal("/sys/fs/cgroup/user.slice/session-3.scope"),
+        Ok(())
+    );
+}
+
+#[test]
+fn test_safety_config_is_applied() {
+    let config = FileConfig::parse(
+        "[safety]\ndenylist = [\"keep.slice\"]\nallowlist = [\"test.slice\"]\nrequire_cgroup2_mount = false\n",
+    )
+    .unwrap();
+    let mut policy = SafetyPolicy::new(PathBuf::from("/sys/fs/cgroup"));
+    config.safety.apply_to(&mut policy).unwrap();
+    policy.self_cgroup = None;
+
+    assert!(!policy.require_cgroup2);
+    assert_eq!(
+        policy.check_removal("/sys/fs/cgroup/system.slice"),
+        Err(UnsafeReason::NotAllowed)
+    );
+    assert_eq!(policy.check_removal("/sys/fs/cgroup/test.slice/x"), Ok(()));
+
+    assert!(FileConfig::parse("[safety]\nallow = []\n").is_err());
+}
+
+#[test]
+fn test_read_only_resolution() {
+    let temp_dir = TempDir::new().unwrap();
+    let writable = temp_dir.path();
+    let missing = Path::new("/nonexistent/cgroup");
+
+    assert!(!resolve_read_only(false, None, writable));
+    assert!(resolve_read_only(false, None, missing));
+    assert!(resolve_read_only(true, Some(false), writable));
+    assert!(resolve_read_only(false, Some(true), writable));
+    // An explicit "read_only = false" wins over the access check
+    assert!(!resolve_read_only(false, Some(false), missing));
+
+    let config = FileConfig::parse("read_only = true\n").unwrap();
+    assert_eq!(config.read_only, Some(true));
+}
+
+#[test]
+fn test_read_only_refuses_mutating_actions() {
+    let temp_dir = TempDir::new().unwrap();
+    let cgroup = temp_dir.path().join("lab.slice");
+    fs::create_dir(&cgroup).unwrap();
+    fs::write(cgroup.join("cgroup.freeze"), "0\n").unwrap();
+    fs::write(cgroup.join("cgroup.procs"), "1\n").unwrap();
+    fs::write(cgroup.join("memory.max"), "max\n").unwrap();
+
+    let mut app = app_for(temp_dir.path());
+    app.ui_state.selected_cgroup = Some(cgroup.display().to_string());
+
+    // A dialog opened before switching to read-only cannot write either
+    app.request_new_child();
+    app.config.read_only = true;
+    type_text(&mut app, "scratch");
+    press(&mut app, KeyCode::Enter);
+    assert!(!cgroup.join("scratch").exists());
+    app.ui_state.dialog = None;
+
+    app.request_delete("Delete cgroup".to_string(), &cgroup);
+    app.request_kill();
+    app.open_memory_editor();
+    app.open_subtree_control_editor();
+    app.open_signal_menu();
+    app.request_move_all();
+    app.request_shell();
+    assert!(app.ui_state.dialog.is_none());
+    assert!(app.ui_state.pending_shell.is_none());
+
+    app.toggle_freeze();
+    assert_eq!(
+        fs::read_to_string(cgroup.join("cgroup.freeze")).unwrap(),
+        "0\n"
+    );
+    assert!(cgroup.exists());
+    assert!(app.audit.entries().is_empty());
+}
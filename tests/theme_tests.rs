@@ -0,0 +1,76 @@
+use cgtop::config::FileConfig;
+use cgtop::theme::{ColorSupport, Theme, ThemeName};
+use pretty_assertions::assert_eq;
+use ratatui::style::{Color, Modifier};
+
+#[test]
+fn test_color_support_detection() {
+    assert_eq!(
+        ColorSupport::from_env(true, Some("xterm-256color"), Some("truecolor")),
+        ColorSupport::None
+    );
+    assert_eq!(
+        ColorSupport::from_env(false, Some("dumb"), None),
+        ColorSupport::None
+    );
+    assert_eq!(
+        ColorSupport::from_env(false, None, None),
+        ColorSupport::None
+    );
+    assert_eq!(
+        ColorSupport::from_env(false, Some("xterm"), None),
+        ColorSupport::Ansi16
+    );
+    assert_eq!(
+        ColorSupport::from_env(false, Some("screen-256color"), None),
+        ColorSupport::Ansi256
+    );
+    assert_eq!(
+        ColorSupport::from_env(false, Some("xterm"), Some("24bit")),
+        ColorSupport::TrueColor
+    );
+}
+
+#[test]
+fn test_colors_are_downgraded_for_the_terminal() {
+    let orange = Color::Rgb(0xff, 0x87, 0x00);
+
+    assert_eq!(ColorSupport::TrueColor.adapt(orange), orange);
+    assert_eq!(ColorSupport::Ansi256.adapt(orange), Color::Indexed(208));
+    assert_eq!(
+        ColorSupport::Ansi256.adapt(Color::Rgb(0, 0, 0)),
+        Color::Indexed(16)
+    );
+    assert_eq!(
+        ColorSupport::Ansi16.adapt(Color::Rgb(250, 5, 5)),
+        Color::LightRed
+    );
+    assert_eq!(ColorSupport::Ansi16.adapt(Color::Indexed(21)), Color::Blue);
+    assert_eq!(ColorSupport::Ansi16.adapt(Color::Cyan), Color::Cyan);
+    assert_eq!(ColorSupport::None.adapt(Color::Cyan), Color::Reset);
+}
+
+#[test]
+fn test_no_color_terminal_gets_monochrome_theme() {
+    let theme = Theme::for_terminal(ThemeName::Light, ColorSupport::None);
+
+    assert_eq!(theme.name, ThemeName::Monochrome);
+    assert_eq!(theme.primary, Color::Reset);
+    assert!(theme.selected.add_modifier.contains(Modifier::REVERSED));
+
+    // Light theme colours never reach a 16-colour terminal as RGB
+    let theme = Theme::for_terminal(ThemeName::Light, ColorSupport::Ansi16);
+    assert!(!matches!(theme.text, Color::Rgb(..)));
+    assert!(!matches!(theme.selected.bg, Some(Color::Rgb(..))));
+}
+
+#[test]
+fn test_config_file_parsing() {
+    let config = FileConfig::parse("theme = \"high-contrast\"\ninterval_ms = 2000\n").unwrap();
+    assert_eq!(config.theme, Some(ThemeName::HighContrast));
+    assert_eq!(config.interval_ms, Some(2000));
+
+    assert_eq!(FileConfig::parse("").unwrap(), FileConfig::default());
+    assert!(FileConfig::parse("theme = \"solarized\"").is_err());
+    assert!(FileConfig::parse("colour = \"dark\"").is_err());
+}
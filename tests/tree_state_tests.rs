@@ -120,10 +120,10 @@ fn test_state_persistence_across_updates() {
     assert_eq!(tree_state.expanded_nodes, expanded_state_before);
 
     // Selection should be preserved if the node still exists
-    if let Some(ref sel) = selection_before {
-        if tree_state.nodes.contains_key(sel) {
-            assert_eq!(tree_state.selected, selection_before);
-        }
+    if let Some(ref sel) = selection_before
+        && tree_state.nodes.contains_key(sel)
+    {
+        assert_eq!(tree_state.selected, selection_before);
     }
 
     // New node should exist
@@ -226,3 +226,15 @@ fn test_edge_cases() {
     tree_state.toggle_expand("nonexistent");
     assert!(tree_state.expanded_nodes.is_empty());
 }
+
+#[test]
+fn test_node_at_row_accounts_for_scroll() {
+    let mut tree_state = CGroupTreeState::default();
+    tree_state.build_from_paths(&create_test_cgroup_paths());
+
+    assert_eq!(tree_state.node_at_row(0), tree_state.visible_nodes.first());
+
+    tree_state.scroll_offset = 2;
+    assert_eq!(tree_state.node_at_row(0), tree_state.visible_nodes.get(2));
+    assert_eq!(tree_state.node_at_row(tree_state.visible_nodes.len()), None);
+}